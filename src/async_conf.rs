@@ -0,0 +1,449 @@
+use crate::{
+    AnyConfEntry, Conf, ConfBool, ConfDuration, ConfEntry, ConfError, ConfList, ConfMap, ConfPath,
+    ConfSource, ConfValue, FiniteFloat, Result, Secret,
+};
+use futures_util::future::join_all;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::hash::Hash;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// An async counterpart to [`ConfSource`], for sources backed by a network service. Implement
+/// it with an `async fn get`. Sync sources such as [`EnvSource`](crate::EnvSource) can be used
+/// by wrapping them in [`SyncSource`].
+pub trait AsyncConfSource: Sync {
+    /// Look up a value and return it in serialized string form. Return `None` if not present;
+    /// default values are handled in [`AsyncConf::get`].
+    fn get(&self, key: &str) -> impl Future<Output = Result<Option<String>>> + Send;
+}
+
+/// Adapts a sync [`ConfSource`] into an [`AsyncConfSource`] whose lookups complete immediately,
+/// so it can be used with [`AsyncConf`]. Lookups run on the calling task, so wrap only sources
+/// that don't block for long, such as [`EnvSource`](crate::EnvSource).
+pub struct SyncSource<S>(pub S);
+
+impl<S: ConfSource + Sync> AsyncConfSource for SyncSource<S> {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        self.0.get(key)
+    }
+}
+
+/// A config read from an [`AsyncConfSource`], for use from async code without blocking. This
+/// is a separate type rather than a mode of [`Conf`], so the sync path is unaffected.
+///
+/// Entries are usually defined with the typed builders on [`Conf`], then moved over with
+/// [`AsyncConf::from_conf`]. [Interpolation](Conf::with_interpolation) is not supported.
+///
+/// ```
+/// use voidconf::{AsyncConf, AsyncConfSource, Conf, ConfError};
+///
+/// struct RemoteSource;
+///
+/// impl AsyncConfSource for RemoteSource {
+///     async fn get(&self, key: &str) -> Result<Option<String>, ConfError> {
+///         // e.g. an HTTP request with an async client
+///         Ok((key == "port").then(|| "8080".to_string()))
+///     }
+/// }
+///
+/// # async fn run() -> Result<u64, ConfError> {
+/// let conf = AsyncConf::from_conf(Conf::default().uint("port", Some(80)), RemoteSource);
+/// conf.require::<u64>("port").await
+/// # }
+/// ```
+pub struct AsyncConf<S: AsyncConfSource> {
+    /// Config name.
+    pub name: &'static str,
+    /// Source for config values. See [`AsyncConfSource`].
+    pub source: S,
+    /// Map of configured [`ConfEntry`] options.
+    pub options: BTreeMap<String, Box<dyn AnyConfEntry>>,
+}
+
+impl<S: AsyncConfSource> AsyncConf<S> {
+    /// Create a new config with an already initialized [`AsyncConfSource`].
+    pub fn with_source(name: &'static str, source: S) -> Self {
+        Self {
+            name,
+            source,
+            options: BTreeMap::new(),
+        }
+    }
+
+    /// Create a new config with the entries of a [`Conf`], reading from `source` instead.
+    pub fn from_conf<T: ConfSource>(conf: Conf<T>, source: S) -> Self {
+        Self {
+            name: conf.name,
+            source,
+            options: conf.options,
+        }
+    }
+
+    /// Add a new [`ConfEntry`]. See [`Conf::entry`].
+    pub fn entry<V: ConfValue + Send + Sync + 'static>(mut self, entry: ConfEntry<V>) -> Self {
+        for alias in &entry.aliases {
+            self.options.insert(alias.clone(), Box::new(entry.clone()));
+        }
+        self.options.insert(entry.name.clone(), Box::new(entry));
+        self
+    }
+
+    /// Look up an entry's name in the source, then each [alias](ConfEntry::alias).
+    async fn lookup(&self, option: &dyn AnyConfEntry) -> Result<Option<String>> {
+        if let Some(v) = self.source.get(option.name()).await? {
+            return Ok(Some(v));
+        }
+        for alias in option.aliases() {
+            if let Some(v) = self.source.get(alias).await? {
+                crate::warn_alias(alias, option.name());
+                return Ok(Some(v));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get a value, falling back to the entry default. See [`Conf::get`].
+    pub async fn get<V: ConfValue + 'static>(&self, key: &str) -> Result<Option<V>> {
+        let option = self
+            .options
+            .get(key)
+            .ok_or_else(|| ConfError::key_not_found(key))?;
+        let Some(entry) = option.as_any().downcast_ref::<ConfEntry<V>>() else {
+            return Err(ConfError::val_parse_failed(
+                key,
+                "",
+                format!("entry is not of type {}", std::any::type_name::<V>()),
+            ));
+        };
+        let raw = entry.or_default(self.lookup(&**option).await)?;
+        raw.map(|v| entry.parse(v)).transpose()
+    }
+
+    /// Get a string value.
+    pub async fn get_string(&self, key: &str) -> Result<Option<String>> {
+        self.get::<String>(key).await
+    }
+
+    /// Get a secret string value.
+    pub async fn get_secret(&self, key: &str) -> Result<Option<Secret<String>>> {
+        self.get::<Secret<String>>(key).await
+    }
+
+    /// Get a byte (`u8`) value.
+    pub async fn get_byte(&self, key: &str) -> Result<Option<u8>> {
+        self.get::<u8>(key).await
+    }
+
+    /// Get an int (`i64`) value.
+    pub async fn get_int(&self, key: &str) -> Result<Option<i64>> {
+        self.get::<i64>(key).await
+    }
+
+    /// Get a uint (`u64`) value.
+    pub async fn get_uint(&self, key: &str) -> Result<Option<u64>> {
+        self.get::<u64>(key).await
+    }
+
+    /// Get a big int (`i128`) value.
+    pub async fn get_big_int(&self, key: &str) -> Result<Option<i128>> {
+        self.get::<i128>(key).await
+    }
+
+    /// Get a big uint (`u128`) value.
+    pub async fn get_big_uint(&self, key: &str) -> Result<Option<u128>> {
+        self.get::<u128>(key).await
+    }
+
+    /// Get a size (`usize`) value.
+    pub async fn get_size(&self, key: &str) -> Result<Option<usize>> {
+        self.get::<usize>(key).await
+    }
+
+    /// Get a float (`f64`) value.
+    pub async fn get_float(&self, key: &str) -> Result<Option<f64>> {
+        self.get::<f64>(key).await
+    }
+
+    /// Get a finite float (`f64`) value added with [`Conf::finite_float`].
+    pub async fn get_finite_float(&self, key: &str) -> Result<Option<f64>> {
+        self.get::<FiniteFloat>(key).await.map(|v| v.map(f64::from))
+    }
+
+    /// Get a bool value.
+    pub async fn get_bool(&self, key: &str) -> Result<Option<bool>> {
+        self.get::<ConfBool>(key).await.map(|v| v.map(bool::from))
+    }
+
+    /// Get a path value as configured.
+    pub async fn get_path(&self, key: &str) -> Result<Option<PathBuf>> {
+        self.get::<ConfPath>(key)
+            .await
+            .map(|v| v.map(PathBuf::from))
+    }
+
+    /// Get a path value with a leading `~` expanded and relative paths resolved against the
+    /// current working directory, see [`ConfPath::normalize`].
+    pub async fn get_path_canonical(&self, key: &str) -> Result<Option<PathBuf>> {
+        self.get::<ConfPath>(key)
+            .await?
+            .map(|path| {
+                path.normalize()
+                    .map_err(|e| ConfError::val_parse_failed(key, path.to_string(), e.to_string()))
+            })
+            .transpose()
+    }
+
+    /// Get a socket address value.
+    pub async fn get_socket_addr(&self, key: &str) -> Result<Option<SocketAddr>> {
+        self.get::<SocketAddr>(key).await
+    }
+
+    /// Get a duration value.
+    pub async fn get_duration(&self, key: &str) -> Result<Option<Duration>> {
+        self.get::<ConfDuration>(key)
+            .await
+            .map(|v| v.map(Duration::from))
+    }
+
+    /// Get a comma separated list value. An empty value is an empty list, not `None`.
+    pub async fn get_list<V: ConfValue + 'static>(&self, key: &str) -> Result<Option<Vec<V>>> {
+        self.get::<ConfList<V>>(key).await.map(|v| v.map(Vec::from))
+    }
+
+    /// Get a map value of comma separated `key=value` pairs. An empty value is an empty map, not
+    /// `None`.
+    pub async fn get_map<K, V>(&self, key: &str) -> Result<Option<HashMap<K, V>>>
+    where
+        K: ConfValue + Eq + Hash + 'static,
+        V: ConfValue + 'static,
+    {
+        self.get::<ConfMap<K, V>>(key)
+            .await
+            .map(|v| v.map(HashMap::from))
+    }
+
+    /// Require a string value.
+    pub async fn require_string(&self, key: &str) -> Result<String> {
+        self.require::<String>(key).await
+    }
+
+    /// Require a secret string value.
+    pub async fn require_secret(&self, key: &str) -> Result<Secret<String>> {
+        self.require::<Secret<String>>(key).await
+    }
+
+    /// Require a byte (`u8`) value.
+    pub async fn require_byte(&self, key: &str) -> Result<u8> {
+        self.require::<u8>(key).await
+    }
+
+    /// Require an int (`i64`) value.
+    pub async fn require_int(&self, key: &str) -> Result<i64> {
+        self.require::<i64>(key).await
+    }
+
+    /// Require a uint (`u64`) value.
+    pub async fn require_uint(&self, key: &str) -> Result<u64> {
+        self.require::<u64>(key).await
+    }
+
+    /// Require a big int (`i128`) value.
+    pub async fn require_big_int(&self, key: &str) -> Result<i128> {
+        self.require::<i128>(key).await
+    }
+
+    /// Require a big uint (`u128`) value.
+    pub async fn require_big_uint(&self, key: &str) -> Result<u128> {
+        self.require::<u128>(key).await
+    }
+
+    /// Require a size (`usize`) value.
+    pub async fn require_size(&self, key: &str) -> Result<usize> {
+        self.require::<usize>(key).await
+    }
+
+    /// Require a float (`f64`) value.
+    pub async fn require_float(&self, key: &str) -> Result<f64> {
+        self.require::<f64>(key).await
+    }
+
+    /// Require a finite float (`f64`) value added with [`Conf::finite_float`].
+    pub async fn require_finite_float(&self, key: &str) -> Result<f64> {
+        self.require::<FiniteFloat>(key).await.map(f64::from)
+    }
+
+    /// Require a bool value.
+    pub async fn require_bool(&self, key: &str) -> Result<bool> {
+        self.require::<ConfBool>(key).await.map(bool::from)
+    }
+
+    /// Require a path value as configured.
+    pub async fn require_path(&self, key: &str) -> Result<PathBuf> {
+        self.require::<ConfPath>(key).await.map(PathBuf::from)
+    }
+
+    /// Require a socket address value.
+    pub async fn require_socket_addr(&self, key: &str) -> Result<SocketAddr> {
+        self.require::<SocketAddr>(key).await
+    }
+
+    /// Require a duration value.
+    pub async fn require_duration(&self, key: &str) -> Result<Duration> {
+        self.require::<ConfDuration>(key).await.map(Duration::from)
+    }
+
+    /// Require a comma separated list value.
+    pub async fn require_list<V: ConfValue + 'static>(&self, key: &str) -> Result<Vec<V>> {
+        self.require::<ConfList<V>>(key).await.map(Vec::from)
+    }
+
+    /// Require a map value of comma separated `key=value` pairs.
+    pub async fn require_map<K, V>(&self, key: &str) -> Result<HashMap<K, V>>
+    where
+        K: ConfValue + Eq + Hash + 'static,
+        V: ConfValue + 'static,
+    {
+        self.require::<ConfMap<K, V>>(key).await.map(HashMap::from)
+    }
+
+    /// Look up an entry's raw value, falling back to its default, and check that it parses.
+    async fn present(&self, option: &dyn AnyConfEntry) -> Result<bool> {
+        match option.apply_default(self.lookup(option).await)? {
+            Some(raw) => option.check(raw).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Check that every [required](ConfEntry::required) entry resolves to a value. Lookups run
+    /// concurrently. See [`Conf::validate`].
+    pub async fn validate(&self) -> Result {
+        let required = self
+            .options
+            .iter()
+            .filter(|(key, o)| o.name() == key.as_str() && o.is_required());
+        let checks = required.map(|(key, o)| async move { (key, self.present(&**o).await) });
+        let mut missing = Vec::new();
+        for (key, present) in join_all(checks).await {
+            if !present? {
+                missing.push(key.clone());
+            }
+        }
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfError::validation_failed(missing))
+        }
+    }
+
+    /// Check that each of the given keys resolves to a value. Lookups run concurrently. See
+    /// [`Conf::require_all`].
+    pub async fn require_all(&self, keys: &[&str]) -> Result {
+        let mut options = Vec::new();
+        for key in keys {
+            let option = self
+                .options
+                .get(*key)
+                .ok_or_else(|| ConfError::key_not_found(*key))?;
+            options.push((key, option));
+        }
+        let checks = options
+            .into_iter()
+            .map(|(key, o)| async move { (key, self.present(&**o).await) });
+        let mut missing = Vec::new();
+        for (key, present) in join_all(checks).await {
+            if !present? {
+                missing.push(key.to_string());
+            }
+        }
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfError::validation_failed(missing))
+        }
+    }
+
+    /// Get a value, or fail with [`ConfError::ValNotFound`]. See [`Conf::require`].
+    pub async fn require<V: ConfValue + 'static>(&self, key: &str) -> Result<V> {
+        self.get(key).await.transpose().ok_or_else(|| {
+            let description = self.options.get(key).and_then(|o| o.description());
+            ConfError::val_not_found(key).with_description(description.map(String::from))
+        })?
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::InMemorySource;
+    use std::time::Instant;
+
+    /// Resolves every key but `missing` to the same value after a delay.
+    struct SlowSource(&'static str);
+
+    impl AsyncConfSource for SlowSource {
+        async fn get(&self, key: &str) -> Result<Option<String>> {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Ok((key != "missing").then(|| self.0.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    pub async fn get_async_fixed() {
+        let conf = AsyncConf::from_conf(
+            Conf::default().uint("port", None).string("name", None),
+            SlowSource("8080"),
+        );
+        assert_eq!(conf.get_uint("port").await.unwrap(), Some(8080));
+        assert_eq!(conf.require_string("name").await.unwrap(), "8080");
+        assert!(matches!(
+            conf.get::<u64>("missing").await.unwrap_err(),
+            ConfError::KeyNotFound { .. }
+        ));
+
+        let conf =
+            AsyncConf::with_source("vcfg", SlowSource("port")).entry(ConfEntry::<u64>::new("port"));
+        assert!(matches!(
+            conf.get_uint("port").await.unwrap_err(),
+            ConfError::ValParseFailed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    pub async fn require_all_async_concurrent() {
+        let conf = AsyncConf::from_conf(
+            Conf::default()
+                .uint("a", None)
+                .uint("b", None)
+                .uint("c", None)
+                .uint("d", None)
+                .uint("missing", None)
+                .require_entry(ConfEntry::<u64>::new("e")),
+            SlowSource("1"),
+        );
+        let start = Instant::now();
+        conf.require_all(&["a", "b", "c", "d"]).await.unwrap();
+        conf.validate().await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(350));
+        assert_eq!(
+            conf.require_all(&["a", "missing"]).await.unwrap_err(),
+            ConfError::ValidationFailed {
+                missing: vec!["missing".to_string()]
+            }
+        );
+    }
+
+    #[tokio::test]
+    pub async fn get_async_sync_source() {
+        let conf = AsyncConf::from_conf(
+            Conf::default().uint("port", Some(80)).string("name", None),
+            SyncSource(InMemorySource::new("vcfg").set("VCFG_NAME", "world")),
+        );
+        assert_eq!(conf.get_uint("port").await.unwrap(), Some(80));
+        assert_eq!(
+            conf.get_string("name").await.unwrap(),
+            Some("world".to_string())
+        );
+    }
+}
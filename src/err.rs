@@ -14,14 +14,87 @@ pub enum ConfError {
     #[error]
     #[display("expected val not found with key: {key}")]
     #[diagnostic()]
-    ValNotFound { key: String },
+    ValNotFound {
+        key: String,
+        #[help]
+        description: Option<String>,
+    },
+
+    /// Type-safe value parsing failed. The `reason` is the message from the underlying parse error.
+    #[error]
+    #[display("failed to parse val as given type: {key} = {val}: {reason}")]
+    #[diagnostic()]
+    ValParseFailed {
+        key: String,
+        val: String,
+        reason: String,
+        #[help]
+        description: Option<String>,
+    },
+
+    /// Value parsed but was rejected by the entry's [validator](crate::ConfEntry::validate_with).
+    #[error]
+    #[display("val failed validation: {key}: {reason}")]
+    #[diagnostic()]
+    ValInvalid {
+        key: String,
+        reason: String,
+        #[help]
+        description: Option<String>,
+    },
+
+    /// Value parsed but is outside the entry's [bounds](crate::ConfEntry::min).
+    #[error]
+    #[display(
+        "val out of range: {key} = {value}: expected min {}, max {}",
+        min.as_deref().unwrap_or("none"),
+        max.as_deref().unwrap_or("none")
+    )]
+    #[diagnostic()]
+    OutOfRange {
+        key: String,
+        value: String,
+        min: Option<String>,
+        max: Option<String>,
+        #[help]
+        description: Option<String>,
+    },
+
+    /// Dotted key path passes through a value that isn't a table or object.
+    #[error]
+    #[display("key path traverses a non-table value: {key} at {at}")]
+    #[diagnostic()]
+    KeyPathInvalid { key: String, at: String },
+
+    /// Value interpolation refers back to a key already being resolved.
+    #[error]
+    #[display("interpolation cycle at key: {key}")]
+    #[diagnostic()]
+    InterpolationCycle { key: String },
 
-    //TODO extract data from parse error
-    /// Type-safe value parsing failed.
+    /// The source doesn't support an operation, e.g. [`ConfSource::keys`](crate::ConfSource::keys).
     #[error]
-    #[display("failed to parse val as given type: {key} = {val}")]
+    #[display("operation not supported by source: {operation}")]
     #[diagnostic()]
-    ValParseFailed { key: String, val: String },
+    Unsupported { operation: String },
+
+    /// Both configs define the same key. Returned by [`Conf::try_merge`](crate::Conf::try_merge).
+    #[error]
+    #[display("key defined in both merged configs: {key}")]
+    #[diagnostic()]
+    KeyConflict { key: String },
+
+    /// One or more expected values are not defined. Reported together by [`Conf::validate`](crate::Conf::validate).
+    #[error]
+    #[display("expected vals not found with keys: {}", missing.join(", "))]
+    #[diagnostic()]
+    ValidationFailed { missing: Vec<String> },
+
+    /// Resolved values don't fit the target type. Returned by [`Conf::deserialize`](crate::Conf::deserialize).
+    #[error]
+    #[display("failed to deserialize config: {reason}")]
+    #[diagnostic()]
+    DeserializeFailed { reason: String },
 
     /// Environment variable lookup failed.
     #[error]
@@ -32,6 +105,133 @@ pub enum ConfError {
         #[error(source)]
         source: std::env::VarError,
     },
+
+    /// Key translates to an env var name that isn't portable, e.g. one containing `-`.
+    #[error]
+    #[display("invalid env var name: {key}: {reason}")]
+    #[diagnostic()]
+    EnvKeyInvalid { key: String, reason: String },
+
+    /// Secret file named by a `_FILE` env var could not be read, see
+    /// [`EnvSource::file_indirection`](crate::EnvSource::file_indirection).
+    #[error]
+    #[display("failed to read secret file from env var: {key} = {path}")]
+    #[diagnostic()]
+    SecretFileUnreadable {
+        key: String,
+        path: String,
+        #[error(source)]
+        source: Box<ConfError>,
+    },
+
+    /// Request to a remote config source failed. The `status` is set when the server responded.
+    #[error]
+    #[display("config request failed: {url} ({}): {reason}", status.map_or("no response".to_string(), |s| s.to_string()))]
+    #[diagnostic()]
+    RequestFailed {
+        url: String,
+        status: Option<u16>,
+        reason: String,
+    },
+
+    /// Value from a source is not valid UTF-8.
+    #[error]
+    #[display("val is not valid UTF-8: {key}")]
+    #[diagnostic()]
+    ValNotUtf8 { key: String },
+
+    /// Command run for a value could not be started or exited unsuccessfully.
+    #[error]
+    #[display("config command failed: {key}: `{command}`: {reason}")]
+    #[diagnostic()]
+    CommandFailed {
+        key: String,
+        command: String,
+        reason: String,
+    },
+
+    /// Connection to a remote config source failed, or it replied with a protocol error.
+    #[error]
+    #[display("config source connection failed: {url}: {reason}")]
+    #[diagnostic()]
+    ConnectionFailed { url: String, reason: String },
+
+    /// Remote config source rejected the credentials, e.g. an expired or invalid token.
+    #[error]
+    #[display("config source authentication failed: {url}: {reason}")]
+    #[diagnostic()]
+    AuthFailed { url: String, reason: String },
+
+    /// OS keychain could not be accessed, e.g. because it's locked or has no backend. Apps may
+    /// want to prompt the user to unlock it and retry.
+    #[error]
+    #[display("keychain access failed: {service}/{key}: {reason}")]
+    #[diagnostic()]
+    KeyringFailed {
+        service: String,
+        key: String,
+        reason: String,
+    },
+
+    /// The 1Password CLI isn't installed or isn't signed in. The help says how to fix it.
+    #[error]
+    #[display("1Password CLI unavailable: {reason}")]
+    #[diagnostic()]
+    OnePasswordUnavailable {
+        reason: String,
+        #[help]
+        help: String,
+    },
+
+    /// Encrypted config file could not be decrypted.
+    #[error]
+    #[display("failed to decrypt config file: {path}: {reason}")]
+    #[diagnostic()]
+    DecryptFailed { path: String, reason: String },
+
+    /// Source could not be initialized by [`Conf::try_new`](crate::Conf::try_new).
+    #[error]
+    #[display("failed to initialize config source: {name}")]
+    #[diagnostic()]
+    SourceInit {
+        name: String,
+        #[error(source)]
+        source: Box<ConfError>,
+    },
+
+    /// Source lookup kept failing after retrying. Returned by
+    /// [`RetryingSource`](crate::RetryingSource).
+    #[error]
+    #[display("config source failed after {attempts} attempts")]
+    #[diagnostic()]
+    RetriesExhausted {
+        attempts: u32,
+        #[error(source)]
+        source: Box<ConfError>,
+    },
+
+    /// Config file could not be read or parsed.
+    #[error]
+    #[display("failed to load config file: {path}: {reason}")]
+    #[diagnostic()]
+    FileLoadFailed { path: String, reason: String },
+
+    /// Source failed to read a file or directory.
+    #[error]
+    #[display("failed to read config source: {path}: {reason}")]
+    #[diagnostic()]
+    SourceIo { path: String, reason: String },
+
+    /// Config file has a syntax error.
+    #[error]
+    #[display("failed to parse config file: {path}:{line}:{column}: {reason}")]
+    #[diagnostic()]
+    FileParseFailed {
+        path: String,
+        line: usize,
+        column: usize,
+        reason: String,
+    },
 }
 
 impl ConfError {
@@ -40,13 +240,130 @@ impl ConfError {
     }
 
     pub fn val_not_found(key: impl Into<String>) -> Self {
-        Self::ValNotFound { key: key.into() }
+        Self::ValNotFound {
+            key: key.into(),
+            description: None,
+        }
     }
 
-    pub fn val_parse_failed(key: impl Into<String>, val: impl Into<String>) -> Self {
+    pub fn val_parse_failed(
+        key: impl Into<String>,
+        val: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
         Self::ValParseFailed {
             key: key.into(),
             val: val.into(),
+            reason: reason.into(),
+            description: None,
+        }
+    }
+
+    pub fn val_invalid(key: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::ValInvalid {
+            key: key.into(),
+            reason: reason.into(),
+            description: None,
+        }
+    }
+
+    pub fn out_of_range(
+        key: impl Into<String>,
+        value: impl Into<String>,
+        min: Option<String>,
+        max: Option<String>,
+    ) -> Self {
+        Self::OutOfRange {
+            key: key.into(),
+            value: value.into(),
+            min,
+            max,
+            description: None,
+        }
+    }
+
+    /// Attach an entry's [description](crate::ConfEntry#structfield.description) to errors about
+    /// its value, where it's shown as the diagnostic help. Other errors are returned unchanged.
+    pub fn with_description(mut self, text: Option<String>) -> Self {
+        match &mut self {
+            Self::ValNotFound { description, .. }
+            | Self::ValParseFailed { description, .. }
+            | Self::ValInvalid { description, .. }
+            | Self::OutOfRange { description, .. } => *description = text,
+            _ => {}
+        }
+        self
+    }
+
+    /// Name where a value came from, e.g. `env var VCFG_PORT`, in the reason of a
+    /// [`ConfError::ValParseFailed`]. Other errors are returned unchanged.
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        if let Self::ValParseFailed { reason, .. } = &mut self {
+            *reason = format!("{reason} (from {origin})");
+        }
+        self
+    }
+
+    /// Replace the raw value in errors about an entry's value with [`crate::REDACTED`], for
+    /// [secret](crate::ConfEntry#structfield.secret) entries. The reason of a
+    /// [`ConfError::ValParseFailed`] is replaced too, since parse errors such as
+    /// [`ConfBool`](crate::ConfBool)'s echo their input. Other errors are returned unchanged.
+    pub fn redacted(mut self) -> Self {
+        match &mut self {
+            Self::ValParseFailed { val, reason, .. } => {
+                *val = crate::REDACTED.to_string();
+                *reason = crate::REDACTED.to_string();
+            }
+            Self::OutOfRange { value: val, .. } => *val = crate::REDACTED.to_string(),
+            Self::EnvLookupFailed {
+                source: std::env::VarError::NotUnicode(val),
+                ..
+            } => *val = crate::REDACTED.into(),
+            _ => {}
+        }
+        self
+    }
+
+    /// Whether the error may be temporary, such as a dropped connection or a 5xx or 429
+    /// response, so the lookup is worth retrying. Authentication failures and bad values are not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::ConnectionFailed { .. } | Self::SourceIo { .. } => true,
+            Self::RequestFailed { status, .. } => {
+                status.is_none_or(|status| status >= 500 || status == 429)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn key_path_invalid(key: impl Into<String>, at: impl Into<String>) -> Self {
+        Self::KeyPathInvalid {
+            key: key.into(),
+            at: at.into(),
+        }
+    }
+
+    pub fn interpolation_cycle(key: impl Into<String>) -> Self {
+        Self::InterpolationCycle { key: key.into() }
+    }
+
+    pub fn unsupported(operation: impl Into<String>) -> Self {
+        Self::Unsupported {
+            operation: operation.into(),
+        }
+    }
+
+    pub fn key_conflict(key: impl Into<String>) -> Self {
+        Self::KeyConflict { key: key.into() }
+    }
+
+    pub fn validation_failed(missing: Vec<String>) -> Self {
+        Self::ValidationFailed { missing }
+    }
+
+    pub fn deserialize_failed(reason: impl Into<String>) -> Self {
+        Self::DeserializeFailed {
+            reason: reason.into(),
         }
     }
 
@@ -56,4 +373,134 @@ impl ConfError {
             source,
         }
     }
+
+    pub fn env_key_invalid(key: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::EnvKeyInvalid {
+            key: key.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn secret_file_unreadable(
+        key: impl Into<String>,
+        path: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        let path = path.into();
+        Self::SecretFileUnreadable {
+            key: key.into(),
+            source: Box::new(Self::source_io(path.clone(), reason)),
+            path,
+        }
+    }
+
+    pub fn request_failed(
+        url: impl Into<String>,
+        status: Option<u16>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::RequestFailed {
+            url: url.into(),
+            status,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn val_not_utf8(key: impl Into<String>) -> Self {
+        Self::ValNotUtf8 { key: key.into() }
+    }
+
+    pub fn command_failed(
+        key: impl Into<String>,
+        command: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::CommandFailed {
+            key: key.into(),
+            command: command.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn connection_failed(url: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::ConnectionFailed {
+            url: url.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn auth_failed(url: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::AuthFailed {
+            url: url.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn keyring_failed(
+        service: impl Into<String>,
+        key: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::KeyringFailed {
+            service: service.into(),
+            key: key.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn one_password_unavailable(reason: impl Into<String>, help: impl Into<String>) -> Self {
+        Self::OnePasswordUnavailable {
+            reason: reason.into(),
+            help: help.into(),
+        }
+    }
+
+    pub fn decrypt_failed(path: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::DecryptFailed {
+            path: path.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn source_init(name: impl Into<String>, source: ConfError) -> Self {
+        Self::SourceInit {
+            name: name.into(),
+            source: Box::new(source),
+        }
+    }
+
+    pub fn file_load_failed(path: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::FileLoadFailed {
+            path: path.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn source_io(path: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::SourceIo {
+            path: path.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn file_parse_failed(
+        path: impl Into<String>,
+        line: usize,
+        column: usize,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::FileParseFailed {
+            path: path.into(),
+            line,
+            column,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn retries_exhausted(attempts: u32, source: ConfError) -> Self {
+        Self::RetriesExhausted {
+            attempts,
+            source: Box::new(source),
+        }
+    }
 }
@@ -5,56 +5,571 @@
 /// The core library currently only supports configs from environment variables in a slightly opinionated format;
 /// other config sources or unsupported var name schemes can be implemented with a custom [`ConfSource`]. Additional
 /// formats will be added over time.
+#[cfg(feature = "async")]
+mod async_conf;
 mod err;
+mod scope;
+mod source;
+mod value;
+#[cfg(feature = "watch")]
+mod watch;
 
+#[cfg(feature = "async")]
+pub use async_conf::{AsyncConf, AsyncConfSource, SyncSource};
 pub use err::ConfError;
+pub use scope::ScopedConf;
+#[cfg(feature = "consul")]
+pub use source::ConsulSource;
+#[cfg(feature = "gcp")]
+pub use source::GcpSecretSource;
+#[cfg(feature = "http")]
+pub use source::HttpSource;
+#[allow(deprecated)]
+pub use source::JsonFileSource;
+#[cfg(feature = "keyring")]
+pub use source::KeyringSource;
+#[cfg(feature = "kube")]
+pub use source::KubeConfigMapSource;
+#[cfg(feature = "redis")]
+pub use source::RedisSource;
+#[cfg(feature = "secretsmanager")]
+pub use source::SecretsManagerSource;
+#[cfg(feature = "sops")]
+pub use source::SopsSource;
+#[cfg(feature = "sqlite")]
+pub use source::SqliteSource;
+#[cfg(feature = "ssm")]
+pub use source::SsmSource;
+#[cfg(feature = "vault")]
+pub use source::VaultSource;
+#[cfg(feature = "yaml")]
+pub use source::YamlSource;
+#[cfg(feature = "zookeeper")]
+pub use source::ZkSource;
+pub use source::{
+    CachedSource, ChainedSource, CliSource, CommandSource, DirSource, DotenvSource, FileSource,
+    GitStyleSource, InMemorySource, IniSource, JsonSource, MapSource, OnePasswordSource,
+    RetryingSource, StaticSource, StdinSource, SystemdCredentialSource,
+};
+#[cfg(all(windows, feature = "registry"))]
+pub use source::{RegistryHive, RegistrySource};
+#[cfg(feature = "toml")]
+pub use source::{TomlSource, XdgSource};
+pub use value::{
+    ConfBool, ConfDuration, ConfList, ConfMap, ConfPath, FiniteFloat, ParseConfBoolError,
+    ParseConfDurationError, ParseConfListError, ParseConfMapError, ParseConfPathError,
+    ParseFiniteFloatError, Secret,
+};
+#[cfg(feature = "derive")]
+pub use voidconf_derive::Conf;
+#[cfg(feature = "watch")]
+pub use watch::{ConfChange, OnChange, WatchableSource};
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::{NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 type Result<T = ()> = core::result::Result<T, ConfError>;
 
+/// A typed entry with its unparsed value and where it came from.
+type Lookup<'a, V, O> = (&'a ConfEntry<V>, String, O);
+
 /// Name used by [`Conf::default`]. Env vars should be prefixed `VCFG_`.
 pub const DEFAULT_NAME: &str = "vcfg";
 
+/// Placeholder shown in errors instead of [secret](ConfEntry#structfield.secret) values.
+pub const REDACTED: &str = "<redacted>";
+
 /// Generic config value trait. Implement this for any custom types you want to support. This
 /// library includes several implementations for commmon types.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a supported config value type",
+    label = "not a `ConfValue`",
+    note = "implement `voidconf::ConfValue` for it, or use a supported type such as `String` or `u64`"
+)]
 pub trait ConfValue:
     Serialize + DeserializeOwned + Clone + std::fmt::Display + FromStr<Err: core::error::Error>
 {
+    /// Serialize the value into the string form it parses from, used when writing values with
+    /// [`Conf::set`] and [`Conf::set_override`]. Defaults to [`Display`](std::fmt::Display);
+    /// override it for types whose display form doesn't round-trip, such as [`Secret`].
+    fn to_raw(&self) -> String {
+        self.to_string()
+    }
 }
 
 impl ConfValue for String {}
+impl ConfValue for bool {}
+impl ConfValue for ConfBool {}
 impl ConfValue for u8 {}
 impl ConfValue for u16 {}
 impl ConfValue for u32 {}
 impl ConfValue for u64 {}
+impl ConfValue for u128 {}
+impl ConfValue for usize {}
 impl ConfValue for i8 {}
 impl ConfValue for i16 {}
 impl ConfValue for i32 {}
 impl ConfValue for i64 {}
+impl ConfValue for i128 {}
+impl ConfValue for isize {}
+impl ConfValue for f32 {}
+impl ConfValue for f64 {}
+impl ConfValue for char {}
+impl ConfValue for NonZeroU8 {}
+impl ConfValue for NonZeroU16 {}
+impl ConfValue for NonZeroU32 {}
+impl ConfValue for NonZeroU64 {}
+impl ConfValue for NonZeroUsize {}
+impl ConfValue for FiniteFloat {}
+impl ConfValue for ConfPath {}
+impl ConfValue for ConfDuration {}
+impl ConfValue for IpAddr {}
+impl ConfValue for Ipv4Addr {}
+impl ConfValue for Ipv6Addr {}
+impl ConfValue for SocketAddr {}
+impl<V: ConfValue, const D: char> ConfValue for ConfList<V, D> {}
+impl<K: ConfValue + Eq + Hash, V: ConfValue, const D: char, const S: char> ConfValue
+    for ConfMap<K, V, D, S>
+{
+}
+impl<V: ConfValue> ConfValue for Secret<V> {
+    fn to_raw(&self) -> String {
+        self.expose().to_raw()
+    }
+}
 impl ConfValue for serde_json::Value {}
+#[cfg(feature = "chrono")]
+impl ConfValue for chrono::DateTime<chrono::Utc> {}
+#[cfg(feature = "url")]
+impl ConfValue for url::Url {}
+#[cfg(feature = "uuid")]
+impl ConfValue for uuid::Uuid {}
 
 /// Source of config values. Can look up from the environment, read from a file, query a server, etc.
+/// The trait is object safe, so sources can be combined at runtime, see [`ChainedSource`].
 pub trait ConfSource {
     /// New [`ConfSource`] should determine where to look for a config based on the given `name`.
-    fn new(name: impl Into<String>) -> Self;
+    fn new(name: impl Into<String>) -> Self
+    where
+        Self: Sized;
+    /// Fallible [`ConfSource::new`], for sources which load a file or other data on
+    /// construction, so errors surface in [`Conf::try_new`] rather than on the first lookup. By
+    /// default this can't fail.
+    fn try_new(name: impl Into<String>) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self::new(name))
+    }
     /// Look up a value and return it in serialized string form. Return `None` if not present; default
     /// values are handled in [`Conf::get`].
-    fn get(&self, key: impl Into<String>) -> Result<Option<String>>;
+    fn get(&self, key: &str) -> Result<Option<String>>;
+    /// List the keys the source has values for, sorted, e.g. to warn about unrecognized settings.
+    /// Sources which can't enumerate their keys, such as remote key-value stores, fail with
+    /// [`ConfError::Unsupported`], the default.
+    fn keys(&self) -> Result<Vec<String>> {
+        Err(ConfError::unsupported("keys"))
+    }
+    /// Describe where the value for a key is looked up, e.g. `env var VCFG_PORT`, to report
+    /// where a value came from. Layered sources describe the layer with a value for the key. See
+    /// [`Conf::get_with_origin`].
+    fn describe(&self, key: &str) -> String {
+        format!("key {key}")
+    }
+    /// Look up a value like [`ConfSource::get`], along with its
+    /// [description](ConfSource::describe). Layered sources override this to describe the layer
+    /// which supplied the value from the same lookup, rather than looking the key up again.
+    fn get_described(&self, key: &str) -> Result<Option<(String, String)>> {
+        Ok(self.get(key)?.map(|v| (v, self.describe(key))))
+    }
+    /// Name of the env var the source reads a key from, for sources named like the environment
+    /// such as [`EnvSource`], so [`Conf::export_env`] writes vars the source reads back. `None`
+    /// by default. Layered sources use the first layer with a name.
+    fn env_var(&self, _key: &str) -> Option<String> {
+        None
+    }
+}
+
+/// A [`ConfSource`] which can also be written to, e.g. to persist preferences changed at runtime.
+/// Keys are translated the same way as in [`ConfSource::get`]. See [`Conf::set`].
+pub trait ConfSink {
+    /// Set a value in serialized string form, replacing any existing value.
+    fn set(&mut self, key: &str, val: &str) -> Result;
+    /// Remove a value. Removing a key which isn't set is not an error.
+    fn remove(&mut self, key: &str) -> Result;
+}
+
+/// Case strategy for translating key names, see [`EnvSource::case`]. Words are the parts of a
+/// key separated by `_`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyCase {
+    /// `MAX_CONNS`, the default.
+    #[default]
+    ScreamingSnake,
+    /// `max_conns`.
+    Snake,
+    /// `MAX-CONNS`. Not portable as an env var name.
+    KebabUpper,
+    /// `max-conns`. Not portable as an env var name.
+    KebabLower,
+    /// Unchanged, so `Max_Conns` stays `Max_Conns`.
+    AsIs,
+}
+
+impl KeyCase {
+    /// Convert a single word.
+    pub fn word(self, word: &str) -> String {
+        match self {
+            Self::ScreamingSnake | Self::KebabUpper => word.to_ascii_uppercase(),
+            Self::Snake | Self::KebabLower => word.to_ascii_lowercase(),
+            Self::AsIs => word.to_string(),
+        }
+    }
+
+    /// Join between words.
+    pub fn joiner(self) -> char {
+        match self {
+            Self::KebabUpper | Self::KebabLower => '-',
+            _ => '_',
+        }
+    }
+
+    /// Convert a key, converting each word and joining them.
+    pub fn apply(self, key: &str) -> String {
+        key.split('_')
+            .map(|word| self.word(word))
+            .collect::<Vec<_>>()
+            .join(&self.joiner().to_string())
+    }
+}
+
+/// Key mapping closure signature for [`KeyMapper`].
+type KeyMapperFn = dyn Fn(&str) -> String + Send + Sync;
+
+/// Custom translation from key names to full env var names, replacing the prefix, case and
+/// separator settings of an [`EnvSource`]. See [`EnvSource::with_key_mapper`]. Any closure
+/// converts into one.
+#[derive(Clone)]
+pub struct KeyMapper(std::sync::Arc<KeyMapperFn>);
+
+impl KeyMapper {
+    /// Map keys to `{PREFIX}__{SECTION}__{KEY}`, so `db.max_conns` with the prefix `app` is
+    /// read from `APP__DB__MAX_CONNS`.
+    pub fn prefixed_screaming_snake(prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into().to_ascii_uppercase();
+        Self::from(move |key: &str| format!("{prefix}__{}", screaming_snake(key)))
+    }
+
+    /// Map keys to `{SECTION}__{KEY}` with no prefix, so `db.max_conns` is read from
+    /// `DB__MAX_CONNS`.
+    pub fn unprefixed() -> Self {
+        Self::from(screaming_snake)
+    }
+
+    /// Translate a key name into an env var name.
+    pub fn map(&self, key: &str) -> String {
+        (self.0)(key)
+    }
+}
+
+impl<F: Fn(&str) -> String + Send + Sync + 'static> From<F> for KeyMapper {
+    fn from(f: F) -> Self {
+        Self(std::sync::Arc::new(f))
+    }
+}
+
+impl std::fmt::Debug for KeyMapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("KeyMapper")
+    }
+}
+
+/// Uppercase a key, joining its sections with `__`.
+fn screaming_snake(key: &str) -> String {
+    key.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .map(str::to_ascii_uppercase)
+        .collect::<Vec<_>>()
+        .join("__")
 }
 
 /// A [`ConfSource`] for resolving prefixed values from environment variables.
+///
+/// Keys are translated to env vars by uppercasing and prepending the prefix, so `port` is read
+/// from `VCFG_PORT`; other conventions can be chosen with [`EnvSource::with_case`]. Dots and
+/// other characters that aren't ASCII letters, digits or `_` become the
+/// [separator](EnvSource::separator), so `db.host` is read from `VCFG_DB__HOST` while `db_host`
+/// is still `VCFG_DB_HOST`. Keys map back with [`EnvSource::key_from_env_key`], as long as they
+/// don't contain the separator themselves.
+#[derive(Clone)]
 pub struct EnvSource {
     /// This should be the value of [`Conf::name`] in uppercase.
     pub prefix: String,
+    /// When a var is not set, read the value from the file named by `{env_key}_FILE` instead,
+    /// the convention Docker and Kubernetes use for secrets. Disabled by default, since it would
+    /// misread entries whose own name ends in `_file`, such as `log` and `log_file`.
+    pub file_indirection: bool,
+    /// Trim surrounding whitespace from values, e.g. a trailing newline from `$(cat ...)`.
+    pub trim: bool,
+    /// Treat an empty var as unset, so the entry default applies.
+    pub empty_as_unset: bool,
+    /// Additional uppercase prefixes tried in order when no var with [`EnvSource::prefix`] is
+    /// set, see [`EnvSource::with_fallback_prefix`].
+    pub fallback_prefixes: Vec<String>,
+    /// Replaces `.` and other separators in keys, `__` by default.
+    pub separator: String,
+    /// Case strategy applied to keys, [`KeyCase::ScreamingSnake`] by default. The prefix is used
+    /// as-is.
+    pub case: KeyCase,
+    /// Vars captured by [`EnvSource::with_snapshot`], used instead of the live environment.
+    /// `None` for live lookups, the default.
+    pub snapshot: Option<std::collections::BTreeMap<String, std::ffi::OsString>>,
+    /// Vars loaded by [`EnvSource::with_dotenv`], used when a var isn't in the environment. They
+    /// are never written to the process environment.
+    pub dotenv: std::collections::BTreeMap<String, String>,
+    /// Translation from keys to env var names used instead of the prefix, case and separator,
+    /// see [`EnvSource::with_key_mapper`].
+    pub key_mapper: Option<KeyMapper>,
 }
 
 impl EnvSource {
+    /// Enable [file indirection](EnvSource::file_indirection), so
+    /// `VCFG_PASSWORD_FILE=/run/secrets/pw` satisfies the `password` key with the contents of
+    /// that file, minus a trailing newline.
+    pub fn with_file_indirection(mut self) -> Self {
+        self.file_indirection = true;
+        self
+    }
+
+    /// Disable [file indirection](EnvSource::file_indirection), the default, so `_FILE` vars are
+    /// ignored.
+    pub fn without_file_indirection(mut self) -> Self {
+        self.file_indirection = false;
+        self
+    }
+
+    /// Add a prefix to fall back to, e.g. the old name of a renamed service. Fallbacks are
+    /// tried in the order added, and only if no var with [`EnvSource::prefix`] is set.
+    pub fn with_fallback_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.fallback_prefixes
+            .push(prefix.into().to_ascii_uppercase());
+        self
+    }
+
+    /// Create a new [`EnvSource`] in [snapshot mode](EnvSource::with_snapshot).
+    pub fn snapshot(name: impl Into<String>) -> Self {
+        Self::new(name).with_snapshot()
+    }
+
+    /// Create a new [`EnvSource`] serving lookups from the given vars, keyed by full env key
+    /// such as `VCFG_PORT`, instead of the environment. Useful in tests, since it doesn't touch
+    /// process state.
+    pub fn from_map(
+        name: impl Into<String>,
+        vars: std::collections::HashMap<String, String>,
+    ) -> Self {
+        Self::new(name).with_vars(vars.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+
+    /// Capture every var starting with the prefix or a fallback prefix now, or every var if a
+    /// [key mapper](EnvSource::with_key_mapper) is set, and serve all lookups from the captured
+    /// vars, so later changes to the environment are ignored. Call this after
+    /// [`EnvSource::with_fallback_prefix`]. Files named by `_FILE` vars are still read on each
+    /// lookup.
+    pub fn with_snapshot(self) -> Self {
+        let vars = std::env::vars_os().filter(|(key, _)| {
+            key.to_str().is_some_and(|key| {
+                self.key_mapper.is_some()
+                    || std::iter::once(&self.prefix)
+                        .chain(&self.fallback_prefixes)
+                        .any(|prefix| {
+                            key.strip_prefix(prefix.as_str())
+                                .is_some_and(|k| k.starts_with('_'))
+                        })
+            })
+        });
+        let vars = vars
+            .map(|(key, val)| (key.to_string_lossy().into_owned(), val))
+            .collect();
+        self.with_vars(vars)
+    }
+
+    /// Serve lookups from the given vars instead of the environment.
+    fn with_vars(mut self, vars: std::collections::BTreeMap<String, std::ffi::OsString>) -> Self {
+        self.snapshot = Some(vars);
+        self
+    }
+
+    /// Load `.env` and then `.env.local` from the current directory, see
+    /// [`EnvSource::with_dotenv_path`].
+    pub fn with_dotenv(self) -> Result<Self> {
+        self.with_dotenv_path(".env")?
+            .with_dotenv_path(".env.local")
+    }
+
+    /// Load vars from a `.env` file into [`EnvSource::dotenv`], overriding vars from files loaded
+    /// earlier. Vars set in the environment still take precedence over the file. A missing file
+    /// is ignored; a malformed one is a [`ConfError::FileParseFailed`] naming the line. See
+    /// [`DotenvSource`] for the syntax.
+    pub fn with_dotenv_path(mut self, path: impl AsRef<std::path::Path>) -> Result<Self> {
+        self.dotenv.extend(source::load_dotenv(path.as_ref())?);
+        Ok(self)
+    }
+
+    /// Look up a var in the snapshot, or the environment if there isn't one, then in the
+    /// [dotenv vars](EnvSource::dotenv).
+    fn var(&self, key: &str) -> Result<Option<String>> {
+        let v = match &self.snapshot {
+            None => env_var(key)?,
+            Some(vars) => vars
+                .get(key)
+                .map(|v| {
+                    v.clone().into_string().map_err(|v| {
+                        ConfError::env_lookup_failed(key, std::env::VarError::NotUnicode(v))
+                    })
+                })
+                .transpose()?,
+        };
+        let clean = |v: String| {
+            let v = if self.trim { v.trim().to_string() } else { v };
+            (!self.empty_as_unset || !v.is_empty()).then_some(v)
+        };
+        Ok(v.and_then(clean)
+            .or_else(|| self.dotenv.get(key).cloned().and_then(clean)))
+    }
+
+    /// Enable [trimming](EnvSource::trim) values.
+    pub fn with_trim(mut self) -> Self {
+        self.trim = true;
+        self
+    }
+
+    /// Treat [empty vars as unset](EnvSource::empty_as_unset). Combined with
+    /// [`EnvSource::with_trim`], whitespace-only vars are unset too.
+    pub fn with_empty_as_unset(mut self) -> Self {
+        self.empty_as_unset = true;
+        self
+    }
+
+    /// Update the [case strategy](EnvSource::case). Lookups with a kebab case fail with
+    /// [`ConfError::EnvKeyInvalid`], since `-` isn't portable in env var names.
+    pub fn with_case(mut self, case: KeyCase) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Update the [separator](EnvSource::separator).
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Translate keys to env var names with a custom [`KeyMapper`] or closure, for naming
+    /// schemes the prefix, case and separator settings can't express. Fallback prefixes are not
+    /// tried, and [`ConfSource::keys`] fails with [`ConfError::Unsupported`], since mapped
+    /// names can't be translated back. Call this before [`EnvSource::with_snapshot`].
+    ///
+    /// ```
+    /// use voidconf::{ConfSource, EnvSource, KeyMapper};
+    ///
+    /// let source = EnvSource::new("vcfg").with_key_mapper(KeyMapper::prefixed_screaming_snake("app"));
+    /// assert_eq!(source.env_key("db.host"), "APP__DB__HOST");
+    /// let source = EnvSource::new("vcfg").with_key_mapper(|key: &str| format!("MY_{key}"));
+    /// assert_eq!(source.env_key("port"), "MY_port");
+    /// ```
+    pub fn with_key_mapper(mut self, mapper: impl Into<KeyMapper>) -> Self {
+        self.key_mapper = Some(mapper.into());
+        self
+    }
+
     /// Translate a key name into its corresponding env key.
-    /// Prepends [`EnvSource::prefix`] and converts to uppercase.
+    /// Prepends [`EnvSource::prefix`], converts to uppercase and replaces separators, unless a
+    /// [key mapper](EnvSource::with_key_mapper) is set.
     pub fn env_key(&self, key: impl Into<String>) -> String {
-        format!("{}_{}", self.prefix, key.into().to_ascii_uppercase())
+        match &self.key_mapper {
+            Some(mapper) => mapper.map(&key.into()),
+            None => format!("{}_{}", self.prefix, self.translate(&key.into())),
+        }
+    }
+
+    /// Translate an env key with any of the prefixes back into a key name, the reverse of
+    /// [`EnvSource::env_key`]. Returns `None` for vars without a prefix, and for every var if a
+    /// [key mapper](EnvSource::with_key_mapper) is set.
+    pub fn key_from_env_key(&self, env_key: &str) -> Option<String> {
+        if self.key_mapper.is_some() {
+            return None;
+        }
+        let rest = std::iter::once(&self.prefix)
+            .chain(&self.fallback_prefixes)
+            .find_map(|prefix| env_key.strip_prefix(prefix)?.strip_prefix('_'))?;
+        let sections: Vec<&str> = match self.separator.as_str() {
+            "" => vec![rest],
+            separator => rest.split(separator).collect(),
+        };
+        let joiner = self.case.joiner().to_string();
+        let key = sections
+            .iter()
+            .map(|section| section.replace(&joiner, "_"))
+            .collect::<Vec<_>>()
+            .join(".");
+        match self.case {
+            KeyCase::AsIs => Some(key),
+            _ => Some(key.to_ascii_lowercase()),
+        }
+    }
+
+    /// Translate a key name into an env key without the prefix.
+    fn translate(&self, key: &str) -> String {
+        key.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .map(|section| self.case.apply(section))
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+
+    /// Look up a key, returning the env var that matched along with its value. Each prefix is
+    /// tried in order, then its `_FILE` var if [file indirection](EnvSource::file_indirection) is
+    /// enabled.
+    pub fn find(&self, key: &str) -> Result<Option<(String, String)>> {
+        let env_keys = match &self.key_mapper {
+            Some(mapper) => vec![mapper.map(key)],
+            None if self.case.joiner() != '_' => {
+                return Err(ConfError::env_key_invalid(
+                    self.env_key(key),
+                    "`-` is not portable in env var names, use a snake case",
+                ));
+            }
+            None => {
+                let key = self.translate(key);
+                std::iter::once(&self.prefix)
+                    .chain(&self.fallback_prefixes)
+                    .map(|prefix| format!("{prefix}_{key}"))
+                    .collect()
+            }
+        };
+        for env_key in env_keys {
+            if let Some(v) = self.var(&env_key)? {
+                return Ok(Some((env_key, v)));
+            }
+            if !self.file_indirection {
+                continue;
+            }
+            let file_key = format!("{env_key}_FILE");
+            if let Some(path) = self.var(&file_key)? {
+                let v = std::fs::read_to_string(&path)
+                    .map(source::trim_newline)
+                    .map_err(|e| {
+                        ConfError::secret_file_unreadable(&file_key, &path, e.to_string())
+                    })?;
+                return Ok(Some((file_key, v)));
+            }
+        }
+        Ok(None)
     }
 }
 
@@ -63,22 +578,106 @@ impl ConfSource for EnvSource {
     fn new(name: impl Into<String>) -> Self {
         Self {
             prefix: name.into().to_ascii_uppercase(),
+            file_indirection: false,
+            trim: false,
+            empty_as_unset: false,
+            fallback_prefixes: Vec::new(),
+            separator: "__".to_string(),
+            case: KeyCase::default(),
+            snapshot: None,
+            dotenv: std::collections::BTreeMap::new(),
+            key_mapper: None,
         }
     }
 
-    /// Query the value using the [translated key](EnvSource::env_key) from the environment.
-    fn get(&self, key: impl Into<String>) -> Result<Option<String>> {
-        let env_key = self.env_key(key);
-        match std::env::var(&env_key) {
-            Ok(v) => Some(
-                v.parse()
-                    .map_err(|_| ConfError::val_parse_failed(&env_key, &v)),
-            )
-            .transpose(),
-            Err(std::env::VarError::NotPresent) => Ok(None),
-            Err(e) => Err(ConfError::env_lookup_failed(&env_key, e)),
+    /// Query the value using the [translated key](EnvSource::env_key) from the environment,
+    /// then any [fallback prefixes](EnvSource::fallback_prefixes). See [`EnvSource::find`].
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.find(key)?.map(|(_, v)| v))
+    }
+
+    /// List the keys of every var with the prefix or a fallback prefix, in the environment or
+    /// snapshot and the [dotenv vars](EnvSource::dotenv), translated back with
+    /// [`EnvSource::key_from_env_key`]. With [file indirection](EnvSource::file_indirection),
+    /// `_FILE` vars are listed both with and without the suffix, since either key may be read
+    /// from them.
+    fn keys(&self) -> Result<Vec<String>> {
+        if self.key_mapper.is_some() {
+            return Err(ConfError::unsupported("keys"));
+        }
+        let vars: Vec<String> = match &self.snapshot {
+            Some(vars) => vars.keys().cloned().collect(),
+            None => std::env::vars_os()
+                .filter_map(|(key, _)| key.into_string().ok())
+                .collect(),
+        };
+        let keys = vars.iter().chain(self.dotenv.keys()).flat_map(|var| {
+            let indirect = var.strip_suffix("_FILE").filter(|_| self.file_indirection);
+            std::iter::once(var.as_str())
+                .chain(indirect)
+                .filter_map(|var| self.key_from_env_key(var))
+        });
+        Ok(keys
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect())
+    }
+
+    /// Name the env var which supplies the key, or the [translated key](EnvSource::env_key) if
+    /// none is set.
+    fn describe(&self, key: &str) -> String {
+        match self.find(key) {
+            Ok(Some((var, _))) => format!("env var {var}"),
+            _ => format!("env var {}", self.env_key(key)),
         }
     }
+
+    /// The [translated key](EnvSource::env_key).
+    fn env_var(&self, key: &str) -> Option<String> {
+        Some(self.env_key(key))
+    }
+}
+
+impl ConfSource for Box<dyn ConfSource> {
+    /// Create a new boxed [`EnvSource`]. To choose a source at runtime, box it and use
+    /// [`Conf::with_boxed_source`].
+    fn new(name: impl Into<String>) -> Self {
+        Box::new(EnvSource::new(name))
+    }
+
+    /// Look up the value in the boxed source.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        (**self).get(key)
+    }
+
+    /// List the keys of the boxed source.
+    fn keys(&self) -> Result<Vec<String>> {
+        (**self).keys()
+    }
+
+    /// Describe the key in the boxed source.
+    fn describe(&self, key: &str) -> String {
+        (**self).describe(key)
+    }
+
+    /// Look up and describe the value in the boxed source.
+    fn get_described(&self, key: &str) -> Result<Option<(String, String)>> {
+        (**self).get_described(key)
+    }
+
+    /// Name the key's env var in the boxed source.
+    fn env_var(&self, key: &str) -> Option<String> {
+        (**self).env_var(key)
+    }
+}
+
+/// Look up an env var, treating an unset var as `None`.
+fn env_var(key: &str) -> Result<Option<String>> {
+    match std::env::var(key) {
+        Ok(v) => Ok(Some(v)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => Err(ConfError::env_lookup_failed(key, e)),
+    }
 }
 
 /// Definition of a single conf option.
@@ -90,6 +689,49 @@ pub struct ConfEntry<V: ConfValue> {
     pub val_type: std::marker::PhantomData<V>,
     /// Optional default value. Must deserialize into `V`.
     pub default: Option<String>,
+    /// Whether a value must be present, from the source or the default. Checked by [`Conf::validate`].
+    #[serde(default)]
+    pub required: bool,
+    /// Optional help text for operators, shown by [`Conf::describe`] and in error diagnostics.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Whether the value is sensitive. Errors show [`REDACTED`] in place of a secret value.
+    #[serde(default)]
+    pub secret: bool,
+    /// Optional inclusive lower bound. Must deserialize into `V`. See [`ConfEntry::min`].
+    #[serde(default)]
+    pub min: Option<String>,
+    /// Optional inclusive upper bound. Must deserialize into `V`. See [`ConfEntry::max`].
+    #[serde(default)]
+    pub max: Option<String>,
+    /// Comparison used to check the bounds, set by [`ConfEntry::min`] and [`ConfEntry::max`].
+    #[serde(skip)]
+    compare: Option<fn(&V, &V) -> Option<std::cmp::Ordering>>,
+    /// Optional check run on parsed values, see [`ConfEntry::validate_with`].
+    #[serde(skip)]
+    validator: Option<Validator<V>>,
+    /// Old names still looked up when the source has no value for `name`. See
+    /// [`ConfEntry::alias`].
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// Validation closure signature for [`ConfEntry::validate_with`].
+type ValidatorFn<V> = dyn Fn(&V) -> core::result::Result<(), String> + Send + Sync;
+
+/// Shared validation closure for a [`ConfEntry`].
+struct Validator<V>(std::sync::Arc<ValidatorFn<V>>);
+
+impl<V> Clone for Validator<V> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<V> std::fmt::Debug for Validator<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Validator")
+    }
 }
 
 impl<V: ConfValue> ConfEntry<V> {
@@ -99,6 +741,14 @@ impl<V: ConfValue> ConfEntry<V> {
             name: name.into(),
             val_type: std::marker::PhantomData::<V>,
             default: None,
+            required: false,
+            description: None,
+            secret: false,
+            min: None,
+            max: None,
+            compare: None,
+            validator: None,
+            aliases: Vec::new(),
         }
     }
 
@@ -107,268 +757,3031 @@ impl<V: ConfValue> ConfEntry<V> {
         self.default = Some(default.into());
         self
     }
-}
 
-/// This trait allows our [`ConfEntry`]s to all get along in [one big map](Conf::options).
-pub trait AnyConfEntry: Send + Sync {
-    /// Get a dynamic reference to the struct.
-    fn as_any(&self) -> &dyn std::any::Any;
-}
+    /// Mark this entry as required. See [`ConfEntry::required`](ConfEntry#structfield.required).
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
 
-impl<V: ConfValue + Send + Sync + 'static> AnyConfEntry for ConfEntry<V> {
-    fn as_any(&self) -> &dyn std::any::Any {
+    /// Add help text describing what this entry does.
+    pub fn describe(mut self, text: impl Into<String>) -> Self {
+        self.description = Some(text.into());
         self
     }
-}
 
-/// Top-level conf struct represents a single named config source.
-pub struct Conf<S: ConfSource = EnvSource> {
-    /// Config name. Source lookups are derived from this.
-    pub name: &'static str,
-    /// Source for config values. See [`ConfSource`].
-    pub source: S,
-    /// Map of configured [`ConfEntry`] options.
-    pub options: std::collections::BTreeMap<String, Box<dyn AnyConfEntry>>,
-}
+    /// Mark this entry as [secret](ConfEntry#structfield.secret), so errors don't include its value.
+    pub fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
 
-impl<S: ConfSource> Conf<S> {
-    /// Create a new config. Also initializes the [`ConfSource`].
-    pub fn new(name: &'static str) -> Self {
-        Self {
-            source: S::new(name),
-            options: std::collections::BTreeMap::new(),
-            name,
-        }
+    /// Add an old name for this entry, so a renamed key keeps working. If the source has no
+    /// value for the entry's name, each alias is looked up in the order added. With the `log`
+    /// feature, a deprecation warning is logged when an alias supplies the value. A [`Conf`]
+    /// also accepts the alias as a key for lookups.
+    pub fn alias(mut self, old_name: impl Into<String>) -> Self {
+        self.aliases.push(old_name.into());
+        self
     }
 
-    /// Add a new [`ConfEntry`]. This is a lower-level function for custom [`ConfValue`] types;
-    /// where possible the typed functions such as [`Conf::string`] are preferred.
-    pub fn entry<V: ConfValue + Send + Sync + 'static>(mut self, entry: ConfEntry<V>) -> Self {
-        self.options.insert(entry.name.clone(), Box::new(entry));
+    /// Reject parsed values below `min`, inclusive, with [`ConfError::OutOfRange`]. Bounds are
+    /// stored in string form like defaults, and are only checked on entries built with this
+    /// method or [`ConfEntry::max`], not on deserialized ones.
+    pub fn min(mut self, min: V) -> Self
+    where
+        V: PartialOrd,
+    {
+        self.min = Some(min.to_string());
+        self.compare = Some(V::partial_cmp);
         self
     }
 
-    /// Add a string entry.
-    pub fn string(self, name: impl Into<String>, default: Option<&str>) -> Self {
-        let entry: ConfEntry<String> = ConfEntry::new(name);
-        match default {
-            Some(d) => self.entry(entry.with_default(d)),
-            None => self.entry(entry),
-        }
+    /// Reject parsed values above `max`, inclusive. See [`ConfEntry::min`].
+    pub fn max(mut self, max: V) -> Self
+    where
+        V: PartialOrd,
+    {
+        self.max = Some(max.to_string());
+        self.compare = Some(V::partial_cmp);
+        self
     }
 
-    /// Add a byte (`u8`) entry.
-    pub fn byte(self, name: impl Into<String>, default: Option<u8>) -> Self {
-        let entry: ConfEntry<u8> = ConfEntry::new(name);
-        match default {
-            Some(d) => self.entry(entry.with_default(d.to_string())),
-            None => self.entry(entry),
+    /// Check a parsed value against the bounds. Incomparable values, like `NaN`, are out of range.
+    fn check_bounds(&self, val: &V, raw: &str) -> Result {
+        use std::cmp::Ordering::{Equal, Greater, Less};
+        let Some(compare) = self.compare else {
+            return Ok(());
+        };
+        let within = |bound: &Option<String>, ok: [std::cmp::Ordering; 2]| -> Result<bool> {
+            match bound {
+                Some(b) => b
+                    .parse::<V>()
+                    .map(|b| compare(val, &b).is_some_and(|o| ok.contains(&o)))
+                    .map_err(|e| ConfError::val_parse_failed(&self.name, b, e.to_string())),
+                None => Ok(true),
+            }
+        };
+        if within(&self.min, [Greater, Equal])? && within(&self.max, [Less, Equal])? {
+            Ok(())
+        } else {
+            Err(ConfError::out_of_range(
+                &self.name,
+                raw,
+                self.min.clone(),
+                self.max.clone(),
+            ))
         }
     }
 
-    /// Add an int (`i64`) entry.
-    pub fn int(self, name: impl Into<String>, default: Option<i64>) -> Self {
-        let entry: ConfEntry<i64> = ConfEntry::new(name);
-        match default {
-            Some(d) => self.entry(entry.with_default(d.to_string())),
-            None => self.entry(entry),
+    /// Add a check run on every parsed value, including the default. A returned `Err(reason)`
+    /// is surfaced as [`ConfError::ValInvalid`]. Useful for values that parse but are only
+    /// meaningful within a range, like a nonzero port.
+    pub fn validate_with(
+        mut self,
+        f: impl Fn(&V) -> core::result::Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.validator = Some(Validator(std::sync::Arc::new(f)));
+        self
+    }
+
+    /// Look up this entry in the given source, falling back to the default, then parse and
+    /// validate it.
+    pub fn resolve(&self, source: &dyn ConfSource) -> Result<Option<V>> {
+        self.raw(source)?.map(|v| self.parse(v)).transpose()
+    }
+
+    /// Look up the unparsed value in the given source, falling back to the default.
+    pub fn raw(&self, source: &dyn ConfSource) -> Result<Option<String>> {
+        Ok(self.raw_with_origin(source)?.map(|(v, _)| v))
+    }
+
+    /// Look up the unparsed value like [`ConfEntry::raw`], along with whether it came from the
+    /// source or the default.
+    pub fn raw_with_origin(
+        &self,
+        source: &dyn ConfSource,
+    ) -> Result<Option<(String, ValueOrigin)>> {
+        let v = self.lookup(|key| source.get(key));
+        let origin = match v {
+            Ok(Some(_)) => ValueOrigin::Source,
+            _ => ValueOrigin::Default,
+        };
+        Ok(self.or_default(v)?.map(|v| (v, origin)))
+    }
+
+    /// Look up the unparsed value like [`ConfEntry::raw`], along with its [`Origin`]. A value
+    /// from the source is [described](ConfSource::get_described) in the same lookup, under the
+    /// name or [alias](ConfEntry#structfield.aliases) which matched.
+    pub fn raw_described(&self, source: &dyn ConfSource) -> Result<Option<(String, Origin)>> {
+        match self.lookup(|key| source.get_described(key)) {
+            Ok(Some((v, detail))) => Ok(Some((v, Origin::Source { detail }))),
+            v => Ok(self
+                .or_default(v.map(|_| None))?
+                .map(|v| (v, Origin::Default))),
         }
     }
 
-    /// Add a uint (`u64`) entry.
-    pub fn uint(self, name: impl Into<String>, default: Option<u64>) -> Self {
-        let entry: ConfEntry<u64> = ConfEntry::new(name);
-        match default {
-            Some(d) => self.entry(entry.with_default(d.to_string())),
-            None => self.entry(entry),
+    /// Look up the entry's name with `get`, then each alias.
+    fn lookup<T>(&self, get: impl Fn(&str) -> Result<Option<T>>) -> Result<Option<T>> {
+        if let Some(v) = get(&self.name)? {
+            return Ok(Some(v));
+        }
+        for alias in &self.aliases {
+            if let Some(v) = get(alias)? {
+                warn_alias(alias, &self.name);
+                return Ok(Some(v));
+            }
         }
+        Ok(None)
     }
 
-    /// Get a value. An error will be thrown if the value cannot parse into the type expected
-    /// by the configured entry.
-    pub fn get<V: ConfValue + 'static>(&self, key: &str) -> Result<Option<V>> {
-        match self.options.get(key) {
-            Some(option) => match option.as_any().downcast_ref::<ConfEntry<V>>() {
-                Some(entry) => self
-                    .source
-                    .get(&entry.name)?
-                    .or_else(|| entry.default.clone())
-                    .map(|v| v.parse().map_err(|_| ConfError::val_parse_failed(key, &v)))
-                    .transpose(),
-                None => Err(ConfError::val_parse_failed(key, "")),
-            },
-            None => Err(ConfError::key_not_found(key)),
+    /// Apply the default to a source lookup result, redacting any error.
+    fn or_default(&self, v: Result<Option<String>>) -> Result<Option<String>> {
+        match v {
+            Ok(v) => Ok(v.or_else(|| self.default.clone())),
+            Err(e) => Err(self.redact(e)),
         }
     }
 
-    /// Get a string value.
-    pub fn get_string(&self, key: &str) -> Result<Option<String>> {
-        self.get::<String>(key)
+    /// Parse a raw value, then check it against the bounds and validator. Errors carry the
+    /// entry's description, if any.
+    pub fn parse(&self, v: String) -> Result<V> {
+        let check = || {
+            let val = v
+                .parse::<V>()
+                .map_err(|e| ConfError::val_parse_failed(&self.name, &v, e.to_string()))?;
+            self.check_bounds(&val, &v)?;
+            if let Some(Validator(f)) = &self.validator {
+                f(&val).map_err(|reason| ConfError::val_invalid(&self.name, reason))?;
+            }
+            Ok(val)
+        };
+        check().map_err(|e: ConfError| self.redact(e.with_description(self.description.clone())))
     }
 
-    /// Get a byte (`u8`) value.
-    pub fn get_byte(&self, key: &str) -> Result<Option<u8>> {
-        self.get::<u8>(key)
+    /// Redact the value from an error if this entry is [secret](ConfEntry#structfield.secret).
+    fn redact(&self, e: ConfError) -> ConfError {
+        if self.secret {
+            e.redacted()
+        } else {
+            e
+        }
     }
+}
 
-    /// Get an int (`i64`) value.
-    pub fn get_int(&self, key: &str) -> Result<Option<i64>> {
-        self.get::<i64>(key)
+/// This trait allows our [`ConfEntry`]s to all get along in [one big map](Conf::options).
+pub trait AnyConfEntry: Send + Sync {
+    /// Get a dynamic reference to the struct.
+    fn as_any(&self) -> &dyn std::any::Any;
+    /// The entry's [name](ConfEntry#structfield.name).
+    fn name(&self) -> &str;
+    /// The entry's [aliases](ConfEntry::alias).
+    fn aliases(&self) -> &[String];
+    /// Clone the entry into a new box.
+    fn boxed_clone(&self) -> Box<dyn AnyConfEntry>;
+    /// Whether the entry is [required](ConfEntry#structfield.required).
+    fn is_required(&self) -> bool;
+    /// Whether the entry is [secret](ConfEntry#structfield.secret).
+    fn is_secret(&self) -> bool;
+    /// [Resolve](ConfEntry::resolve) the entry without knowing its type, and report whether
+    /// a value is present.
+    fn is_present(&self, source: &dyn ConfSource) -> Result<bool>;
+    /// Look up the [unparsed value](ConfEntry::raw) without knowing the entry's type.
+    fn raw(&self, source: &dyn ConfSource) -> Result<Option<String>>;
+    /// Look up the [unparsed value and its origin](ConfEntry::raw_with_origin) without knowing
+    /// the entry's type.
+    fn raw_with_origin(&self, source: &dyn ConfSource) -> Result<Option<(String, ValueOrigin)>>;
+    /// Name of the entry's value type, from [`std::any::type_name`].
+    fn type_name(&self) -> &'static str;
+    /// The entry's [default value](ConfEntry#structfield.default), if any.
+    fn default_val(&self) -> Option<&str>;
+    /// The entry's [description](ConfEntry#structfield.description), if any.
+    fn description(&self) -> Option<&str>;
+    /// [Parse](ConfEntry::parse) a raw value without knowing the entry's type, discarding the
+    /// result.
+    fn check(&self, raw: String) -> Result;
+    /// Apply the default to a source lookup result, as in [`ConfEntry::raw`], without knowing
+    /// the entry's type.
+    fn apply_default(&self, v: Result<Option<String>>) -> Result<Option<String>>;
+    /// [Parse](ConfEntry::parse) a raw value and serialize the result to JSON, so it keeps its
+    /// type, e.g. a number rather than a string.
+    fn to_json(&self, raw: String) -> Result<serde_json::Value>;
+}
+
+impl<V: ConfValue + Send + Sync + 'static> AnyConfEntry for ConfEntry<V> {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
     }
 
-    /// Get a uint (`u64`) value.
-    pub fn get_uint(&self, key: &str) -> Result<Option<u64>> {
-        self.get::<u64>(key)
+    fn name(&self) -> &str {
+        &self.name
     }
 
-    /// Require a value. Similar to [`Conf::get`] except a `None` return value
-    /// is treated as an error.
-    pub fn require<V: ConfValue + 'static>(&self, key: &str) -> Result<V> {
-        self.get(key)
-            .transpose()
-            .ok_or_else(|| ConfError::val_not_found(key))?
+    fn aliases(&self) -> &[String] {
+        &self.aliases
     }
 
-    /// Require a string value.
-    pub fn require_string(&self, key: &str) -> Result<String> {
-        self.require::<String>(key)
+    fn boxed_clone(&self) -> Box<dyn AnyConfEntry> {
+        Box::new(self.clone())
     }
 
-    /// Require a byte (`u8`) value.
-    pub fn require_byte(&self, key: &str) -> Result<u8> {
-        self.require::<u8>(key)
+    fn is_required(&self) -> bool {
+        self.required
     }
 
-    /// Require an int (`i64`) value.
-    pub fn require_int(&self, key: &str) -> Result<i64> {
-        self.require::<i64>(key)
+    fn is_secret(&self) -> bool {
+        self.secret
     }
 
-    /// Require a uint (`u64`) value.
-    pub fn require_uint(&self, key: &str) -> Result<u64> {
-        self.require::<u64>(key)
+    fn is_present(&self, source: &dyn ConfSource) -> Result<bool> {
+        self.resolve(source).map(|v| v.is_some())
+    }
+
+    fn raw(&self, source: &dyn ConfSource) -> Result<Option<String>> {
+        ConfEntry::raw(self, source)
+    }
+
+    fn raw_with_origin(&self, source: &dyn ConfSource) -> Result<Option<(String, ValueOrigin)>> {
+        ConfEntry::raw_with_origin(self, source)
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<V>()
+    }
+
+    fn default_val(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn check(&self, raw: String) -> Result {
+        self.parse(raw).map(|_| ())
+    }
+
+    fn apply_default(&self, v: Result<Option<String>>) -> Result<Option<String>> {
+        self.or_default(v)
+    }
+
+    fn to_json(&self, raw: String) -> Result<serde_json::Value> {
+        serde_json::to_value(self.parse(raw)?)
+            .map_err(|e| ConfError::deserialize_failed(format!("{}: {e}", self.name)))
+    }
+}
+
+/// Single quote a value for a POSIX shell, unless it only has characters the shell leaves alone.
+fn shell_quote(val: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "%+,-./:=@_".contains(c);
+    if !val.is_empty() && val.chars().all(plain) {
+        val.to_string()
+    } else {
+        format!("'{}'", val.replace('\'', r"'\''"))
+    }
+}
+
+/// Log that a deprecated alias supplied the value for an entry. Does nothing without the `log`
+/// feature.
+#[cfg_attr(not(feature = "log"), allow(unused_variables))]
+fn warn_alias(alias: &str, name: &str) {
+    #[cfg(feature = "log")]
+    log::warn!("conf key `{alias}` is deprecated, use `{name}` instead");
+}
+
+/// Where a resolved value came from. See [`Conf::get_with_source`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueOrigin {
+    /// Looked up in the [`ConfSource`].
+    Source,
+    /// The entry's [default value](ConfEntry#structfield.default).
+    Default,
+    /// Set with [`Conf::set_override`].
+    Override,
+}
+
+/// Where a resolved value came from, including the part of the source which supplied it. See
+/// [`Conf::get_with_origin`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Origin {
+    /// Looked up in the [`ConfSource`], with its [description](ConfSource::describe) of where,
+    /// e.g. `env var VCFG_PORT`.
+    Source { detail: String },
+    /// The entry's [default value](ConfEntry#structfield.default).
+    Default,
+    /// Set with [`Conf::set_override`].
+    Override,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Source { detail } => f.write_str(detail),
+            Self::Default => f.write_str("default"),
+            Self::Override => f.write_str("override"),
+        }
+    }
+}
+
+/// Metadata about a configured option, for generating docs or admin UIs. See [`Conf::describe`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConfOptionInfo {
+    /// Conf key name.
+    pub key: String,
+    /// Name of the value type, from [`std::any::type_name`], e.g. `alloc::string::String`.
+    pub type_name: String,
+    /// Default value in string form, if any.
+    pub default: Option<String>,
+    /// Whether the entry is [required](ConfEntry#structfield.required).
+    pub required: bool,
+    /// Help text, if any.
+    pub description: Option<String>,
+}
+
+impl ConfOptionInfo {
+    /// Whether the option has a default value.
+    pub fn has_default(&self) -> bool {
+        self.default.is_some()
+    }
+}
+
+/// Top-level conf struct represents a single named config source.
+///
+/// Values are looked up in the source first, then fall back to the entry default. To layer
+/// several sources, use [`Conf::add_source`] or [`Conf::with_sources`]; the first source with a
+/// value wins.
+pub struct Conf<S: ConfSource = EnvSource> {
+    /// Config name. Source lookups are derived from this.
+    pub name: &'static str,
+    /// Source for config values. See [`ConfSource`].
+    pub source: S,
+    /// Map of configured [`ConfEntry`] options.
+    pub options: std::collections::BTreeMap<String, Box<dyn AnyConfEntry>>,
+    /// Whether `${key}` references in values are expanded, see [`Conf::with_interpolation`].
+    pub interpolate: bool,
+    /// Whether keys are matched case-insensitively, see [`Conf::case_insensitive`].
+    pub case_insensitive: bool,
+    /// Values set with [`Conf::set_override`] by entry name, used instead of the source.
+    pub overrides: std::collections::BTreeMap<String, String>,
+}
+
+impl<S: ConfSource> Conf<S> {
+    /// Create a new config. Also initializes the [`ConfSource`].
+    pub fn new(name: &'static str) -> Self {
+        Self::with_source(name, S::new(name))
+    }
+
+    /// Create a new config, initializing the [`ConfSource`] with [`ConfSource::try_new`]. Source
+    /// errors are wrapped in [`ConfError::SourceInit`].
+    pub fn try_new(name: &'static str) -> Result<Self> {
+        let source = S::try_new(name).map_err(|e| ConfError::source_init(name, e))?;
+        Ok(Self::with_source(name, source))
+    }
+
+    /// Create a new config with an already initialized [`ConfSource`].
+    pub fn with_source(name: &'static str, source: S) -> Self {
+        Self {
+            source,
+            options: std::collections::BTreeMap::new(),
+            name,
+            interpolate: false,
+            case_insensitive: false,
+            overrides: std::collections::BTreeMap::new(),
+        }
+    }
+
+    /// Add a fallback source, queried only for keys the current source doesn't have. This turns
+    /// the config into a [`ChainedSource`] config, where sources are queried in the order they
+    /// were added and the first value found wins; entry defaults are used only if no source has
+    /// a value. Adding to a config that already has a [`ChainedSource`] appends to that chain.
+    ///
+    /// ```
+    /// use voidconf::{Conf, ConfSource, InMemorySource};
+    ///
+    /// let conf = Conf::default()
+    ///     .add_source(InMemorySource::new("vcfg").set("VCFG_PORT", "8080"))
+    ///     .uint("port", Some(80));
+    /// ```
+    pub fn add_source(self, source: impl ConfSource + 'static) -> Conf<ChainedSource>
+    where
+        S: 'static,
+    {
+        let name = self.name;
+        self.map_source(|current| {
+            let current: Box<dyn std::any::Any> = Box::new(current);
+            let chain = match current.downcast::<ChainedSource>() {
+                Ok(chain) => *chain,
+                Err(current) => match current.downcast::<S>() {
+                    Ok(current) => ChainedSource::with_sources(name, vec![current]),
+                    Err(_) => unreachable!("source is always an S"),
+                },
+            };
+            chain.push(source)
+        })
+    }
+
+    /// Cache source lookups, so each key is only fetched from the source once, see
+    /// [`CachedSource`]. Values are frozen until [`Conf::invalidate`] or [`Conf::clear_cache`].
+    pub fn with_cache(self) -> Conf<CachedSource<S>> {
+        self.map_source(CachedSource::from_source)
+    }
+
+    /// Cache source lookups like [`Conf::with_cache`], re-fetching each value once it is older
+    /// than `ttl`, see [`CachedSource::with_ttl`].
+    pub fn with_cache_ttl(self, ttl: Duration) -> Conf<CachedSource<S>> {
+        self.map_source(|source| CachedSource::from_source(source).with_ttl(ttl))
+    }
+
+    /// Replace the source, keeping the entries.
+    fn map_source<T: ConfSource>(self, f: impl FnOnce(S) -> T) -> Conf<T> {
+        Conf {
+            name: self.name,
+            source: f(self.source),
+            options: self.options,
+            interpolate: self.interpolate,
+            case_insensitive: self.case_insensitive,
+            overrides: self.overrides,
+        }
+    }
+
+    /// Expand `${key}` references in values with the values of other entries, so
+    /// `VCFG_LOG_PATH=${DATA_DIR}/app.log` uses the `data_dir` entry. References are resolved
+    /// recursively and matched to entry keys case-insensitively. Referencing an undefined
+    /// entry is a [`ConfError::KeyNotFound`], and a cycle is a [`ConfError::InterpolationCycle`].
+    pub fn with_interpolation(mut self) -> Self {
+        self.interpolate = true;
+        self
+    }
+
+    /// Match keys case-insensitively, so `get("Port")` finds the `port` entry. Entries are
+    /// stored under their lowercase key, while sources are still queried with the name the entry
+    /// was defined with.
+    ///
+    /// # Panics
+    ///
+    /// If two entries have names differing only in case, e.g. `Port` and `port`, whether defined
+    /// before or after this is called.
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_insensitive = true;
+        let options = std::mem::take(&mut self.options);
+        for (key, option) in options {
+            self.insert_option(&key, option);
+        }
+        self
+    }
+
+    /// Normalize a key for lookup in [`Conf::options`].
+    fn option_key<'a>(&self, key: &'a str) -> std::borrow::Cow<'a, str> {
+        if self.case_insensitive {
+            std::borrow::Cow::Owned(key.to_lowercase())
+        } else {
+            std::borrow::Cow::Borrowed(key)
+        }
+    }
+
+    /// Insert an entry under a normalized key, its name or an alias, replacing any entry with
+    /// the same name.
+    fn insert_option(&mut self, key: &str, option: Box<dyn AnyConfEntry>) {
+        let key = self.option_key(key).into_owned();
+        if let Some(existing) = self.options.get(&key) {
+            assert!(
+                existing.name() == option.name(),
+                "conf entries `{}` and `{}` collide on key `{key}`",
+                existing.name(),
+                option.name()
+            );
+        }
+        self.options.insert(key, option);
+    }
+
+    /// Add all entries from another config, replacing entries defined in both with `other`'s.
+    /// This config's name, source and settings are kept; `other`'s source is dropped.
+    ///
+    /// # Panics
+    ///
+    /// If an entry from `other` collides with a different entry here, e.g. its name is another
+    /// entry's [alias](ConfEntry::alias).
+    pub fn merge<T: ConfSource>(mut self, other: Conf<T>) -> Self {
+        let replaced: std::collections::BTreeSet<String> = other
+            .options
+            .values()
+            .map(|o| o.name().to_string())
+            .collect();
+        self.options.retain(|_, o| !replaced.contains(o.name()));
+        self.overrides.retain(|name, _| !replaced.contains(name));
+        for (key, option) in other.options {
+            self.insert_option(&key, option);
+        }
+        self.overrides.extend(other.overrides);
+        self
+    }
+
+    /// Add all entries from another config like [`Conf::merge`], but fail with
+    /// [`ConfError::KeyConflict`] if any key, including an alias, is defined in both.
+    pub fn try_merge<T: ConfSource>(self, other: Conf<T>) -> Result<Self> {
+        if let Some(key) = other
+            .options
+            .keys()
+            .find(|key| self.options.contains_key(&*self.option_key(key)))
+        {
+            return Err(ConfError::key_conflict(key));
+        }
+        Ok(self.merge(other))
+    }
+
+    /// Iterate over defined entries by key, skipping [aliases](ConfEntry::alias).
+    fn entries(&self) -> impl Iterator<Item = (&String, &Box<dyn AnyConfEntry>)> {
+        self.options
+            .iter()
+            .filter(|(key, option)| *self.option_key(option.name()) == **key)
+    }
+
+    /// Look up an entry's unparsed value, from its [override](Conf::set_override) if set or
+    /// else the source, falling back to the default.
+    fn raw(&self, option: &dyn AnyConfEntry) -> Result<Option<String>> {
+        Ok(self.raw_with_origin(option)?.map(|(v, _)| v))
+    }
+
+    /// Look up an entry's unparsed value like [`Conf::raw`], along with where it came from.
+    fn raw_with_origin(&self, option: &dyn AnyConfEntry) -> Result<Option<(String, ValueOrigin)>> {
+        match self.overrides.get(option.name()) {
+            Some(v) => Ok(Some((v.clone(), ValueOrigin::Override))),
+            None => option.raw_with_origin(&self.source),
+        }
+    }
+
+    /// Whether an entry resolves to a value, checking that it parses.
+    fn is_present(&self, option: &dyn AnyConfEntry) -> Result<bool> {
+        match self.overrides.get(option.name()) {
+            Some(_) => Ok(true),
+            None => option.is_present(&self.source),
+        }
+    }
+
+    /// Describe every defined entry, sorted by key.
+    pub fn describe(&self) -> Vec<ConfOptionInfo> {
+        self.entries()
+            .map(|(key, option)| ConfOptionInfo {
+                key: key.clone(),
+                type_name: option.type_name().to_string(),
+                default: option.default_val().map(String::from),
+                required: option.is_required(),
+                description: option.description().map(String::from),
+            })
+            .collect()
+    }
+
+    /// Render a `.env` template for all defined entries, sorted by key. Vars are named like
+    /// [`Conf::export_env`], by the source's [`ConfSource::env_var`] or else the default
+    /// [`EnvSource`] translation. Each entry is written as `VCFG_KEY=default`, or with a blank
+    /// value if there's no default, preceded by its description as a comment. Required entries
+    /// without a default are marked `# REQUIRED`.
+    pub fn to_env_template(&self) -> String {
+        let keys = EnvSource::new(self.name);
+        self.entries()
+            .map(|(key, option)| {
+                let mut block = String::new();
+                for line in option.description().into_iter().flat_map(str::lines) {
+                    block.push_str(&format!("# {line}\n"));
+                }
+                let default = option.default_val();
+                if option.is_required() && default.is_none() {
+                    block.push_str("# REQUIRED\n");
+                }
+                let var = self
+                    .source
+                    .env_var(key)
+                    .unwrap_or_else(|| keys.env_key(key));
+                block.push_str(&format!("{var}={}\n", default.unwrap_or_default()));
+                block
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Resolve every entry like [`Conf::snapshot`] and render the values as shell `export`
+    /// statements, sorted by key. Vars are named by the source's [`ConfSource::env_var`], so an
+    /// [`EnvSource`] with a custom case, separator or [`KeyMapper`] reads them back, or else
+    /// with the default [`EnvSource`] translation. Values are single quoted where the shell would
+    /// otherwise split or expand them. Entries with no value
+    /// are left out, and [secret](ConfEntry#structfield.secret) values are written as
+    /// [`REDACTED`] unless `include_secrets` is set.
+    ///
+    /// ```
+    /// use voidconf::{Conf, ConfError, ConfSource, InMemorySource};
+    ///
+    /// let source = InMemorySource::new("vcfg").set("VCFG_GREETING", "Hello, world");
+    /// let conf = Conf::with_source("vcfg", source)
+    ///     .string("greeting", None)
+    ///     .uint("port", Some(8080));
+    /// assert_eq!(
+    ///     conf.export_env(false)?,
+    ///     "export VCFG_GREETING='Hello, world'\nexport VCFG_PORT=8080\n"
+    /// );
+    /// # Ok::<(), ConfError>(())
+    /// ```
+    pub fn export_env(&self, include_secrets: bool) -> Result<String> {
+        let resolved = self.snapshot()?;
+        let keys = EnvSource::new(self.name);
+        let mut out = String::new();
+        for (key, option) in self.entries() {
+            let Some(val) = resolved.values.get(key) else {
+                continue;
+            };
+            let val = match option.is_secret() && !include_secrets {
+                true => REDACTED,
+                false => val,
+            };
+            let var = self
+                .source
+                .env_var(key)
+                .unwrap_or_else(|| keys.env_key(key));
+            out.push_str(&format!("export {var}={}\n", shell_quote(val)));
+        }
+        Ok(out)
+    }
+
+    /// Expand `${key}` references in a raw value. `visiting` holds the keys currently being
+    /// resolved, to detect cycles.
+    fn interpolate(&self, raw: String, visiting: &mut Vec<String>) -> Result<String> {
+        let mut out = String::new();
+        let mut rest = raw.as_str();
+        while let Some(start) = rest.find("${") {
+            let Some(len) = rest[start + 2..].find('}') else {
+                break;
+            };
+            out.push_str(&rest[..start]);
+            let reference = &rest[start + 2..start + 2 + len];
+            let key = if self.options.contains_key(&*self.option_key(reference)) {
+                self.option_key(reference).into_owned()
+            } else {
+                reference.to_ascii_lowercase()
+            };
+            if visiting.contains(&key) {
+                return Err(ConfError::interpolation_cycle(key));
+            }
+            let option = self
+                .options
+                .get(&key)
+                .ok_or_else(|| ConfError::key_not_found(&key))?;
+            let val = self
+                .raw(option.as_ref())?
+                .ok_or_else(|| ConfError::val_not_found(&key))?;
+            visiting.push(key);
+            out.push_str(&self.interpolate(val, visiting)?);
+            visiting.pop();
+            rest = &rest[start + 3 + len..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// Write a value through to the source, e.g. to persist a changed preference. The value is
+    /// serialized with [`ConfValue::to_raw`] and must parse as the entry's type, passing its
+    /// bounds and validator. An undefined key is a [`ConfError::KeyNotFound`].
+    pub fn set<V: ConfValue>(&mut self, key: &str, value: V) -> Result
+    where
+        S: ConfSink,
+    {
+        let option = self
+            .options
+            .get(&*self.option_key(key))
+            .ok_or_else(|| ConfError::key_not_found(key))?;
+        let val = value.to_raw();
+        option.check(val.clone())?;
+        let name = option.name().to_string();
+        self.source.set(&name, &val)
+    }
+
+    /// Force a value for an entry regardless of the source or default, e.g. in tests or to apply
+    /// a computed setting, without touching the process environment. The value is serialized
+    /// with [`ConfValue::to_raw`] and must parse as the entry's type, passing its bounds and
+    /// validator. An undefined key is a [`ConfError::KeyNotFound`].
+    ///
+    /// ```
+    /// use voidconf::{Conf, ConfError};
+    ///
+    /// let mut conf = Conf::default().uint("port", Some(8080));
+    /// conf.set_override("port", 9090)?;
+    /// assert_eq!(conf.get_uint("port")?, Some(9090));
+    /// conf.clear_override("port");
+    /// assert_eq!(conf.get_uint("port")?, Some(8080));
+    /// # Ok::<(), ConfError>(())
+    /// ```
+    pub fn set_override<V: ConfValue>(&mut self, key: &str, value: V) -> Result {
+        let option = self
+            .options
+            .get(&*self.option_key(key))
+            .ok_or_else(|| ConfError::key_not_found(key))?;
+        let val = value.to_raw();
+        option.check(val.clone())?;
+        let name = option.name().to_string();
+        self.overrides.insert(name, val);
+        Ok(())
+    }
+
+    /// Remove an [override](Conf::set_override), so the entry resolves from the source again.
+    /// Does nothing if the key has no override.
+    pub fn clear_override(&mut self, key: &str) {
+        if let Some(option) = self.options.get(&*self.option_key(key)) {
+            let name = option.name().to_string();
+            self.overrides.remove(&name);
+        }
+    }
+
+    /// List the keys the source has values for, see [`ConfSource::keys`]. Compare with
+    /// [`Conf::keys`] to find settings that aren't defined as entries, such as typos.
+    pub fn source_keys(&self) -> Result<Vec<String>> {
+        self.source.keys()
+    }
+
+    /// Whether an entry with the given key is defined.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.options.contains_key(&*self.option_key(key))
+    }
+
+    /// Iterate over the keys of all defined entries, in sorted order. Aliases are not included.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries().map(|(key, _)| key.as_str())
+    }
+
+    /// Number of defined entries, not counting aliases.
+    pub fn len(&self) -> usize {
+        self.entries().count()
+    }
+
+    /// Whether no entries are defined.
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    /// Add a new [`ConfEntry`]. This is a lower-level function for custom [`ConfValue`] types;
+    /// where possible the typed functions such as [`Conf::string`] are preferred.
+    pub fn entry<V: ConfValue + Send + Sync + 'static>(mut self, entry: ConfEntry<V>) -> Self {
+        for alias in &entry.aliases {
+            self.insert_option(alias, Box::new(entry.clone()));
+        }
+        self.insert_option(&entry.name.clone(), Box::new(entry));
+        self
+    }
+
+    /// Add a new [`ConfEntry`], marked as [required](ConfEntry::required).
+    pub fn require_entry<V: ConfValue + Send + Sync + 'static>(self, entry: ConfEntry<V>) -> Self {
+        self.entry(entry.required())
+    }
+
+    /// Add a string entry.
+    pub fn string(self, name: impl Into<String>, default: Option<&str>) -> Self {
+        let entry: ConfEntry<String> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d)),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a secret string entry, see [`Secret`]. Errors don't include its value.
+    pub fn secret(self, name: impl Into<String>, default: Option<&str>) -> Self {
+        let entry: ConfEntry<Secret<String>> = ConfEntry::new(name).secret();
+        match default {
+            Some(d) => self.entry(entry.with_default(d)),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a byte (`u8`) entry.
+    pub fn byte(self, name: impl Into<String>, default: Option<u8>) -> Self {
+        let entry: ConfEntry<u8> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add an int (`i64`) entry.
+    pub fn int(self, name: impl Into<String>, default: Option<i64>) -> Self {
+        let entry: ConfEntry<i64> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add an int (`i64`) entry which rejects values outside `range` with
+    /// [`ConfError::OutOfRange`]. For other types, use [`ConfEntry::min`] and [`ConfEntry::max`].
+    pub fn int_range(
+        self,
+        name: impl Into<String>,
+        default: Option<i64>,
+        range: RangeInclusive<i64>,
+    ) -> Self {
+        let entry: ConfEntry<i64> = ConfEntry::new(name).min(*range.start()).max(*range.end());
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a uint (`u64`) entry.
+    pub fn uint(self, name: impl Into<String>, default: Option<u64>) -> Self {
+        let entry: ConfEntry<u64> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a big int (`i128`) entry.
+    pub fn big_int(self, name: impl Into<String>, default: Option<i128>) -> Self {
+        let entry: ConfEntry<i128> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a big uint (`u128`) entry.
+    pub fn big_uint(self, name: impl Into<String>, default: Option<u128>) -> Self {
+        let entry: ConfEntry<u128> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a size (`usize`) entry.
+    pub fn size(self, name: impl Into<String>, default: Option<usize>) -> Self {
+        let entry: ConfEntry<usize> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a float (`f64`) entry.
+    pub fn float(self, name: impl Into<String>, default: Option<f64>) -> Self {
+        let entry: ConfEntry<f64> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a float (`f64`) entry which rejects `NaN` and infinite values, see [`FiniteFloat`].
+    pub fn finite_float(self, name: impl Into<String>, default: Option<f64>) -> Self {
+        let entry: ConfEntry<FiniteFloat> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a bool entry. Values are parsed leniently, see [`ConfBool`].
+    pub fn boolean(self, name: impl Into<String>, default: Option<bool>) -> Self {
+        let entry: ConfEntry<ConfBool> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a path entry. Empty values are rejected, see [`ConfPath`].
+    pub fn path(self, name: impl Into<String>, default: Option<&Path>) -> Self {
+        let entry: ConfEntry<ConfPath> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.display().to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a socket address entry, like `0.0.0.0:8080` or `[::1]:9000`. The port is required.
+    pub fn socket_addr(self, name: impl Into<String>, default: Option<SocketAddr>) -> Self {
+        let entry: ConfEntry<SocketAddr> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a UTC timestamp entry in RFC 3339 form, like `2024-01-02T03:04:05Z`. Other offsets,
+    /// like `+02:00`, are converted to UTC.
+    #[cfg(feature = "chrono")]
+    pub fn datetime(
+        self,
+        name: impl Into<String>,
+        default: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Self {
+        let entry: ConfEntry<chrono::DateTime<chrono::Utc>> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_rfc3339())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a URL entry, like `https://api.example.com/v1`. Relative URLs are rejected.
+    #[cfg(feature = "url")]
+    pub fn url(self, name: impl Into<String>, default: Option<url::Url>) -> Self {
+        let entry: ConfEntry<url::Url> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a UUID entry, like `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    #[cfg(feature = "uuid")]
+    pub fn uuid(self, name: impl Into<String>, default: Option<uuid::Uuid>) -> Self {
+        let entry: ConfEntry<uuid::Uuid> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(d.to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a duration entry, like `500ms` or `5m`. See [`ConfDuration`].
+    pub fn duration(self, name: impl Into<String>, default: Option<Duration>) -> Self {
+        let entry: ConfEntry<ConfDuration> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(ConfDuration(d).to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a comma separated list entry. For other delimiters, add a [`ConfList`] entry directly.
+    pub fn list<V: ConfValue + Send + Sync + 'static>(
+        self,
+        name: impl Into<String>,
+        default: Option<&[V]>,
+    ) -> Self {
+        let entry: ConfEntry<ConfList<V>> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(ConfList::<V>(d.to_vec()).to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Add a map entry of comma separated `key=value` pairs, such as `free=10,pro=100`. For other
+    /// delimiters, add a [`ConfMap`] entry directly.
+    pub fn map<K, V>(self, name: impl Into<String>, default: Option<&HashMap<K, V>>) -> Self
+    where
+        K: ConfValue + Eq + Hash + Send + Sync + 'static,
+        V: ConfValue + Send + Sync + 'static,
+    {
+        let entry: ConfEntry<ConfMap<K, V>> = ConfEntry::new(name);
+        match default {
+            Some(d) => self.entry(entry.with_default(ConfMap::<K, V>(d.clone()).to_string())),
+            None => self.entry(entry),
+        }
+    }
+
+    /// Get a value. An error will be thrown if the value cannot parse into the type expected
+    /// by the configured entry.
+    pub fn get<V: ConfValue + 'static>(&self, key: &str) -> Result<Option<V>> {
+        Ok(self.get_with_source(key)?.map(|(v, _)| v))
+    }
+
+    /// Get a value like [`Conf::get`], along with whether it came from the source, the entry
+    /// default or an [override](Conf::set_override), to trace why a setting has the value it
+    /// does.
+    ///
+    /// ```
+    /// use voidconf::{Conf, ConfError, ValueOrigin};
+    ///
+    /// let conf = Conf::default().uint("origin_port", Some(8080));
+    /// assert_eq!(
+    ///     conf.get_with_source::<u64>("origin_port")?,
+    ///     Some((8080, ValueOrigin::Default))
+    /// );
+    /// # Ok::<(), ConfError>(())
+    /// ```
+    pub fn get_with_source<V: ConfValue + 'static>(
+        &self,
+        key: &str,
+    ) -> Result<Option<(V, ValueOrigin)>> {
+        self.lookup(key, ValueOrigin::Override, |entry: &ConfEntry<V>| {
+            entry.raw_with_origin(&self.source)
+        })?
+        .map(|(entry, v, origin)| Ok((entry.parse(v)?, origin)))
+        .transpose()
+    }
+
+    /// Get a value like [`Conf::get_with_source`], with the source's
+    /// [description](ConfSource::describe) of where it came from, such as the env var or file.
+    /// Parse errors for values from the source name it too.
+    ///
+    /// ```
+    /// use voidconf::{Conf, ConfError, ConfSource, InMemorySource, Origin};
+    ///
+    /// let source = InMemorySource::new("vcfg").set("VCFG_PORT", "9090");
+    /// let conf = Conf::with_source("vcfg", source).uint("port", Some(8080));
+    /// let (port, origin) = conf.get_with_origin::<u64>("port")?.unwrap();
+    /// assert_eq!(port, 9090);
+    /// assert_eq!(origin.to_string(), "in-memory var VCFG_PORT");
+    /// # Ok::<(), ConfError>(())
+    /// ```
+    pub fn get_with_origin<V: ConfValue + 'static>(
+        &self,
+        key: &str,
+    ) -> Result<Option<(V, Origin)>> {
+        self.lookup(key, Origin::Override, |entry: &ConfEntry<V>| {
+            entry.raw_described(&self.source)
+        })?
+        .map(|(entry, v, origin)| {
+            let v = match &origin {
+                Origin::Source { detail } => entry.parse(v).map_err(|e| e.with_origin(detail)),
+                _ => entry.parse(v),
+            };
+            Ok((v?, origin))
+        })
+        .transpose()
+    }
+
+    /// Find a typed entry and look up its unparsed, interpolated value and where it came from:
+    /// `overridden` for an [override](Conf::set_override), or else as `raw` finds it.
+    fn lookup<V: ConfValue + 'static, O>(
+        &self,
+        key: &str,
+        overridden: O,
+        raw: impl FnOnce(&ConfEntry<V>) -> Result<Option<(String, O)>>,
+    ) -> Result<Option<Lookup<'_, V, O>>> {
+        let option_key = self.option_key(key);
+        let option = self
+            .options
+            .get(&*option_key)
+            .ok_or_else(|| ConfError::key_not_found(key))?;
+        let entry = option
+            .as_any()
+            .downcast_ref::<ConfEntry<V>>()
+            .ok_or_else(|| {
+                ConfError::val_parse_failed(
+                    key,
+                    "",
+                    format!("entry is not of type {}", std::any::type_name::<V>()),
+                )
+            })?;
+        let raw = match self.overrides.get(&entry.name) {
+            Some(v) => Some((v.clone(), overridden)),
+            None => raw(entry)?,
+        };
+        raw.map(|(v, origin)| {
+            let v = if self.interpolate {
+                self.interpolate(v, &mut vec![option_key.to_string()])?
+            } else {
+                v
+            };
+            Ok((entry, v, origin))
+        })
+        .transpose()
+    }
+
+    /// Get a value, or the given fallback when neither the source nor the entry default has one.
+    /// Errors, including parse errors, are still returned rather than replaced by the fallback.
+    pub fn get_or<V: ConfValue + 'static>(&self, key: &str, default: V) -> Result<V> {
+        self.get(key).map(|v| v.unwrap_or(default))
+    }
+
+    /// Get a value, or compute a fallback when neither the source nor the entry default has one.
+    /// See [`Conf::get_or`].
+    pub fn get_or_else<V: ConfValue + 'static, F: FnOnce() -> V>(
+        &self,
+        key: &str,
+        default: F,
+    ) -> Result<V> {
+        self.get(key).map(|v| v.unwrap_or_else(default))
+    }
+
+    /// Get a string value.
+    pub fn get_string(&self, key: &str) -> Result<Option<String>> {
+        self.get::<String>(key)
+    }
+
+    /// Get a secret string value.
+    pub fn get_secret(&self, key: &str) -> Result<Option<Secret<String>>> {
+        self.get::<Secret<String>>(key)
+    }
+
+    /// Get a byte (`u8`) value.
+    pub fn get_byte(&self, key: &str) -> Result<Option<u8>> {
+        self.get::<u8>(key)
+    }
+
+    /// Get an int (`i64`) value.
+    pub fn get_int(&self, key: &str) -> Result<Option<i64>> {
+        self.get::<i64>(key)
+    }
+
+    /// Get a uint (`u64`) value.
+    pub fn get_uint(&self, key: &str) -> Result<Option<u64>> {
+        self.get::<u64>(key)
+    }
+
+    /// Get a big int (`i128`) value.
+    pub fn get_big_int(&self, key: &str) -> Result<Option<i128>> {
+        self.get::<i128>(key)
+    }
+
+    /// Get a big uint (`u128`) value.
+    pub fn get_big_uint(&self, key: &str) -> Result<Option<u128>> {
+        self.get::<u128>(key)
+    }
+
+    /// Get a size (`usize`) value.
+    pub fn get_size(&self, key: &str) -> Result<Option<usize>> {
+        self.get::<usize>(key)
+    }
+
+    /// Get a float (`f64`) value.
+    pub fn get_float(&self, key: &str) -> Result<Option<f64>> {
+        self.get::<f64>(key)
+    }
+
+    /// Get a finite float (`f64`) value added with [`Conf::finite_float`].
+    pub fn get_finite_float(&self, key: &str) -> Result<Option<f64>> {
+        self.get::<FiniteFloat>(key).map(|v| v.map(f64::from))
+    }
+
+    /// Get a bool value.
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>> {
+        self.get::<ConfBool>(key).map(|v| v.map(bool::from))
+    }
+
+    /// Get a path value as configured.
+    pub fn get_path(&self, key: &str) -> Result<Option<PathBuf>> {
+        self.get::<ConfPath>(key).map(|v| v.map(PathBuf::from))
+    }
+
+    /// Get a path value with a leading `~` expanded and relative paths resolved against the
+    /// current working directory, see [`ConfPath::normalize`].
+    pub fn get_path_canonical(&self, key: &str) -> Result<Option<PathBuf>> {
+        self.get::<ConfPath>(key)?
+            .map(|path| {
+                path.normalize()
+                    .map_err(|e| ConfError::val_parse_failed(key, path.to_string(), e.to_string()))
+            })
+            .transpose()
+    }
+
+    /// Get a socket address value.
+    pub fn get_socket_addr(&self, key: &str) -> Result<Option<SocketAddr>> {
+        self.get::<SocketAddr>(key)
+    }
+
+    /// Get a UTC timestamp value.
+    #[cfg(feature = "chrono")]
+    pub fn get_datetime(&self, key: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        self.get::<chrono::DateTime<chrono::Utc>>(key)
+    }
+
+    /// Get a URL value.
+    #[cfg(feature = "url")]
+    pub fn get_url(&self, key: &str) -> Result<Option<url::Url>> {
+        self.get::<url::Url>(key)
+    }
+
+    /// Get a UUID value.
+    #[cfg(feature = "uuid")]
+    pub fn get_uuid(&self, key: &str) -> Result<Option<uuid::Uuid>> {
+        self.get::<uuid::Uuid>(key)
+    }
+
+    /// Get a duration value.
+    pub fn get_duration(&self, key: &str) -> Result<Option<Duration>> {
+        self.get::<ConfDuration>(key).map(|v| v.map(Duration::from))
+    }
+
+    /// Get a comma separated list value. An empty value is an empty list, not `None`.
+    pub fn get_list<V: ConfValue + 'static>(&self, key: &str) -> Result<Option<Vec<V>>> {
+        self.get::<ConfList<V>>(key).map(|v| v.map(Vec::from))
+    }
+
+    /// Get a map value of comma separated `key=value` pairs. An empty value is an empty map, not
+    /// `None`.
+    pub fn get_map<K, V>(&self, key: &str) -> Result<Option<HashMap<K, V>>>
+    where
+        K: ConfValue + Eq + Hash + 'static,
+        V: ConfValue + 'static,
+    {
+        self.get::<ConfMap<K, V>>(key).map(|v| v.map(HashMap::from))
+    }
+
+    /// Require a value. Similar to [`Conf::get`] except a `None` return value
+    /// is treated as an error.
+    pub fn require<V: ConfValue + 'static>(&self, key: &str) -> Result<V> {
+        self.get(key).transpose().ok_or_else(|| {
+            let description = self
+                .options
+                .get(&*self.option_key(key))
+                .and_then(|o| o.description());
+            ConfError::val_not_found(key).with_description(description.map(String::from))
+        })?
+    }
+
+    /// Require a string value.
+    pub fn require_string(&self, key: &str) -> Result<String> {
+        self.require::<String>(key)
+    }
+
+    /// Require a secret string value.
+    pub fn require_secret(&self, key: &str) -> Result<Secret<String>> {
+        self.require::<Secret<String>>(key)
+    }
+
+    /// Require a byte (`u8`) value.
+    pub fn require_byte(&self, key: &str) -> Result<u8> {
+        self.require::<u8>(key)
+    }
+
+    /// Require an int (`i64`) value.
+    pub fn require_int(&self, key: &str) -> Result<i64> {
+        self.require::<i64>(key)
+    }
+
+    /// Require a uint (`u64`) value.
+    pub fn require_uint(&self, key: &str) -> Result<u64> {
+        self.require::<u64>(key)
+    }
+
+    /// Require a big int (`i128`) value.
+    pub fn require_big_int(&self, key: &str) -> Result<i128> {
+        self.require::<i128>(key)
+    }
+
+    /// Require a big uint (`u128`) value.
+    pub fn require_big_uint(&self, key: &str) -> Result<u128> {
+        self.require::<u128>(key)
+    }
+
+    /// Require a size (`usize`) value.
+    pub fn require_size(&self, key: &str) -> Result<usize> {
+        self.require::<usize>(key)
+    }
+
+    /// Require a float (`f64`) value.
+    pub fn require_float(&self, key: &str) -> Result<f64> {
+        self.require::<f64>(key)
+    }
+
+    /// Require a finite float (`f64`) value added with [`Conf::finite_float`].
+    pub fn require_finite_float(&self, key: &str) -> Result<f64> {
+        self.require::<FiniteFloat>(key).map(f64::from)
+    }
+
+    /// Require a bool value.
+    pub fn require_bool(&self, key: &str) -> Result<bool> {
+        self.require::<ConfBool>(key).map(bool::from)
+    }
+
+    /// Require a path value as configured.
+    pub fn require_path(&self, key: &str) -> Result<PathBuf> {
+        self.require::<ConfPath>(key).map(PathBuf::from)
+    }
+
+    /// Require a socket address value.
+    pub fn require_socket_addr(&self, key: &str) -> Result<SocketAddr> {
+        self.require::<SocketAddr>(key)
+    }
+
+    /// Require a UTC timestamp value.
+    #[cfg(feature = "chrono")]
+    pub fn require_datetime(&self, key: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+        self.require::<chrono::DateTime<chrono::Utc>>(key)
+    }
+
+    /// Require a URL value.
+    #[cfg(feature = "url")]
+    pub fn require_url(&self, key: &str) -> Result<url::Url> {
+        self.require::<url::Url>(key)
+    }
+
+    /// Require a UUID value.
+    #[cfg(feature = "uuid")]
+    pub fn require_uuid(&self, key: &str) -> Result<uuid::Uuid> {
+        self.require::<uuid::Uuid>(key)
+    }
+
+    /// Require a duration value.
+    pub fn require_duration(&self, key: &str) -> Result<Duration> {
+        self.require::<ConfDuration>(key).map(Duration::from)
+    }
+
+    /// Require a comma separated list value.
+    pub fn require_list<V: ConfValue + 'static>(&self, key: &str) -> Result<Vec<V>> {
+        self.require::<ConfList<V>>(key).map(Vec::from)
+    }
+
+    /// Require a map value of comma separated `key=value` pairs.
+    pub fn require_map<K, V>(&self, key: &str) -> Result<HashMap<K, V>>
+    where
+        K: ConfValue + Eq + Hash + 'static,
+        V: ConfValue + 'static,
+    {
+        self.require::<ConfMap<K, V>>(key).map(HashMap::from)
+    }
+
+    /// Resolve every entry once, applying defaults, into a [`ResolvedConf`] that no longer reads
+    /// from the source. Any parse error is returned, and all missing
+    /// [required](ConfEntry::required) keys are reported together in
+    /// [`ConfError::ValidationFailed`].
+    pub fn snapshot(&self) -> Result<ResolvedConf> {
+        let mut resolved = ResolvedConf::default();
+        let mut missing = Vec::new();
+        for (key, option) in self.entries() {
+            resolved.types.insert(key.clone(), option.type_name());
+            let raw = match self.raw(option.as_ref())? {
+                Some(v) if self.interpolate => Some(self.interpolate(v, &mut vec![key.clone()])?),
+                raw => raw,
+            };
+            match raw {
+                Some(v) => {
+                    option.check(v.clone())?;
+                    resolved.values.insert(key.clone(), v);
+                }
+                None if option.is_required() => missing.push(key.clone()),
+                None => {}
+            }
+        }
+        if missing.is_empty() {
+            Ok(resolved)
+        } else {
+            Err(ConfError::validation_failed(missing))
+        }
+    }
+
+    /// Resolve every entry like [`Conf::snapshot`] and deserialize the values into a struct.
+    /// Each value is parsed as its entry's type first, so e.g. a `u16` field gets a number
+    /// rather than a string. Dotted keys like `db.host` become nested structs. Keys with no
+    /// value are left out, so they can fill `Option` or `#[serde(default)]` fields.
+    ///
+    /// Missing [required](ConfEntry::required) keys are reported in
+    /// [`ConfError::ValidationFailed`]. Values that don't fit `T`, such as a missing field
+    /// with no entry marked required, fail with [`ConfError::DeserializeFailed`].
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use voidconf::{Conf, ConfError, ConfSource, InMemorySource};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Settings {
+    ///     port: u16,
+    ///     name: String,
+    /// }
+    ///
+    /// let source = InMemorySource::new("vcfg").set("VCFG_NAME", "xela");
+    /// let conf = Conf::with_source("vcfg", source)
+    ///     .uint("port", Some(8080))
+    ///     .string("name", None);
+    /// let settings: Settings = conf.deserialize()?;
+    /// assert_eq!(settings.port, 8080);
+    /// assert_eq!(settings.name, "xela");
+    /// # Ok::<(), ConfError>(())
+    /// ```
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T> {
+        let resolved = self.snapshot()?;
+        let mut root = serde_json::Map::new();
+        for (key, raw) in resolved.values {
+            let val = self.options[&key].to_json(raw)?;
+            let mut parts = key.split('.').peekable();
+            let mut map = &mut root;
+            while let Some(part) = parts.next() {
+                if parts.peek().is_none() {
+                    map.insert(part.to_string(), val);
+                    break;
+                }
+                let next = map
+                    .entry(part)
+                    .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+                map = next
+                    .as_object_mut()
+                    .ok_or_else(|| ConfError::key_path_invalid(&key, part))?;
+            }
+        }
+        serde_json::from_value(serde_json::Value::Object(root))
+            .map_err(|e| ConfError::deserialize_failed(e.to_string()))
+    }
+
+    /// Check that every [required](ConfEntry::required) entry resolves to a value, either from
+    /// the source or its default. All missing keys are reported together in
+    /// [`ConfError::ValidationFailed`], so this is useful for failing fast at startup.
+    pub fn validate(&self) -> Result {
+        let mut missing = Vec::new();
+        for (key, option) in self.entries() {
+            if option.is_required() && !self.is_present(option.as_ref())? {
+                missing.push(key.clone());
+            }
+        }
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfError::validation_failed(missing))
+        }
+    }
+
+    /// Check that each of the given keys resolves to a value, like [`Conf::validate`] but for
+    /// an explicit list rather than the [required](ConfEntry::required) entries. All missing
+    /// keys are reported together in [`ConfError::ValidationFailed`]. An undefined key is a
+    /// [`ConfError::KeyNotFound`].
+    pub fn require_all(&self, keys: &[&str]) -> Result {
+        let mut missing = Vec::new();
+        for key in keys {
+            let option = self
+                .options
+                .get(&*self.option_key(key))
+                .ok_or_else(|| ConfError::key_not_found(*key))?;
+            if !self.is_present(option.as_ref())? {
+                missing.push(key.to_string());
+            }
+        }
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfError::validation_failed(missing))
+        }
+    }
+}
+
+/// Fully resolved values of a [`Conf`], from [`Conf::snapshot`]. Values are stored in string
+/// form, already validated, and parsed again on each [`ResolvedConf::get`].
+#[derive(Clone, Default)]
+pub struct ResolvedConf {
+    /// Resolved values in string form. Keys with no value are absent.
+    pub values: std::collections::BTreeMap<String, String>,
+    /// Value type name of every entry, from [`std::any::type_name`].
+    pub types: std::collections::BTreeMap<String, &'static str>,
+}
+
+impl std::fmt::Debug for ResolvedConf {
+    /// Values are left out, since they may be secret.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResolvedConf")
+            .field("types", &self.types)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResolvedConf {
+    /// Get a value. An error will be thrown if the entry is undefined or of a different type.
+    pub fn get<V: ConfValue + 'static>(&self, key: &str) -> Result<Option<V>> {
+        let type_name = self
+            .types
+            .get(key)
+            .ok_or_else(|| ConfError::key_not_found(key))?;
+        if *type_name != std::any::type_name::<V>() {
+            return Err(ConfError::val_parse_failed(
+                key,
+                "",
+                format!("entry is not of type {}", std::any::type_name::<V>()),
+            ));
+        }
+        self.values
+            .get(key)
+            .map(|v| {
+                v.parse::<V>()
+                    .map_err(|e| ConfError::val_parse_failed(key, v, e.to_string()))
+            })
+            .transpose()
+    }
+
+    /// Require a value. An error will be thrown if it's missing.
+    pub fn require<V: ConfValue + 'static>(&self, key: &str) -> Result<V> {
+        self.get(key)?.ok_or_else(|| ConfError::val_not_found(key))
+    }
+
+    /// Iterate over the keys of all defined entries, in sorted order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.types.keys().map(String::as_str)
+    }
+}
+
+impl<S: ConfSource> Conf<CachedSource<S>> {
+    /// Forget the cached value for a key, so the next lookup reads the source again.
+    pub fn invalidate(&self, key: &str) {
+        self.source.invalidate(key);
+    }
+
+    /// Forget all cached values, e.g. to reload config.
+    pub fn clear_cache(&self) {
+        self.source.invalidate_all();
+    }
+}
+
+impl Conf<Box<dyn ConfSource>> {
+    /// Create a new config with a source chosen at runtime, e.g. from an env var at startup.
+    ///
+    /// ```
+    /// use voidconf::{Conf, ConfSource, EnvSource, JsonSource};
+    ///
+    /// let source: Box<dyn ConfSource> = match std::env::var("VCFG_SOURCE").as_deref() {
+    ///     Ok("json") => Box::new(JsonSource::new("vcfg")),
+    ///     _ => Box::new(EnvSource::new("vcfg")),
+    /// };
+    /// let conf = Conf::with_boxed_source("vcfg", source).uint("port", Some(8080));
+    /// ```
+    pub fn with_boxed_source(name: &'static str, source: Box<dyn ConfSource>) -> Self {
+        Self::with_source(name, source)
+    }
+}
+
+impl Conf<ChainedSource> {
+    /// Create a new config querying several sources in priority order, highest first. See
+    /// [`Conf::add_source`].
+    pub fn with_sources(name: &'static str, sources: Vec<Box<dyn ConfSource>>) -> Self {
+        Self::with_source(name, ChainedSource::with_sources(name, sources))
+    }
+}
+
+impl Default for Conf {
+    /// Create the default [`Conf`] with [`DEFAULT_NAME`].
+    fn default() -> Self {
+        Self::new(DEFAULT_NAME)
     }
 }
 
-impl Default for Conf {
-    /// Create the default [`Conf`] with [`DEFAULT_NAME`].
-    fn default() -> Self {
-        Self::new(DEFAULT_NAME)
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// An [`EnvSource`] named [`DEFAULT_NAME`] reading from the given vars rather than the
+    /// environment, so tests don't race on shared vars.
+    fn env_snapshot(vars: &[(&str, &str)]) -> EnvSource {
+        EnvSource::new(DEFAULT_NAME).with_vars(
+            vars.iter()
+                .map(|(k, v)| (k.to_string(), v.into()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    pub fn get_boxed_source() {
+        for (choice, port) in [("env", Some(8080)), ("memory", Some(9090)), ("none", None)] {
+            let env = env_snapshot(&[("VCFG_SOURCE", choice), ("VCFG_PORT", "8080")]);
+            let source: Box<dyn ConfSource> = match env.get("source").unwrap().as_deref() {
+                Some("env") => Box::new(env),
+                Some("memory") => {
+                    Box::new(InMemorySource::new(DEFAULT_NAME).set("VCFG_PORT", "9090"))
+                }
+                _ => Box::new(InMemorySource::new(DEFAULT_NAME)),
+            };
+            let conf = Conf::with_boxed_source(DEFAULT_NAME, source).uint("port", None);
+            assert_eq!(conf.get_uint("port").unwrap(), port);
+        }
+    }
+
+    #[test]
+    pub fn get_alias() {
+        let conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[("VCFG_OLD_NAME", "xela")]))
+            .entry(
+                ConfEntry::<String>::new("new_name")
+                    .alias("older_name")
+                    .alias("old_name"),
+            );
+        assert_eq!(
+            conf.get_string("new_name").unwrap(),
+            Some("xela".to_string())
+        );
+        assert_eq!(
+            conf.get_string("old_name").unwrap(),
+            Some("xela".to_string())
+        );
+        assert_eq!(conf.keys().collect::<Vec<_>>(), ["new_name"]);
+
+        let conf = Conf::with_source(
+            DEFAULT_NAME,
+            env_snapshot(&[("VCFG_OLD_NAME", "xela"), ("VCFG_NEW_NAME", "alex")]),
+        )
+        .entry(ConfEntry::<String>::new("new_name").alias("old_name"));
+        assert_eq!(
+            conf.get_string("new_name").unwrap(),
+            Some("alex".to_string())
+        );
+    }
+
+    #[test]
+    pub fn merge_override() {
+        let base = || {
+            Conf::with_source(DEFAULT_NAME, env_snapshot(&[]))
+                .string("host", Some("localhost"))
+                .uint("port", Some(80))
+        };
+        let conf = base().merge(Conf::default().uint("port", Some(8080)));
+        assert_eq!(conf.keys().collect::<Vec<_>>(), ["host", "port"]);
+        assert_eq!(
+            conf.get_string("host").unwrap(),
+            Some("localhost".to_string())
+        );
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(
+            base()
+                .try_merge(Conf::default().uint("port", Some(8080)))
+                .err()
+                .unwrap(),
+            ConfError::KeyConflict {
+                key: "port".to_string()
+            }
+        );
+        assert!(base()
+            .try_merge(Conf::default().boolean("debug", None))
+            .is_ok());
+    }
+
+    #[test]
+    pub fn source_keys_env() {
+        let env = env_snapshot(&[
+            ("VCFG_PORT", "8080"),
+            ("VCFG_DB__HOST", "localhost"),
+            ("VCFG_PASWORD", "typo"),
+            ("OTHER_PORT", "9090"),
+        ]);
+        let conf = Conf::with_source(DEFAULT_NAME, env)
+            .uint("port", None)
+            .string("db.host", None)
+            .string("password", None);
+        let source_keys = conf.source_keys().unwrap();
+        assert_eq!(source_keys, ["db.host", "pasword", "port"]);
+        let unknown: Vec<_> = source_keys
+            .iter()
+            .filter(|key| !conf.contains_key(key))
+            .collect();
+        assert_eq!(unknown, ["pasword"]);
+
+        let vars = [("VCFG_LOG_FILE", "app.log")];
+        assert_eq!(env_snapshot(&vars).keys().unwrap(), ["log_file"]);
+        let source = env_snapshot(&vars).with_file_indirection();
+        assert_eq!(source.keys().unwrap(), ["log", "log_file"]);
+
+        let conf = Conf::with_source(DEFAULT_NAME, CommandSource::new("true"));
+        assert_eq!(
+            conf.source_keys().unwrap_err(),
+            ConfError::Unsupported {
+                operation: "keys".to_string()
+            }
+        );
+    }
+
+    #[test]
+    pub fn set_map() {
+        let mut conf = Conf::with_source(DEFAULT_NAME, MapSource::default())
+            .uint("port", Some(80))
+            .entry(ConfEntry::<u64>::new("workers").max(8));
+        conf.set("port", 8080u64).unwrap();
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert!(matches!(
+            conf.set("workers", 16u64).unwrap_err(),
+            ConfError::OutOfRange { .. }
+        ));
+        assert_eq!(
+            conf.set("missing", "x".to_string()).unwrap_err(),
+            ConfError::key_not_found("missing")
+        );
+        ConfSink::remove(&mut conf.source, "port").unwrap();
+        assert_eq!(conf.get_uint("port").unwrap(), Some(80));
+
+        let mut conf = conf.secret("db_password", None);
+        conf.set("db_password", Secret("hunter2".to_string()))
+            .unwrap();
+        assert_eq!(
+            conf.require_secret("db_password").unwrap().expose(),
+            "hunter2"
+        );
+    }
+
+    #[test]
+    pub fn get_with_source_origin() {
+        let conf = |vars| {
+            Conf::with_source(DEFAULT_NAME, env_snapshot(vars))
+                .uint("port", Some(80))
+                .string("host", None)
+        };
+        let unset = conf(&[]);
+        assert_eq!(
+            unset.get_with_source::<u64>("port").unwrap(),
+            Some((80, ValueOrigin::Default))
+        );
+        assert_eq!(unset.get_with_source::<String>("host").unwrap(), None);
+        let set = conf(&[("VCFG_PORT", "8080")]);
+        assert_eq!(
+            set.get_with_source::<u64>("port").unwrap(),
+            Some((8080, ValueOrigin::Source))
+        );
+    }
+
+    #[test]
+    pub fn set_override_beats_env_and_default() {
+        let mut conf = Conf::with_source(
+            DEFAULT_NAME,
+            env_snapshot(&[("VCFG_PORT", "8080"), ("VCFG_HOST", "example.com")]),
+        )
+        .uint("port", Some(80))
+        .string("host", None)
+        .string("user", Some("xela"))
+        .secret("password", None);
+        conf.set_override("port", 9090).unwrap();
+        conf.set_override("user", "root".to_string()).unwrap();
+        conf.set_override("password", Secret("hunter2".to_string()))
+            .unwrap();
+        assert_eq!(conf.require_secret("password").unwrap().expose(), "hunter2");
+        assert_eq!(conf.get_uint("port").unwrap(), Some(9090));
+        assert_eq!(conf.get_string("user").unwrap(), Some("root".to_string()));
+        assert_eq!(
+            conf.snapshot().unwrap().get::<u64>("port").unwrap(),
+            Some(9090)
+        );
+        assert!(matches!(
+            conf.set_override("port", "high".to_string()).unwrap_err(),
+            ConfError::ValParseFailed { .. }
+        ));
+        assert_eq!(
+            conf.set_override("missing", 1).unwrap_err(),
+            ConfError::key_not_found("missing")
+        );
+        conf.clear_override("port");
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(
+            conf.get_with_source::<String>("user").unwrap(),
+            Some(("root".to_string(), ValueOrigin::Override))
+        );
+        assert_eq!(
+            conf.get_string("host").unwrap(),
+            Some("example.com".to_string())
+        );
+    }
+
+    #[test]
+    pub fn get_err_key_not_found() {
+        let mut conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[]));
+        assert_eq!(
+            conf.get_string("test").unwrap_err(),
+            ConfError::KeyNotFound {
+                key: "test".to_string()
+            }
+        );
+        conf = conf.string("test", Some("hi"));
+        let test = conf.get_string("test").unwrap();
+        let testy = conf.get_string("testy").unwrap_err();
+        assert_eq!(test, Some("hi".to_string()));
+        assert_eq!(
+            testy,
+            ConfError::KeyNotFound {
+                key: "testy".to_string()
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_str_default() {
+        let conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[])).string("name", Some("world"));
+        assert_eq!(conf.get_string("name").unwrap(), Some("world".to_string()));
+    }
+
+    #[test]
+    pub fn get_str_env() {
+        let source = env_snapshot(&[("VCFG_NAME", "xela")]);
+        let conf = Conf::with_source(DEFAULT_NAME, source).string("name", Some("world"));
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+    }
+
+    #[test]
+    pub fn get_str_multi() {
+        let conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[("VCFG_NAME", "world")]))
+            .string("greeting", Some("Hello"))
+            .string("name", None);
+        let greeting = conf.get_string("greeting").unwrap();
+        let name = conf.get_string("name").unwrap();
+        assert_eq!(greeting, Some("Hello".to_string()));
+        assert_eq!(name, Some("world".to_string()));
+    }
+
+    #[test]
+    pub fn get_int_multi() {
+        let mut conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[]))
+            .byte("max_byte", Some(255))
+            .int("a_number", Some(-42))
+            .uint("another_number", None);
+        let a_number = conf.get_int("a_number").unwrap();
+        let max_byte = conf.get_byte("max_byte").unwrap();
+        let another_number = conf.get_uint("another_number").unwrap();
+        assert_eq!(a_number, Some(-42));
+        assert_eq!(max_byte, Some(255));
+        assert_eq!(another_number, None);
+        conf.source = env_snapshot(&[("VCFG_MAX_BYTE", "4")]);
+        let new_max_byte = conf.get_byte("max_byte").unwrap();
+        assert_eq!(new_max_byte, Some(4));
+    }
+
+    #[test]
+    pub fn require_str_multi() {
+        let mut conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[("VCFG_NAME", "world")]))
+            .string("greeting", Some("Hello"))
+            .string("name", None);
+        let greet = |g: String, n: String| format!("{}, {}!", g, n);
+        let conf_greet = |conf: &Conf| {
+            greet(
+                conf.require("greeting").unwrap(),
+                conf.require("name").unwrap(),
+            )
+        };
+        assert_eq!(conf_greet(&conf), "Hello, world!");
+        conf.source = env_snapshot(&[("VCFG_NAME", "xela"), ("VCFG_GREETING", "Hail")]);
+        assert_eq!(conf_greet(&conf), "Hail, xela!");
+    }
+
+    #[test]
+    pub fn require_int_default() {
+        let conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[])).uint("count", Some(3));
+        let count = conf.require_uint("count").unwrap();
+        assert_eq!(count, 3u64);
+    }
+
+    #[test]
+    pub fn get_bool_lenient() {
+        let mut conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[]))
+            .boolean("enabled", Some(false))
+            .boolean("verbose", None);
+        assert_eq!(conf.get_bool("enabled").unwrap(), Some(false));
+        conf.source = env_snapshot(&[("VCFG_ENABLED", "on")]);
+        assert!(conf.require_bool("enabled").unwrap());
+        for (val, expected) in [
+            ("True", true),
+            ("yes", true),
+            ("1", true),
+            ("FALSE", false),
+            ("Off", false),
+            ("0", false),
+        ] {
+            conf.source = env_snapshot(&[("VCFG_ENABLED", val)]);
+            assert_eq!(conf.require_bool("enabled").unwrap(), expected, "{val}");
+        }
+        conf.source = env_snapshot(&[("VCFG_ENABLED", "NO"), ("VCFG_VERBOSE", "maybe")]);
+        assert!(!conf.require_bool("enabled").unwrap());
+        assert_eq!(
+            conf.get_bool("verbose").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "verbose".to_string(),
+                val: "maybe".to_string(),
+                reason: "invalid bool: maybe".to_string(),
+                description: None
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_float_finite() {
+        let mut conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[]))
+            .float("rate", Some(0.1))
+            .finite_float("threshold", None)
+            .float("third", Some(1.0 / 3.0));
+        assert_eq!(conf.get_float("rate").unwrap(), Some(0.1));
+        assert_eq!(conf.get_float("third").unwrap(), Some(1.0 / 3.0));
+        conf.source = env_snapshot(&[("VCFG_RATE", "0.25"), ("VCFG_THRESHOLD", "1e-3")]);
+        assert_eq!(conf.require_float("rate").unwrap(), 0.25);
+        assert_eq!(conf.require_finite_float("threshold").unwrap(), 0.001);
+        conf.source = env_snapshot(&[
+            ("VCFG_RATE", "inf"),
+            ("VCFG_THIRD", "NaN"),
+            ("VCFG_THRESHOLD", "NaN"),
+        ]);
+        assert_eq!(conf.require_float("rate").unwrap(), f64::INFINITY);
+        assert!(conf.require_float("third").unwrap().is_nan());
+        assert_eq!(
+            conf.get_finite_float("threshold").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "threshold".to_string(),
+                val: "NaN".to_string(),
+                reason: "float is not finite: NaN".to_string(),
+                description: None
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_env_trim_empty() {
+        let vars = [("VCFG_PORT", "8080\n"), ("VCFG_NAME", "")];
+        let conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&vars))
+            .uint("port", None)
+            .string("name", Some("world"));
+        assert!(conf.get_uint("port").is_err());
+        assert_eq!(conf.get_string("name").unwrap(), Some(String::new()));
+        let source = env_snapshot(&vars).with_trim().with_empty_as_unset();
+        let conf = Conf::with_source(DEFAULT_NAME, source)
+            .uint("port", None)
+            .string("name", Some("world"));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(conf.get_string("name").unwrap(), Some("world".to_string()));
+    }
+
+    #[test]
+    pub fn get_env_file_indirection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("password");
+        std::fs::write(&path, "hunter2\n").unwrap();
+        let token_path = dir.path().join("token");
+        let vars = [
+            ("VCFG_PASSWORD_FILE", path.to_str().unwrap()),
+            ("VCFG_TOKEN_FILE", token_path.to_str().unwrap()),
+        ];
+        let source = env_snapshot(&vars).with_file_indirection();
+        let conf = Conf::with_source(DEFAULT_NAME, source)
+            .string("password", None)
+            .string("token", None);
+        assert_eq!(
+            conf.get_string("password").unwrap(),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(
+            conf.get_string("token").unwrap_err(),
+            ConfError::secret_file_unreadable(
+                "VCFG_TOKEN_FILE",
+                token_path.to_str().unwrap(),
+                "No such file or directory (os error 2)"
+            )
+        );
+        let conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&vars)).string("password", None);
+        assert_eq!(conf.get_string("password").unwrap(), None);
+
+        let vars = [("VCFG_LOG_FILE", "/nonexistent/app.log")];
+        let conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&vars))
+            .string("log", Some("stderr"))
+            .string("log_file", None);
+        assert_eq!(conf.get_string("log").unwrap(), Some("stderr".to_string()));
+        assert_eq!(
+            conf.get_string("log_file").unwrap(),
+            Some("/nonexistent/app.log".to_string())
+        );
+    }
+
+    #[test]
+    pub fn get_parse_failed_reason() {
+        let conf = Conf::with_source(
+            "vcfg",
+            InMemorySource::new("vcfg").set("VCFG_PORT", "99999"),
+        )
+        .entry(ConfEntry::<u16>::new("port"));
+        assert_eq!(
+            conf.get::<u16>("port").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "port".to_string(),
+                val: "99999".to_string(),
+                reason: "number too large to fit in target type".to_string(),
+                description: None
+            }
+        );
+        assert_eq!(
+            conf.get::<u32>("port").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "port".to_string(),
+                val: "".to_string(),
+                reason: "entry is not of type u32".to_string(),
+                description: None
+            }
+        );
+    }
+
+    #[test]
+    pub fn validate_missing() {
+        let source = InMemorySource::new("vcfg").set("VCFG_NAME", "xela");
+        let conf = Conf::with_source("vcfg", source)
+            .require_entry(ConfEntry::<String>::new("name"))
+            .require_entry(ConfEntry::<String>::new("greeting").with_default("Hello"))
+            .require_entry(ConfEntry::<u64>::new("port"))
+            .require_entry(ConfEntry::<String>::new("host"))
+            .string("optional", None);
+        assert_eq!(
+            conf.validate().unwrap_err(),
+            ConfError::ValidationFailed {
+                missing: vec!["host".to_string(), "port".to_string()]
+            }
+        );
+        let conf = conf
+            .require_entry(ConfEntry::<String>::new("host").with_default("localhost"))
+            .require_entry(ConfEntry::<u64>::new("port").with_default("80"));
+        assert_eq!(conf.validate(), Ok(()));
+    }
+
+    #[test]
+    pub fn validate_required() {
+        let source = InMemorySource::new("vcfg").set("VCFG_PRESENT", "here");
+        let conf = Conf::with_source("vcfg", source)
+            .entry(
+                ConfEntry::<String>::new("with_default")
+                    .with_default("default")
+                    .required(),
+            )
+            .entry(ConfEntry::<String>::new("present").required());
+        assert_eq!(conf.validate(), Ok(()));
+        let conf = conf.entry(ConfEntry::<String>::new("missing").required());
+        assert_eq!(
+            conf.validate().unwrap_err(),
+            ConfError::ValidationFailed {
+                missing: vec!["missing".to_string()]
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_case_insensitive() {
+        let source = InMemorySource::new("vcfg").set("VCFG_PORT", "8080");
+        let conf = Conf::with_source("vcfg", source)
+            .string("Name", Some("xela"))
+            .case_insensitive()
+            .uint("port", None);
+        assert_eq!(conf.get_uint("Port").unwrap(), Some(8080));
+        assert_eq!(conf.require_uint("PORT").unwrap(), 8080);
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert!(conf.contains_key("NAME"));
+        assert_eq!(conf.keys().collect::<Vec<_>>(), ["name", "port"]);
+        let conf = conf.uint("port", Some(80));
+        assert_eq!(conf.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "conf entries `port` and `Port` collide")]
+    pub fn get_case_insensitive_collision() {
+        let _ = Conf::default()
+            .case_insensitive()
+            .uint("port", None)
+            .uint("Port", None);
+    }
+
+    #[test]
+    pub fn require_all_missing() {
+        let source = InMemorySource::new("vcfg").set("VCFG_NAME", "xela");
+        let conf = Conf::with_source("vcfg", source)
+            .string("name", None)
+            .string("greeting", Some("Hello"))
+            .string("token", None)
+            .uint("port", None);
+        assert_eq!(conf.require_all(&["name", "greeting"]), Ok(()));
+        let err = conf.require_all(&["port", "name", "token"]).unwrap_err();
+        assert_eq!(
+            err,
+            ConfError::ValidationFailed {
+                missing: vec!["port".to_string(), "token".to_string()]
+            }
+        );
+        assert!(err.to_string().contains("port, token"));
+        assert!(matches!(
+            conf.require_all(&["undefined"]).unwrap_err(),
+            ConfError::KeyNotFound { .. }
+        ));
+    }
+
+    #[test]
+    pub fn get_list_trimmed() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_PORTS", "8080, 8081 ,8082")
+            .set("VCFG_HOSTS", "")
+            .set("VCFG_PATHS", "/bin;/usr/bin")
+            .set("VCFG_BAD_PORTS", "80,http");
+        let conf = Conf::with_source("vcfg", source)
+            .list::<u16>("ports", None)
+            .list::<String>("hosts", Some(&["localhost".to_string()]))
+            .list::<u16>("bad_ports", None)
+            .list::<u16>("missing", None)
+            .list::<u16>("defaulted", Some(&[1, 2]))
+            .entry(ConfEntry::<ConfList<String, ';'>>::new("paths"));
+        assert_eq!(
+            conf.get_list::<u16>("ports").unwrap(),
+            Some(vec![8080, 8081, 8082])
+        );
+        assert_eq!(conf.get_list::<String>("hosts").unwrap(), Some(vec![]));
+        assert_eq!(conf.get_list::<u16>("missing").unwrap(), None);
+        assert_eq!(conf.require_list::<u16>("defaulted").unwrap(), vec![1, 2]);
+        assert_eq!(
+            conf.require::<ConfList<String, ';'>>("paths").unwrap().0,
+            vec!["/bin".to_string(), "/usr/bin".to_string()]
+        );
+        assert_eq!(
+            conf.get_list::<u16>("bad_ports").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "bad_ports".to_string(),
+                val: "80,http".to_string(),
+                reason: "invalid list element `http`: invalid digit found in string".to_string(),
+                description: None
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_map() {
+        let source = env_snapshot(&[
+            ("VCFG_LIMITS", "free=10, pro = 100"),
+            ("VCFG_EMPTY", ""),
+            ("VCFG_BAD_PAIR", "free=10,pro"),
+            ("VCFG_BAD_VALUE", "free=ten"),
+        ]);
+        let conf = Conf::with_source("vcfg", source)
+            .map::<String, u64>("limits", None)
+            .map::<String, u64>("empty", None)
+            .map::<String, u64>("bad_pair", None)
+            .map::<String, u64>("bad_value", None)
+            .map("defaulted", Some(&HashMap::from([(1u16, true)])));
+        assert_eq!(
+            conf.require_map::<String, u64>("limits").unwrap(),
+            HashMap::from([("free".to_string(), 10), ("pro".to_string(), 100)])
+        );
+        assert_eq!(
+            conf.get_map::<String, u64>("empty").unwrap(),
+            Some(HashMap::new())
+        );
+        assert_eq!(
+            conf.get_map::<u16, bool>("defaulted").unwrap(),
+            Some(HashMap::from([(1, true)]))
+        );
+        assert_eq!(
+            conf.get_map::<String, u64>("bad_pair").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "bad_pair".to_string(),
+                val: "free=10,pro".to_string(),
+                reason: "invalid map entry `pro`: expected `=`".to_string(),
+                description: None
+            }
+        );
+        assert_eq!(
+            conf.get_map::<String, u64>("bad_value").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "bad_value".to_string(),
+                val: "free=ten".to_string(),
+                reason: "invalid map entry `free=ten`: value: invalid digit found in string"
+                    .to_string(),
+                description: None
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_or_fallback() {
+        let source = InMemorySource::new("vcfg").set("VCFG_PORT", "http");
+        let conf = Conf::with_source("vcfg", source)
+            .uint("port", None)
+            .uint("timeout", None)
+            .uint("retries", Some(3));
+        assert_eq!(conf.get_or("timeout", 30u64).unwrap(), 30);
+        assert_eq!(conf.get_or("retries", 5u64).unwrap(), 3);
+        assert_eq!(conf.get_or_else("timeout", || 60u64).unwrap(), 60);
+        assert!(matches!(
+            conf.get_or("port", 80u64).unwrap_err(),
+            ConfError::ValParseFailed { .. }
+        ));
+        assert!(matches!(
+            conf.get_or_else("port", || 80u64).unwrap_err(),
+            ConfError::ValParseFailed { .. }
+        ));
+    }
+
+    #[test]
+    pub fn keys_sorted() {
+        let conf = Conf::with_source("vcfg", InMemorySource::new("vcfg"));
+        assert!(conf.is_empty());
+        let conf = conf
+            .uint("port", None)
+            .string("name", None)
+            .boolean("debug", None);
+        assert_eq!(
+            conf.keys().collect::<Vec<_>>(),
+            vec!["debug", "name", "port"]
+        );
+        assert_eq!(conf.len(), 3);
+        assert!(!conf.is_empty());
+        assert!(conf.contains_key("name"));
+        assert!(!conf.contains_key("greeting"));
+    }
+
+    #[test]
+    pub fn get_path_normalized() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_DATA_DIR", "~/data")
+            .set("VCFG_LOG_PATH", "logs/app.log")
+            .set("VCFG_EMPTY", "");
+        let conf = Conf::with_source("vcfg", source)
+            .path("data_dir", None)
+            .path("log_path", None)
+            .path("empty", None)
+            .path("cache_dir", Some(Path::new("/var/cache/vcfg")));
+        assert_eq!(conf.get_path("data_dir").unwrap(), Some("~/data".into()));
+        let data_dir = conf.require::<ConfPath>("data_dir").unwrap();
+        assert_eq!(
+            data_dir
+                .normalize_with_home(|| Ok("/home/xela".into()))
+                .unwrap(),
+            PathBuf::from("/home/xela/data")
+        );
+        assert_eq!(
+            conf.get_path_canonical("log_path").unwrap(),
+            Some(std::env::current_dir().unwrap().join("logs/app.log"))
+        );
+        assert_eq!(
+            conf.require_path("cache_dir").unwrap(),
+            PathBuf::from("/var/cache/vcfg")
+        );
+        assert_eq!(
+            conf.get_path("empty").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "empty".to_string(),
+                val: "".to_string(),
+                reason: "path is empty".to_string(),
+                description: None
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_socket_addr() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_BIND", "[::1]:9000")
+            .set("VCFG_NO_PORT", "127.0.0.1")
+            .set("VCFG_HOST", "10.0.0.1");
+        let conf = Conf::with_source("vcfg", source)
+            .socket_addr("bind", None)
+            .socket_addr("no_port", None)
+            .socket_addr("listen", Some(([0, 0, 0, 0], 8080).into()))
+            .entry(ConfEntry::<Ipv4Addr>::new("host"));
+        assert_eq!(
+            conf.get_socket_addr("bind").unwrap(),
+            Some(SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 9000))
+        );
+        assert_eq!(
+            conf.require_socket_addr("listen").unwrap().to_string(),
+            "0.0.0.0:8080"
+        );
+        assert_eq!(
+            conf.require::<Ipv4Addr>("host").unwrap(),
+            Ipv4Addr::new(10, 0, 0, 1)
+        );
+        assert_eq!(
+            conf.get_socket_addr("no_port").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "no_port".to_string(),
+                val: "127.0.0.1".to_string(),
+                reason: "invalid socket address syntax".to_string(),
+                description: None
+            }
+        );
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    #[test]
+    #[cfg(feature = "chrono")]
+    pub fn get_datetime() {
+        use chrono::TimeZone;
 
-    fn clean_env() {
-        let vars = vec![
-            "test",
-            "testy",
-            "greeting",
-            "name",
-            "max_byte",
-            "a_number",
-            "another_number",
-        ];
-        vars.iter().for_each(|n| {
-            std::env::remove_var(format!("{}_{}", DEFAULT_NAME, n.to_ascii_uppercase()))
-        });
+        let cutoff = chrono::Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let conf = Conf::with_source(
+            DEFAULT_NAME,
+            env_snapshot(&[
+                ("VCFG_CUTOFF", "2024-01-02T03:04:05Z"),
+                ("VCFG_LOCAL", "2024-01-02T05:04:05+02:00"),
+                ("VCFG_BAD", "not-a-date"),
+            ]),
+        )
+        .datetime("cutoff", None)
+        .datetime("local", None)
+        .datetime("bad", None)
+        .datetime("start", Some(cutoff));
+        assert_eq!(conf.require_datetime("cutoff").unwrap(), cutoff);
+        assert_eq!(conf.get_datetime("local").unwrap(), Some(cutoff));
+        assert_eq!(conf.get_datetime("start").unwrap(), Some(cutoff));
+        assert!(matches!(
+            conf.get_datetime("bad").unwrap_err(),
+            ConfError::ValParseFailed { val, .. } if val == "not-a-date"
+        ));
     }
 
     #[test]
-    pub fn get_err_key_not_found() {
-        clean_env();
-        let mut conf = Conf::default();
+    #[cfg(feature = "url")]
+    pub fn get_url() {
+        let conf = Conf::with_source(
+            DEFAULT_NAME,
+            env_snapshot(&[
+                ("VCFG_API_ENDPOINT", "https://api.example.com/v1"),
+                ("VCFG_WEBHOOK", "example.com"),
+            ]),
+        )
+        .url("api_endpoint", None)
+        .url("webhook", None);
+        let endpoint = conf.require::<url::Url>("api_endpoint").unwrap();
+        assert_eq!(endpoint.scheme(), "https");
+        assert_eq!(endpoint.host_str(), Some("api.example.com"));
+        assert_eq!(endpoint.path(), "/v1");
+        assert!(matches!(
+            conf.get_url("webhook").unwrap_err(),
+            ConfError::ValParseFailed { val, reason, .. }
+                if val == "example.com" && reason == "relative URL without a base"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    pub fn get_uuid() {
+        let conf = Conf::with_source(
+            DEFAULT_NAME,
+            env_snapshot(&[
+                ("VCFG_TENANT", "67e55044-10b1-426f-9247-bb680e5fe0c8"),
+                ("VCFG_BAD", "67e55044-10b1-426f"),
+            ]),
+        )
+        .uuid("tenant", None)
+        .uuid("bad", None)
+        .uuid("service", Some(uuid::Uuid::nil()));
         assert_eq!(
-            conf.get_string("test").unwrap_err(),
-            ConfError::KeyNotFound {
-                key: "test".to_string()
+            conf.require_uuid("tenant").unwrap(),
+            uuid::Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8)
+        );
+        assert_eq!(conf.get_uuid("service").unwrap(), Some(uuid::Uuid::nil()));
+        assert!(matches!(
+            conf.get_uuid("bad").unwrap_err(),
+            ConfError::ValParseFailed { reason, .. } if reason.starts_with("invalid group count")
+        ));
+    }
+
+    #[test]
+    pub fn get_duration() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_TIMEOUT", "250ms")
+            .set("VCFG_INTERVAL", "1h30m")
+            .set("VCFG_RETRY", "30")
+            .set("VCFG_TTL", "fortnight");
+        let conf = Conf::with_source("vcfg", source)
+            .duration("timeout", None)
+            .duration("interval", None)
+            .duration("retry", None)
+            .duration("ttl", None)
+            .duration("grace", Some(Duration::from_secs(300)));
+        assert_eq!(
+            conf.get_duration("timeout").unwrap(),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            conf.require_duration("interval").unwrap(),
+            Duration::from_secs(5400)
+        );
+        assert_eq!(
+            conf.require_duration("retry").unwrap(),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            conf.require_duration("grace").unwrap(),
+            Duration::from_secs(300)
+        );
+        assert_eq!(
+            conf.get_duration("ttl").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "ttl".to_string(),
+                val: "fortnight".to_string(),
+                reason: "invalid duration: fortnight (expected a number with unit ns, us, ms, s, \
+                         m, h or d, e.g. 1h30m)"
+                    .to_string(),
+                description: None
             }
         );
-        conf = conf.string("test", Some("hi"));
-        let test = conf.get_string("test").unwrap();
-        let testy = conf.get_string("testy").unwrap_err();
-        assert_eq!(test, Some("hi".to_string()));
+        for s in ["250ms", "1h", "1m30s", "5m", "0s", "1d2h", "1s500ms"] {
+            assert_eq!(s.parse::<ConfDuration>().unwrap().to_string(), s);
+        }
+        assert_eq!("90s".parse::<ConfDuration>().unwrap().to_string(), "1m30s");
         assert_eq!(
-            testy,
+            "1h 30 m".parse::<ConfDuration>().unwrap(),
+            ConfDuration(Duration::from_secs(5400))
+        );
+        for s in ["", "h", "1h30", "5 minutes", "-1s"] {
+            assert!(s.parse::<ConfDuration>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    pub fn get_validated() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_PORT", "0")
+            .set("VCFG_ADMIN_PORT", "9000");
+        let nonzero = |p: &u16| {
+            if *p == 0 {
+                Err("port must be nonzero".to_string())
+            } else {
+                Ok(())
+            }
+        };
+        let conf = Conf::with_source("vcfg", source)
+            .entry(ConfEntry::<u16>::new("port").validate_with(nonzero))
+            .entry(ConfEntry::<u16>::new("admin_port").validate_with(nonzero))
+            .entry(
+                ConfEntry::<u16>::new("metrics_port")
+                    .with_default("0")
+                    .validate_with(nonzero),
+            );
+        assert_eq!(
+            conf.get::<u16>("port").unwrap_err(),
+            ConfError::ValInvalid {
+                key: "port".to_string(),
+                reason: "port must be nonzero".to_string(),
+                description: None
+            }
+        );
+        assert_eq!(conf.require::<u16>("admin_port").unwrap(), 9000);
+        assert!(matches!(
+            conf.require::<u16>("metrics_port").unwrap_err(),
+            ConfError::ValInvalid { .. }
+        ));
+    }
+
+    #[test]
+    pub fn get_bounded() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_WORKERS", "11")
+            .set("VCFG_THREADS", "5")
+            .set("VCFG_RATIO", "1.5");
+        let conf = Conf::with_source("vcfg", source)
+            .int_range("workers", None, 1..=10)
+            .int_range("threads", None, 1..=10)
+            .int_range("retries", Some(3), 1..=10)
+            .entry(ConfEntry::<f64>::new("ratio").max(1.0));
+        assert_eq!(
+            conf.get_int("workers").unwrap_err(),
+            ConfError::OutOfRange {
+                key: "workers".to_string(),
+                value: "11".to_string(),
+                min: Some("1".to_string()),
+                max: Some("10".to_string()),
+                description: None
+            }
+        );
+        assert_eq!(conf.get_int("threads").unwrap(), Some(5));
+        assert_eq!(conf.require_int("retries").unwrap(), 3);
+        assert_eq!(
+            conf.get_float("ratio").unwrap_err().to_string(),
+            "val out of range: ratio = 1.5: expected min none, max 1"
+        );
+    }
+
+    #[test]
+    pub fn get_interpolated() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_ROOT", "/srv")
+            .set("VCFG_DATA_DIR", "${ROOT}/data")
+            .set("VCFG_LOG_PATH", "${DATA_DIR}/app.log")
+            .set("VCFG_PING", "${pong}")
+            .set("VCFG_PONG", "${ping}")
+            .set("VCFG_BROKEN", "${missing}/x");
+        let conf = Conf::with_source("vcfg", source)
+            .with_interpolation()
+            .string("root", None)
+            .string("data_dir", None)
+            .string("log_path", None)
+            .string("ping", None)
+            .string("pong", None)
+            .string("broken", None);
+        assert_eq!(
+            conf.require_string("log_path").unwrap(),
+            "/srv/data/app.log"
+        );
+        assert_eq!(
+            conf.get_string("ping").unwrap_err(),
+            ConfError::InterpolationCycle {
+                key: "ping".to_string()
+            }
+        );
+        assert_eq!(
+            conf.get_string("broken").unwrap_err(),
             ConfError::KeyNotFound {
-                key: "testy".to_string()
+                key: "missing".to_string()
             }
         );
     }
 
     #[test]
-    pub fn get_str_default() {
-        clean_env();
-        let conf = Conf::default().string("name", Some("world"));
-        assert_eq!(conf.get_string("name").unwrap(), Some("world".to_string()));
+    pub fn describe_sorted() {
+        let conf = Conf::with_source("vcfg", InMemorySource::new("vcfg"))
+            .string("name", Some("xela"))
+            .require_entry(ConfEntry::<i64>::new("count"));
+        assert_eq!(
+            conf.describe(),
+            vec![
+                ConfOptionInfo {
+                    key: "count".to_string(),
+                    type_name: "i64".to_string(),
+                    default: None,
+                    required: true,
+                    description: None,
+                },
+                ConfOptionInfo {
+                    key: "name".to_string(),
+                    type_name: "alloc::string::String".to_string(),
+                    default: Some("xela".to_string()),
+                    required: false,
+                    description: None,
+                },
+            ]
+        );
+        assert!(conf.describe()[1].has_default());
     }
 
     #[test]
-    pub fn get_str_env() {
-        clean_env();
-        std::env::set_var("VCFG_NAME", "xela");
-        let conf = Conf::default().string("name", Some("world"));
-        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+    pub fn describe_description() {
+        let source = InMemorySource::new("vcfg").set("VCFG_WORKERS", "many");
+        let conf = Conf::with_source("vcfg", source)
+            .entry(ConfEntry::<u16>::new("workers").describe("Number of worker threads."))
+            .require_entry(ConfEntry::<String>::new("token").describe("API token."));
+        assert_eq!(
+            conf.describe()[1].description,
+            Some("Number of worker threads.".to_string())
+        );
+        let err = conf.get::<u16>("workers").unwrap_err();
+        assert_eq!(
+            miette::Diagnostic::help(&err).unwrap().to_string(),
+            "Number of worker threads."
+        );
+        assert_eq!(
+            conf.require::<String>("token").unwrap_err(),
+            ConfError::ValNotFound {
+                key: "token".to_string(),
+                description: Some("API token.".to_string())
+            }
+        );
     }
 
     #[test]
-    pub fn get_str_multi() {
-        clean_env();
-        let conf = Conf::default()
-            .string("greeting", Some("Hello"))
-            .string("name", None);
-        std::env::set_var("VCFG_NAME", "world");
-        let greeting = conf.get_string("greeting").unwrap();
-        let name = conf.get_string("name").unwrap();
-        assert_eq!(greeting, Some("Hello".to_string()));
-        assert_eq!(name, Some("world".to_string()));
+    pub fn env_template() {
+        let conf = Conf::with_source("vcfg", InMemorySource::new("vcfg"))
+            .entry(
+                ConfEntry::<u16>::new("port")
+                    .with_default("8080")
+                    .describe("Port to listen on."),
+            )
+            .require_entry(ConfEntry::<String>::new("api_token").describe("API token."))
+            .string("log_level", None);
+        assert_eq!(
+            conf.to_env_template(),
+            "# API token.\n# REQUIRED\nVCFG_API_TOKEN=\n\nVCFG_LOG_LEVEL=\n\n# Port to listen on.\nVCFG_PORT=8080\n"
+        );
+
+        let source = env_snapshot(&[]).with_key_mapper(KeyMapper::prefixed_screaming_snake("app"));
+        let conf = Conf::with_source("vcfg", source).uint("db.port", Some(5432));
+        assert_eq!(conf.to_env_template(), "APP__DB__PORT=5432\n");
     }
 
     #[test]
-    pub fn get_int_multi() {
-        clean_env();
-        let conf = Conf::default()
-            .byte("max_byte", Some(255))
-            .int("a_number", Some(-42))
-            .uint("another_number", None);
-        let a_number = conf.get_int("a_number").unwrap();
-        let max_byte = conf.get_byte("max_byte").unwrap();
-        let another_number = conf.get_uint("another_number").unwrap();
-        assert_eq!(a_number, Some(-42));
-        assert_eq!(max_byte, Some(255));
-        assert_eq!(another_number, None);
-        std::env::set_var("VCFG_MAX_BYTE", "4");
-        let new_max_byte = conf.get_byte("max_byte").unwrap();
-        assert_eq!(new_max_byte, Some(4));
+    pub fn export_env() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_GREETING", "it's $HOME")
+            .set("VCFG_API_KEY", "hunter2")
+            .set("VCFG_DB__HOST", "db.example.com");
+        let conf = Conf::with_source("vcfg", source)
+            .string("greeting", None)
+            .secret("api_key", None)
+            .string("db.host", None)
+            .string("log_level", None)
+            .string("empty", Some(""))
+            .uint("port", Some(8080));
+        assert_eq!(
+            conf.export_env(false).unwrap(),
+            "export VCFG_API_KEY='<redacted>'\n\
+             export VCFG_DB__HOST=db.example.com\n\
+             export VCFG_EMPTY=''\n\
+             export VCFG_GREETING='it'\\''s $HOME'\n\
+             export VCFG_PORT=8080\n"
+        );
+        assert!(conf
+            .export_env(true)
+            .unwrap()
+            .starts_with("export VCFG_API_KEY=hunter2\n"));
+
+        let source = env_snapshot(&[("VCFG_DB_HOST", "localhost")]).with_separator("_");
+        let conf = Conf::with_source("vcfg", source).string("db.host", None);
+        assert_eq!(
+            conf.export_env(false).unwrap(),
+            "export VCFG_DB_HOST=localhost\n"
+        );
+        let source = env_snapshot(&[]).with_key_mapper(KeyMapper::prefixed_screaming_snake("app"));
+        let conf = Conf::with_source("vcfg", source).uint("db.port", Some(5432));
+        assert_eq!(
+            conf.export_env(false).unwrap(),
+            "export APP__DB__PORT=5432\n"
+        );
+        let conf = Conf::with_source("vcfg", MapSource::default()).uint("db.port", Some(5432));
+        assert_eq!(
+            conf.export_env(false).unwrap(),
+            "export VCFG_DB__PORT=5432\n"
+        );
     }
 
     #[test]
-    pub fn require_str_multi() {
-        clean_env();
+    pub fn get_layered_env_over_memory() {
+        let memory = InMemorySource::new("vcfg")
+            .set("VCFG_LAYERED_NAME", "memory")
+            .set("VCFG_LAYERED_GREETING", "Hello");
+        let conf = Conf::with_source(DEFAULT_NAME, env_snapshot(&[("VCFG_LAYERED_NAME", "env")]))
+            .add_source(memory)
+            .add_source(InMemorySource::new("vcfg").set("VCFG_LAYERED_GREETING", "Hi"))
+            .string("layered_name", None)
+            .string("layered_greeting", None)
+            .uint("layered_port", Some(8080));
+        assert_eq!(conf.source.sources.len(), 3);
+        assert_eq!(
+            conf.get_string("layered_name").unwrap(),
+            Some("env".to_string())
+        );
+        assert_eq!(
+            conf.get_string("layered_greeting").unwrap(),
+            Some("Hello".to_string())
+        );
+        assert_eq!(conf.get_uint("layered_port").unwrap(), Some(8080));
+    }
+
+    #[test]
+    pub fn get_with_sources() {
+        let conf = Conf::with_sources(
+            "vcfg",
+            vec![
+                Box::new(InMemorySource::new("vcfg").set("VCFG_LEVEL", "debug")),
+                Box::new(InMemorySource::new("vcfg").set("VCFG_LEVEL", "info")),
+            ],
+        )
+        .string("level", None);
+        assert_eq!(conf.get_string("level").unwrap(), Some("debug".to_string()));
+    }
+
+    #[test]
+    pub fn get_char_and_size() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_DELIM", ";")
+            .set("VCFG_BAD_DELIM", "ab")
+            .set("VCFG_BUFFER", "4096");
+        let conf = Conf::with_source("vcfg", source)
+            .entry(ConfEntry::<char>::new("delim"))
+            .entry(ConfEntry::<char>::new("bad_delim"))
+            .size("buffer", None)
+            .entry(ConfEntry::<isize>::new("offset").with_default("-1"));
+        assert_eq!(conf.get::<char>("delim").unwrap(), Some(';'));
+        assert_eq!(
+            conf.get::<char>("bad_delim").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "bad_delim".to_string(),
+                val: "ab".to_string(),
+                reason: "too many characters in string".to_string(),
+                description: None,
+            }
+        );
+        assert_eq!(conf.get_size("buffer").unwrap(), Some(4096));
+        assert_eq!(conf.get::<isize>("offset").unwrap(), Some(-1));
+    }
+
+    #[test]
+    pub fn get_big_int() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_NODE_ID", "340282366920938463463374607431768211455")
+            .set("VCFG_TOO_BIG", "340282366920938463463374607431768211456")
+            .set("VCFG_OFFSET", "-18446744073709551616");
+        let conf = Conf::with_source("vcfg", source)
+            .big_uint("node_id", None)
+            .big_uint("too_big", None)
+            .big_int("offset", None);
+        assert_eq!(conf.get_big_uint("node_id").unwrap(), Some(u128::MAX));
+        assert_eq!(
+            conf.get_big_uint("too_big").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "too_big".to_string(),
+                val: "340282366920938463463374607431768211456".to_string(),
+                reason: "number too large to fit in target type".to_string(),
+                description: None,
+            }
+        );
+        assert_eq!(
+            conf.require_big_int("offset").unwrap(),
+            -(u64::MAX as i128) - 1
+        );
+    }
+
+    #[test]
+    pub fn get_non_zero() {
+        let conf = Conf::with_source("vcfg", InMemorySource::new("vcfg").set("VCFG_PORT", "0"))
+            .entry(ConfEntry::<NonZeroU16>::new("port"));
+        assert_eq!(
+            conf.require::<NonZeroU16>("port").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "port".to_string(),
+                val: "0".to_string(),
+                reason: "number would be zero for non-zero type".to_string(),
+                description: None,
+            }
+        );
+        let conf = Conf::with_source("vcfg", InMemorySource::new("vcfg").set("VCFG_PORT", "8080"))
+            .entry(ConfEntry::<NonZeroU16>::new("port"));
+        assert_eq!(
+            conf.require::<NonZeroU16>("port").unwrap(),
+            NonZeroU16::new(8080).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn get_env_fallback_prefix() {
+        let vars = [
+            ("NEWNAME_FALLBACK_HOST", "new.example.com"),
+            ("OLDNAME_FALLBACK_HOST", "old.example.com"),
+            ("OLDNAME_FALLBACK_PORT", "8080"),
+        ];
+        let source = EnvSource::new("newname")
+            .with_fallback_prefix("oldname")
+            .with_vars(
+                vars.iter()
+                    .map(|(k, v)| (k.to_string(), v.into()))
+                    .collect(),
+            );
+        assert_eq!(
+            source.find("fallback_port").unwrap(),
+            Some(("OLDNAME_FALLBACK_PORT".to_string(), "8080".to_string()))
+        );
+        let conf = Conf::with_source("newname", source)
+            .string("fallback_host", None)
+            .uint("fallback_port", None)
+            .string("fallback_user", None);
+        assert_eq!(
+            conf.get_string("fallback_host").unwrap(),
+            Some("new.example.com".to_string())
+        );
+        assert_eq!(conf.get_uint("fallback_port").unwrap(), Some(8080));
+        assert_eq!(conf.get_string("fallback_user").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_secret_redacted() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_API_KEY", "hunter2")
+            .set("VCFG_DB_PIN", "hunter2")
+            .set("VCFG_DB_TLS", "hunter2")
+            .set("VCFG_DB_TIMEOUT", "hunter2")
+            .set("VCFG_DB_PORTS", "5432,hunter2");
+        let conf = Conf::with_source("vcfg", source)
+            .secret("api_key", None)
+            .entry(ConfEntry::<Secret<u16>>::new("db_pin").secret())
+            .entry(ConfEntry::<Secret<ConfBool>>::new("db_tls").secret())
+            .entry(ConfEntry::<Secret<ConfDuration>>::new("db_timeout").secret())
+            .entry(ConfEntry::<Secret<ConfList<u32>>>::new("db_ports").secret());
+        let api_key = conf.require_secret("api_key").unwrap();
+        assert_eq!(api_key.expose(), "hunter2");
+        assert_eq!(api_key.to_string(), "****");
+        assert_eq!(format!("{api_key:?}"), "Secret(****)");
+        assert_eq!(serde_json::to_string(&api_key).unwrap(), "\"hunter2\"");
+        let errs = [
+            conf.get::<Secret<u16>>("db_pin").unwrap_err(),
+            conf.get::<Secret<ConfBool>>("db_tls").unwrap_err(),
+            conf.get::<Secret<ConfDuration>>("db_timeout").unwrap_err(),
+            conf.get::<Secret<ConfList<u32>>>("db_ports").unwrap_err(),
+        ];
+        for err in errs {
+            assert!(!err.to_string().contains("hunter2"), "{err}");
+            assert!(!format!("{err:?}").contains("hunter2"), "{err:?}");
+        }
+    }
+
+    #[test]
+    pub fn env_key_separator() {
+        let source = EnvSource::new("vcfg");
+        assert_eq!(source.env_key("db.host"), "VCFG_DB__HOST");
+        assert_eq!(source.env_key("db_host"), "VCFG_DB_HOST");
+        assert_eq!(source.env_key("db.max_conns"), "VCFG_DB__MAX_CONNS");
+        assert_eq!(source.env_key("tls-cert"), "VCFG_TLS__CERT");
+        assert_eq!(
+            source.key_from_env_key("VCFG_DB__MAX_CONNS"),
+            Some("db.max_conns".to_string())
+        );
+        assert_eq!(
+            source.key_from_env_key("VCFG_DB_HOST"),
+            Some("db_host".to_string())
+        );
+        assert_eq!(source.key_from_env_key("OTHER_DB_HOST"), None);
+        let source = EnvSource::new("vcfg").with_separator("_X_");
+        assert_eq!(source.env_key("db.host"), "VCFG_DB_X_HOST");
+        assert_eq!(
+            source.key_from_env_key("VCFG_DB_X_HOST"),
+            Some("db.host".to_string())
+        );
+
+        let source = env_snapshot(&[("VCFG_SECTION__HOST", "db.example.com")]);
+        let conf = Conf::with_source(DEFAULT_NAME, source).string("section.host", None);
+        assert_eq!(
+            conf.get_string("section.host").unwrap(),
+            Some("db.example.com".to_string())
+        );
+    }
+
+    #[test]
+    pub fn env_key_case() {
+        let key = "db2.max_conns_v1";
+        let cases = [
+            (KeyCase::ScreamingSnake, "VCFG_DB2__MAX_CONNS_V1"),
+            (KeyCase::Snake, "VCFG_db2__max_conns_v1"),
+            (KeyCase::KebabUpper, "VCFG_DB2__MAX-CONNS-V1"),
+            (KeyCase::KebabLower, "VCFG_db2__max-conns-v1"),
+            (KeyCase::AsIs, "VCFG_db2__max_conns_v1"),
+        ];
+        for (case, env_key) in cases {
+            let source = EnvSource::new("vcfg").with_case(case);
+            assert_eq!(source.env_key(key), env_key);
+            assert_eq!(source.key_from_env_key(env_key), Some(key.to_string()));
+        }
+        assert_eq!(
+            EnvSource::new("vcfg")
+                .with_case(KeyCase::AsIs)
+                .env_key("Api_Key1"),
+            "VCFG_Api_Key1"
+        );
+        assert_eq!(
+            EnvSource::new("vcfg")
+                .with_case(KeyCase::KebabLower)
+                .get("max_conns")
+                .unwrap_err(),
+            ConfError::EnvKeyInvalid {
+                key: "VCFG_max-conns".to_string(),
+                reason: "`-` is not portable in env var names, use a snake case".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    pub fn env_key_mapper() {
+        let vars = [
+            ("APP__DB__MAX_CONNS", "16"),
+            ("DB__HOST", "localhost"),
+            ("VCFG_DB__HOST", "ignored"),
+        ];
+        let source =
+            env_snapshot(&vars).with_key_mapper(KeyMapper::prefixed_screaming_snake("app"));
+        assert_eq!(source.env_key("db.max_conns"), "APP__DB__MAX_CONNS");
+        assert_eq!(source.get("db.max_conns").unwrap(), Some("16".to_string()));
+        assert_eq!(source.get("db.host").unwrap(), None);
+        assert_eq!(source.keys().unwrap_err(), ConfError::unsupported("keys"));
+        let source = env_snapshot(&vars).with_key_mapper(KeyMapper::unprefixed());
+        assert_eq!(
+            source.get("db.host").unwrap(),
+            Some("localhost".to_string())
+        );
+        let source = env_snapshot(&vars).with_key_mapper(|key: &str| key.replace('.', "__"));
+        assert_eq!(
+            source.get("APP.DB.MAX_CONNS").unwrap(),
+            Some("16".to_string())
+        );
+        assert_eq!(env_snapshot(&vars).env_key("db.host"), "VCFG_DB__HOST");
+    }
+
+    #[test]
+    pub fn get_secret_error_redacted() {
+        let conf = Conf::with_source(
+            "vcfg",
+            InMemorySource::new("vcfg").set("VCFG_PIN", "hunter2"),
+        )
+        .entry(ConfEntry::<u16>::new("pin").secret());
+        let err = conf.get::<u16>("pin").unwrap_err();
+        assert_eq!(
+            err,
+            ConfError::ValParseFailed {
+                key: "pin".to_string(),
+                val: "<redacted>".to_string(),
+                reason: "<redacted>".to_string(),
+                description: None,
+            }
+        );
+        assert!(!format!("{err} {err:?}").contains("hunter2"));
+
+        let conf = Conf::with_source(
+            "vcfg",
+            InMemorySource::new("vcfg")
+                .set("VCFG_TLS", "hunter2")
+                .set("VCFG_TIMEOUT", "hunter2"),
+        )
+        .entry(ConfEntry::<ConfBool>::new("tls").secret())
+        .entry(ConfEntry::<ConfDuration>::new("timeout").secret());
+        let err = conf.get::<ConfBool>("tls").unwrap_err();
+        assert!(matches!(err, ConfError::ValParseFailed { .. }));
+        assert!(!format!("{err} {err:?}").contains("hunter2"));
+        let err = conf.get::<ConfDuration>("timeout").unwrap_err();
+        assert!(!format!("{err} {err:?}").contains("hunter2"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            let val = std::ffi::OsStr::from_bytes(b"hunter2\xff");
+            let source = EnvSource::new(DEFAULT_NAME)
+                .with_vars([("VCFG_REDACTED_PIN".to_string(), val.into())].into());
+            let conf = Conf::with_source(DEFAULT_NAME, source)
+                .entry(ConfEntry::<u16>::new("redacted_pin").secret());
+            let err = conf.get::<u16>("redacted_pin").unwrap_err();
+            assert!(matches!(err, ConfError::EnvLookupFailed { .. }));
+            assert!(!format!("{err} {err:?}").contains("hunter2"));
+        }
+    }
+
+    #[test]
+    pub fn snapshot_frozen() {
+        std::env::set_var("VCFG_SNAPSHOT_LEVEL", "info");
+        std::env::remove_var("VCFG_SNAPSHOT_TOKEN");
         let conf = Conf::default()
-            .string("greeting", Some("Hello"))
-            .string("name", None);
-        std::env::set_var("VCFG_NAME", "world");
-        let greet = |g: String, n: String| format!("{}, {}!", g, n);
-        let conf_greet = || {
-            greet(
-                conf.require("greeting").unwrap(),
-                conf.require("name").unwrap(),
-            )
+            .string("snapshot_level", None)
+            .uint("snapshot_port", Some(8080))
+            .string("snapshot_user", None);
+        let snapshot = conf.snapshot().unwrap();
+        std::env::set_var("VCFG_SNAPSHOT_LEVEL", "debug");
+        assert_eq!(
+            snapshot.get::<String>("snapshot_level").unwrap(),
+            Some("info".to_string())
+        );
+        assert_eq!(
+            conf.get_string("snapshot_level").unwrap(),
+            Some("debug".to_string())
+        );
+        assert_eq!(snapshot.require::<u64>("snapshot_port").unwrap(), 8080);
+        assert_eq!(snapshot.get::<String>("snapshot_user").unwrap(), None);
+        assert!(snapshot.get::<i64>("snapshot_port").is_err());
+
+        let conf = conf
+            .require_entry(ConfEntry::<String>::new("snapshot_token"))
+            .require_entry(ConfEntry::<String>::new("snapshot_user_id"));
+        assert_eq!(
+            conf.snapshot().unwrap_err(),
+            ConfError::ValidationFailed {
+                missing: vec!["snapshot_token".to_string(), "snapshot_user_id".to_string()]
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_env_from_map() {
+        let vars = |port: &str| {
+            std::collections::HashMap::from([("VCFG_MAP_PORT".to_string(), port.to_string())])
         };
-        assert_eq!(conf_greet(), "Hello, world!");
-        std::env::set_var("VCFG_NAME", "xela");
-        std::env::set_var("VCFG_GREETING", "Hail");
-        assert_eq!(conf_greet(), "Hail, xela!");
+        let first = Conf::with_source(DEFAULT_NAME, EnvSource::from_map(DEFAULT_NAME, vars("80")))
+            .uint("map_port", None);
+        let second = Conf::with_source(
+            DEFAULT_NAME,
+            EnvSource::from_map(DEFAULT_NAME, vars("8080")),
+        )
+        .uint("map_port", None);
+        assert_eq!(first.get_uint("map_port").unwrap(), Some(80));
+        assert_eq!(second.get_uint("map_port").unwrap(), Some(8080));
+        assert_eq!(std::env::var("VCFG_MAP_PORT").ok(), None);
     }
 
     #[test]
-    pub fn require_int_default() {
-        clean_env();
-        let conf = Conf::default().uint("count", Some(3));
-        let count = conf.require_uint("count").unwrap();
-        assert_eq!(count, 3u64);
+    pub fn deserialize_settings() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Db {
+            host: String,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Settings {
+            port: u16,
+            name: String,
+            db: Db,
+            debug: Option<bool>,
+        }
+
+        let source = env_snapshot(&[
+            ("VCFG_PORT", "8080"),
+            ("VCFG_NAME", "xela"),
+            ("VCFG_DB__HOST", "localhost"),
+        ]);
+        let conf = Conf::with_source("vcfg", source)
+            .uint("port", None)
+            .string("name", None)
+            .string("db.host", None)
+            .boolean("debug", None);
+        assert_eq!(
+            conf.deserialize::<Settings>().unwrap(),
+            Settings {
+                port: 8080,
+                name: "xela".to_string(),
+                db: Db {
+                    host: "localhost".to_string()
+                },
+                debug: None,
+            }
+        );
+
+        let conf = conf.require_entry(ConfEntry::<String>::new("token"));
+        assert_eq!(
+            conf.deserialize::<Settings>().unwrap_err(),
+            ConfError::ValidationFailed {
+                missing: vec!["token".to_string()]
+            }
+        );
+
+        let conf =
+            Conf::with_source("vcfg", env_snapshot(&[("VCFG_PORT", "8080")])).uint("port", None);
+        assert!(matches!(
+            conf.deserialize::<Settings>().unwrap_err(),
+            ConfError::DeserializeFailed { reason } if reason.contains("missing field `name`")
+        ));
+    }
+
+    #[test]
+    pub fn get_env_snapshot() {
+        std::env::set_var("VCFG_FROZEN_LEVEL", "info");
+        std::env::set_var("VCFG_FROZENX", "other");
+        let source = EnvSource::snapshot(DEFAULT_NAME);
+        std::env::set_var("VCFG_FROZEN_LEVEL", "debug");
+        let snapshot = source.snapshot.as_ref().unwrap();
+        assert_eq!(snapshot["VCFG_FROZEN_LEVEL"], "info");
+        assert!(snapshot.contains_key("VCFG_FROZENX"));
+        assert!(!snapshot.keys().any(|k| !k.starts_with("VCFG_")));
+        let conf = Conf::with_source(DEFAULT_NAME, source).string("frozen_level", None);
+        assert_eq!(
+            conf.get_string("frozen_level").unwrap(),
+            Some("info".to_string())
+        );
     }
 }
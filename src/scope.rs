@@ -0,0 +1,329 @@
+use crate::{Conf, ConfSource, ConfValue, EnvSource, Result, Secret, ValueOrigin};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A view of the entries of a [`Conf`] under a key prefix, from [`Conf::scope`]. Keys are
+/// relative to the prefix, so a database module can read `host` without knowing the
+/// application stores it as `db.host`. Entries outside the prefix can't be reached through the
+/// view. Defaults, types and validation come from the parent's entries, and errors name the
+/// full key.
+///
+/// ```
+/// use voidconf::{Conf, ConfError, ConfSource, InMemorySource};
+///
+/// let source = InMemorySource::new("vcfg").set("VCFG_DB__HOST", "localhost");
+/// let conf = Conf::with_source("vcfg", source)
+///     .string("db.host", None)
+///     .uint("db.port", Some(5432))
+///     .uint("port", Some(8080));
+/// let db = conf.scope("db");
+/// assert_eq!(db.get_string("host")?, Some("localhost".to_string()));
+/// assert_eq!(db.require_uint("port")?, 5432);
+/// # Ok::<(), ConfError>(())
+/// ```
+pub struct ScopedConf<'a, S: ConfSource = EnvSource> {
+    conf: &'a Conf<S>,
+    /// Prefix of the keys in view, without a trailing `.`.
+    pub prefix: String,
+}
+
+impl<S: ConfSource> Conf<S> {
+    /// View the entries under a key prefix, like `db` for `db.host` and `db.port`, with keys
+    /// relative to it. See [`ScopedConf`].
+    pub fn scope(&self, prefix: &str) -> ScopedConf<'_, S> {
+        ScopedConf {
+            conf: self,
+            prefix: prefix.trim_end_matches('.').to_string(),
+        }
+    }
+}
+
+impl<'a, S: ConfSource> ScopedConf<'a, S> {
+    /// Narrow the view further, so `conf.scope("db").scope("pool")` reads `db.pool.*` keys.
+    pub fn scope(&self, prefix: &str) -> ScopedConf<'a, S> {
+        self.conf.scope(&self.key(prefix))
+    }
+
+    /// Full key in the parent config for a key relative to the prefix.
+    fn key(&self, key: &str) -> String {
+        format!("{}.{key}", self.prefix)
+    }
+
+    /// Whether an entry with the given relative key is defined.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.conf.contains_key(&self.key(key))
+    }
+
+    /// Iterate over the relative keys of the entries in view, in sorted order.
+    pub fn keys(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.conf
+            .keys()
+            .filter_map(|key| key.strip_prefix(self.prefix.as_str())?.strip_prefix('.'))
+    }
+
+    /// Get a value, see [`Conf::get`].
+    pub fn get<V: ConfValue + 'static>(&self, key: &str) -> Result<Option<V>> {
+        self.conf.get(&self.key(key))
+    }
+
+    /// Get a value along with where it came from, see [`Conf::get_with_source`].
+    pub fn get_with_source<V: ConfValue + 'static>(
+        &self,
+        key: &str,
+    ) -> Result<Option<(V, ValueOrigin)>> {
+        self.conf.get_with_source(&self.key(key))
+    }
+
+    /// Require a value, see [`Conf::require`].
+    pub fn require<V: ConfValue + 'static>(&self, key: &str) -> Result<V> {
+        self.conf.require(&self.key(key))
+    }
+
+    /// Get a string value.
+    pub fn get_string(&self, key: &str) -> Result<Option<String>> {
+        self.conf.get_string(&self.key(key))
+    }
+
+    /// Get a secret string value.
+    pub fn get_secret(&self, key: &str) -> Result<Option<Secret<String>>> {
+        self.conf.get_secret(&self.key(key))
+    }
+
+    /// Get a byte (`u8`) value.
+    pub fn get_byte(&self, key: &str) -> Result<Option<u8>> {
+        self.conf.get_byte(&self.key(key))
+    }
+
+    /// Get an int (`i64`) value.
+    pub fn get_int(&self, key: &str) -> Result<Option<i64>> {
+        self.conf.get_int(&self.key(key))
+    }
+
+    /// Get a uint (`u64`) value.
+    pub fn get_uint(&self, key: &str) -> Result<Option<u64>> {
+        self.conf.get_uint(&self.key(key))
+    }
+
+    /// Get a big int (`i128`) value.
+    pub fn get_big_int(&self, key: &str) -> Result<Option<i128>> {
+        self.conf.get_big_int(&self.key(key))
+    }
+
+    /// Get a big uint (`u128`) value.
+    pub fn get_big_uint(&self, key: &str) -> Result<Option<u128>> {
+        self.conf.get_big_uint(&self.key(key))
+    }
+
+    /// Get a size (`usize`) value.
+    pub fn get_size(&self, key: &str) -> Result<Option<usize>> {
+        self.conf.get_size(&self.key(key))
+    }
+
+    /// Get a float (`f64`) value.
+    pub fn get_float(&self, key: &str) -> Result<Option<f64>> {
+        self.conf.get_float(&self.key(key))
+    }
+
+    /// Get a finite float (`f64`) value added with [`Conf::finite_float`].
+    pub fn get_finite_float(&self, key: &str) -> Result<Option<f64>> {
+        self.conf.get_finite_float(&self.key(key))
+    }
+
+    /// Get a bool value.
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>> {
+        self.conf.get_bool(&self.key(key))
+    }
+
+    /// Get a path value as configured.
+    pub fn get_path(&self, key: &str) -> Result<Option<PathBuf>> {
+        self.conf.get_path(&self.key(key))
+    }
+
+    /// Get a path value with a leading `~` expanded and relative paths resolved against the
+    /// current working directory, see [`ConfPath::normalize`].
+    pub fn get_path_canonical(&self, key: &str) -> Result<Option<PathBuf>> {
+        self.conf.get_path_canonical(&self.key(key))
+    }
+
+    /// Get a socket address value.
+    pub fn get_socket_addr(&self, key: &str) -> Result<Option<SocketAddr>> {
+        self.conf.get_socket_addr(&self.key(key))
+    }
+
+    /// Get a UTC timestamp value.
+    #[cfg(feature = "chrono")]
+    pub fn get_datetime(&self, key: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        self.conf.get_datetime(&self.key(key))
+    }
+
+    /// Get a URL value.
+    #[cfg(feature = "url")]
+    pub fn get_url(&self, key: &str) -> Result<Option<url::Url>> {
+        self.conf.get_url(&self.key(key))
+    }
+
+    /// Get a UUID value.
+    #[cfg(feature = "uuid")]
+    pub fn get_uuid(&self, key: &str) -> Result<Option<uuid::Uuid>> {
+        self.conf.get_uuid(&self.key(key))
+    }
+
+    /// Get a duration value.
+    pub fn get_duration(&self, key: &str) -> Result<Option<Duration>> {
+        self.conf.get_duration(&self.key(key))
+    }
+
+    /// Get a comma separated list value. An empty value is an empty list, not `None`.
+    pub fn get_list<V: ConfValue + 'static>(&self, key: &str) -> Result<Option<Vec<V>>> {
+        self.conf.get_list(&self.key(key))
+    }
+
+    /// Get a map value of comma separated `key=value` pairs. An empty value is an empty map, not
+    /// `None`.
+    pub fn get_map<K, V>(&self, key: &str) -> Result<Option<HashMap<K, V>>>
+    where
+        K: ConfValue + Eq + Hash + 'static,
+        V: ConfValue + 'static,
+    {
+        self.conf.get_map(&self.key(key))
+    }
+
+    /// Require a string value.
+    pub fn require_string(&self, key: &str) -> Result<String> {
+        self.conf.require_string(&self.key(key))
+    }
+
+    /// Require a secret string value.
+    pub fn require_secret(&self, key: &str) -> Result<Secret<String>> {
+        self.conf.require_secret(&self.key(key))
+    }
+
+    /// Require a byte (`u8`) value.
+    pub fn require_byte(&self, key: &str) -> Result<u8> {
+        self.conf.require_byte(&self.key(key))
+    }
+
+    /// Require an int (`i64`) value.
+    pub fn require_int(&self, key: &str) -> Result<i64> {
+        self.conf.require_int(&self.key(key))
+    }
+
+    /// Require a uint (`u64`) value.
+    pub fn require_uint(&self, key: &str) -> Result<u64> {
+        self.conf.require_uint(&self.key(key))
+    }
+
+    /// Require a big int (`i128`) value.
+    pub fn require_big_int(&self, key: &str) -> Result<i128> {
+        self.conf.require_big_int(&self.key(key))
+    }
+
+    /// Require a big uint (`u128`) value.
+    pub fn require_big_uint(&self, key: &str) -> Result<u128> {
+        self.conf.require_big_uint(&self.key(key))
+    }
+
+    /// Require a size (`usize`) value.
+    pub fn require_size(&self, key: &str) -> Result<usize> {
+        self.conf.require_size(&self.key(key))
+    }
+
+    /// Require a float (`f64`) value.
+    pub fn require_float(&self, key: &str) -> Result<f64> {
+        self.conf.require_float(&self.key(key))
+    }
+
+    /// Require a finite float (`f64`) value added with [`Conf::finite_float`].
+    pub fn require_finite_float(&self, key: &str) -> Result<f64> {
+        self.conf.require_finite_float(&self.key(key))
+    }
+
+    /// Require a bool value.
+    pub fn require_bool(&self, key: &str) -> Result<bool> {
+        self.conf.require_bool(&self.key(key))
+    }
+
+    /// Require a path value as configured.
+    pub fn require_path(&self, key: &str) -> Result<PathBuf> {
+        self.conf.require_path(&self.key(key))
+    }
+
+    /// Require a socket address value.
+    pub fn require_socket_addr(&self, key: &str) -> Result<SocketAddr> {
+        self.conf.require_socket_addr(&self.key(key))
+    }
+
+    /// Require a UTC timestamp value.
+    #[cfg(feature = "chrono")]
+    pub fn require_datetime(&self, key: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+        self.conf.require_datetime(&self.key(key))
+    }
+
+    /// Require a URL value.
+    #[cfg(feature = "url")]
+    pub fn require_url(&self, key: &str) -> Result<url::Url> {
+        self.conf.require_url(&self.key(key))
+    }
+
+    /// Require a UUID value.
+    #[cfg(feature = "uuid")]
+    pub fn require_uuid(&self, key: &str) -> Result<uuid::Uuid> {
+        self.conf.require_uuid(&self.key(key))
+    }
+
+    /// Require a duration value.
+    pub fn require_duration(&self, key: &str) -> Result<Duration> {
+        self.conf.require_duration(&self.key(key))
+    }
+
+    /// Require a comma separated list value.
+    pub fn require_list<V: ConfValue + 'static>(&self, key: &str) -> Result<Vec<V>> {
+        self.conf.require_list(&self.key(key))
+    }
+
+    /// Require a map value of comma separated `key=value` pairs.
+    pub fn require_map<K, V>(&self, key: &str) -> Result<HashMap<K, V>>
+    where
+        K: ConfValue + Eq + Hash + 'static,
+        V: ConfValue + 'static,
+    {
+        self.conf.require_map(&self.key(key))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Conf, ConfError, ConfSource, InMemorySource};
+
+    #[test]
+    pub fn get_scoped() {
+        let source = InMemorySource::new("vcfg")
+            .set("VCFG_DB__HOST", "localhost")
+            .set("VCFG_DB__POOL__SIZE", "4")
+            .set("VCFG_PORT", "8080");
+        let conf = Conf::with_source("vcfg", source)
+            .string("db.host", None)
+            .uint("db.port", Some(5432))
+            .uint("db.pool.size", None)
+            .string("dbx", None)
+            .uint("port", None);
+        let db = conf.scope("db");
+        assert_eq!(
+            db.get_string("host").unwrap(),
+            Some("localhost".to_string())
+        );
+        assert_eq!(db.require_uint("port").unwrap(), 5432);
+        assert_eq!(db.scope("pool").get_uint("size").unwrap(), Some(4));
+        assert_eq!(db.keys().collect::<Vec<_>>(), ["host", "pool.size", "port"]);
+        assert!(db.contains_key("pool.size"));
+        assert!(!db.contains_key("dbx"));
+        assert_eq!(
+            db.get_string("dbx").unwrap_err(),
+            ConfError::key_not_found("db.dbx")
+        );
+        assert_eq!(conf.scope("db.").get_uint("port").unwrap(), Some(5432));
+    }
+}
@@ -0,0 +1,182 @@
+//! Additional [`ConfSource`](crate::ConfSource) implementations beyond [`EnvSource`](crate::EnvSource).
+#[cfg(any(feature = "secretsmanager", feature = "ssm"))]
+mod aws;
+mod cached;
+mod chained;
+mod cli;
+mod command;
+#[cfg(feature = "consul")]
+mod consul;
+mod dir;
+mod dotenv;
+mod embedded;
+mod file;
+#[cfg(feature = "gcp")]
+mod gcp;
+mod git;
+#[cfg(feature = "http")]
+mod http;
+mod ini;
+mod json;
+#[cfg(feature = "keyring")]
+mod keyring;
+#[cfg(feature = "kube")]
+mod kube;
+mod memory;
+mod onepassword;
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(all(windows, feature = "registry"))]
+mod registry;
+mod retry;
+#[cfg(feature = "secretsmanager")]
+mod secretsmanager;
+#[cfg(feature = "sops")]
+mod sops;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "ssm")]
+mod ssm;
+mod stdin;
+mod systemd;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "vault")]
+mod vault;
+#[cfg(feature = "toml")]
+mod xdg;
+#[cfg(feature = "yaml")]
+mod yaml;
+#[cfg(feature = "zookeeper")]
+mod zookeeper;
+
+pub use self::cached::CachedSource;
+pub use self::chained::ChainedSource;
+pub use self::cli::CliSource;
+pub use self::command::CommandSource;
+#[cfg(feature = "consul")]
+pub use self::consul::ConsulSource;
+pub use self::dir::DirSource;
+pub(crate) use self::dotenv::load_dotenv;
+pub use self::dotenv::DotenvSource;
+pub use self::embedded::StaticSource;
+pub use self::file::FileSource;
+#[cfg(feature = "gcp")]
+pub use self::gcp::GcpSecretSource;
+pub use self::git::GitStyleSource;
+#[cfg(feature = "http")]
+pub use self::http::HttpSource;
+pub use self::ini::IniSource;
+#[allow(deprecated)]
+pub use self::json::{JsonFileSource, JsonSource};
+#[cfg(feature = "keyring")]
+pub use self::keyring::KeyringSource;
+#[cfg(feature = "kube")]
+pub use self::kube::KubeConfigMapSource;
+pub use self::memory::{InMemorySource, MapSource};
+pub use self::onepassword::OnePasswordSource;
+#[cfg(feature = "redis")]
+pub use self::redis::RedisSource;
+#[cfg(all(windows, feature = "registry"))]
+pub use self::registry::{RegistryHive, RegistrySource};
+pub use self::retry::RetryingSource;
+#[cfg(feature = "secretsmanager")]
+pub use self::secretsmanager::SecretsManagerSource;
+#[cfg(feature = "sops")]
+pub use self::sops::SopsSource;
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteSource;
+#[cfg(feature = "ssm")]
+pub use self::ssm::SsmSource;
+pub use self::stdin::StdinSource;
+pub use self::systemd::SystemdCredentialSource;
+#[cfg(feature = "toml")]
+pub use self::toml::TomlSource;
+#[cfg(feature = "vault")]
+pub use self::vault::VaultSource;
+#[cfg(feature = "toml")]
+pub use self::xdg::XdgSource;
+#[cfg(feature = "yaml")]
+pub use self::yaml::YamlSource;
+#[cfg(feature = "zookeeper")]
+pub use self::zookeeper::ZkSource;
+
+/// Translate a byte offset into `contents` to a 1-based line and column.
+#[cfg(feature = "toml")]
+pub(crate) fn line_col(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
+/// Trim a single trailing newline, as left by most editors and `echo`.
+pub(crate) fn trim_newline(mut val: String) -> String {
+    if val.ends_with('\n') {
+        val.pop();
+        if val.ends_with('\r') {
+            val.pop();
+        }
+    }
+    val
+}
+
+/// A minimal HTTP server for testing remote sources.
+#[cfg(all(
+    test,
+    any(
+        feature = "consul",
+        feature = "gcp",
+        feature = "http",
+        feature = "kube",
+        feature = "secretsmanager",
+        feature = "ssm",
+        feature = "vault"
+    )
+))]
+pub(crate) mod mock {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    /// Serve the given `(status, body)` responses, one per connection, in order. Returns the base
+    /// URL and a handle yielding the request line, headers and body of each request received.
+    pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = String::new();
+                let mut reader = BufReader::new(&mut stream);
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, val)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = val.trim().parse().unwrap();
+                        }
+                    }
+                    request.push_str(&line);
+                }
+                let mut body_in = vec![0; length];
+                reader.read_exact(&mut body_in).unwrap();
+                request.push_str("\r\n");
+                request.push_str(&String::from_utf8(body_in).unwrap());
+                requests.push(request);
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+}
@@ -0,0 +1,219 @@
+//! Minimal client for AWS services speaking the JSON 1.1 protocol, shared by the AWS sources.
+use crate::{ConfError, Result};
+use aws_credential_types::Credentials;
+use aws_sigv4::http_request::{sign, SignableBody, SignableRequest, SigningSettings};
+use aws_smithy_runtime_api::client::identity::Identity;
+use serde::Deserialize;
+use std::time::{Duration, SystemTime};
+
+/// Region used when neither `AWS_REGION` nor `AWS_DEFAULT_REGION` is set.
+pub(crate) const DEFAULT_REGION: &str = "us-east-1";
+
+/// Host of the ECS container credentials endpoint.
+const CONTAINER_CREDENTIALS_HOST: &str = "http://169.254.170.2";
+
+/// Error codes meaning the request was throttled and may be retried.
+const THROTTLING_CODES: [&str; 4] = [
+    "ThrottlingException",
+    "ThrottledException",
+    "TooManyRequestsException",
+    "RequestLimitExceeded",
+];
+
+/// Error codes meaning the credentials were rejected.
+const AUTH_CODES: [&str; 6] = [
+    "AccessDeniedException",
+    "ExpiredTokenException",
+    "IncompleteSignature",
+    "InvalidClientTokenId",
+    "InvalidSignatureException",
+    "UnrecognizedClientException",
+];
+
+/// Client for a single AWS service.
+pub(crate) struct AwsClient {
+    /// Signing name of the service, e.g. `ssm`.
+    pub service: &'static str,
+    /// Prefix of the `X-Amz-Target` header, e.g. `AmazonSSM`.
+    pub target_prefix: &'static str,
+    pub region: String,
+    /// Endpoint override, for testing or VPC endpoints.
+    pub endpoint: Option<String>,
+    /// Explicit credentials. When unset they are resolved from the environment on each call.
+    pub credentials: Option<Credentials>,
+    /// Number of retries for throttled requests.
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each subsequent retry.
+    pub backoff: Duration,
+    agent: ureq::Agent,
+}
+
+/// Error body returned by AWS JSON services.
+#[derive(Deserialize)]
+struct ErrorBody {
+    #[serde(rename = "__type", default)]
+    code: String,
+    #[serde(alias = "Message", default)]
+    message: String,
+}
+
+/// Credentials returned by the ECS container credentials endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContainerCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    token: Option<String>,
+}
+
+impl AwsClient {
+    /// Create a client with the region from the environment and 3 retries.
+    pub fn new(service: &'static str, target_prefix: &'static str) -> Self {
+        Self {
+            service,
+            target_prefix,
+            region: std::env::var("AWS_REGION")
+                .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+                .unwrap_or_else(|_| DEFAULT_REGION.to_string()),
+            endpoint: None,
+            credentials: None,
+            retries: 3,
+            backoff: Duration::from_millis(100),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(10))
+                .build(),
+        }
+    }
+
+    /// Service endpoint URL.
+    pub fn endpoint(&self) -> String {
+        self.endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://{}.{}.amazonaws.com", self.service, self.region))
+    }
+
+    /// Resolve credentials: explicit, then `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY`, then the
+    /// ECS container credentials endpoint.
+    fn resolve_credentials(&self) -> Result<Credentials> {
+        if let Some(credentials) = &self.credentials {
+            return Ok(credentials.clone());
+        }
+        if let (Ok(key), Ok(secret)) = (
+            std::env::var("AWS_ACCESS_KEY_ID"),
+            std::env::var("AWS_SECRET_ACCESS_KEY"),
+        ) {
+            let token = std::env::var("AWS_SESSION_TOKEN").ok();
+            return Ok(Credentials::new(key, secret, token, None, "environment"));
+        }
+        let url = match (
+            std::env::var("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI"),
+            std::env::var("AWS_CONTAINER_CREDENTIALS_FULL_URI"),
+        ) {
+            (Ok(uri), _) => format!("{CONTAINER_CREDENTIALS_HOST}{uri}"),
+            (_, Ok(url)) => url,
+            _ => {
+                return Err(ConfError::auth_failed(
+                    self.endpoint(),
+                    "no AWS credentials found",
+                ))
+            }
+        };
+        let mut request = self.agent.get(&url);
+        if let Ok(token) = std::env::var("AWS_CONTAINER_AUTHORIZATION_TOKEN") {
+            request = request.set("Authorization", &token);
+        }
+        let credentials: ContainerCredentials = request
+            .call()
+            .map_err(|e| e.to_string())
+            .and_then(|response| response.into_string().map_err(|e| e.to_string()))
+            .and_then(|body| serde_json::from_str(&body).map_err(|e| e.to_string()))
+            .map_err(|reason| ConfError::auth_failed(&url, reason))?;
+        Ok(Credentials::new(
+            credentials.access_key_id,
+            credentials.secret_access_key,
+            credentials.token,
+            None,
+            "container",
+        ))
+    }
+
+    /// Call an operation, retrying throttled requests with exponential backoff. Errors with one
+    /// of the `not_found` codes resolve to `None`.
+    pub fn call(
+        &self,
+        operation: &str,
+        body: &serde_json::Value,
+        not_found: &[&str],
+    ) -> Result<Option<serde_json::Value>> {
+        let url = self.endpoint();
+        let target = format!("{}.{}", self.target_prefix, operation);
+        let body = body.to_string();
+        let identity: Identity = self.resolve_credentials()?.into();
+        let mut attempt = 0;
+        loop {
+            let headers = [
+                ("content-type", "application/x-amz-json-1.1"),
+                ("x-amz-target", target.as_str()),
+            ];
+            let params = aws_sigv4::sign::v4::SigningParams::builder()
+                .identity(&identity)
+                .region(&self.region)
+                .name(self.service)
+                .time(SystemTime::now())
+                .settings(SigningSettings::default())
+                .build()
+                .map_err(|e| ConfError::request_failed(&url, None, e.to_string()))?
+                .into();
+            let signable = SignableRequest::new(
+                "POST",
+                url.as_str(),
+                headers.into_iter(),
+                SignableBody::Bytes(body.as_bytes()),
+            )
+            .map_err(|e| ConfError::request_failed(&url, None, e.to_string()))?;
+            let (instructions, _) = sign(signable, &params)
+                .map_err(|e| ConfError::request_failed(&url, None, e.to_string()))?
+                .into_parts();
+            let mut request = self.agent.post(&url);
+            for (name, val) in headers.into_iter().chain(instructions.headers()) {
+                request = request.set(name, val);
+            }
+            let (status, response) = match request.send_string(&body) {
+                Ok(response) => (response.status(), response),
+                Err(ureq::Error::Status(status, response)) => (status, response),
+                Err(e) => return Err(ConfError::request_failed(url, None, e.to_string())),
+            };
+            let text = response
+                .into_string()
+                .map_err(|e| ConfError::request_failed(&url, Some(status), e.to_string()))?;
+            if status < 300 {
+                return serde_json::from_str(&text)
+                    .map(Some)
+                    .map_err(|e| ConfError::request_failed(url, Some(status), e.to_string()));
+            }
+            let error: ErrorBody = serde_json::from_str(&text).unwrap_or(ErrorBody {
+                code: String::new(),
+                message: text,
+            });
+            let code = error.code.rsplit('#').next().unwrap_or_default();
+            let throttled = status == 429 || THROTTLING_CODES.contains(&code);
+            if throttled && attempt < self.retries {
+                std::thread::sleep(self.backoff * 2u32.pow(attempt));
+                attempt += 1;
+            } else if not_found.contains(&code) {
+                return Ok(None);
+            } else if AUTH_CODES.contains(&code) {
+                return Err(ConfError::auth_failed(
+                    url,
+                    format!("{code}: {}", error.message),
+                ));
+            } else {
+                return Err(ConfError::request_failed(
+                    url,
+                    Some(status),
+                    format!("{code}: {}", error.message),
+                ));
+            }
+        }
+    }
+}
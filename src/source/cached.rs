@@ -0,0 +1,217 @@
+use crate::{ConfSource, Result};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// A [`ConfSource`] which memoizes lookups in another source, so each key is only fetched once.
+/// Misses are cached too; errors are not. Cached values are frozen until
+/// [invalidated](CachedSource::invalidate), one key or
+/// [all at once](CachedSource::invalidate_all), or until they expire if a
+/// [TTL](CachedSource::with_ttl) is set, so remote sources are re-queried periodically.
+///
+/// Usually created with [`Conf::with_cache`](crate::Conf::with_cache) or
+/// [`Conf::with_cache_ttl`](crate::Conf::with_cache_ttl).
+pub struct CachedSource<S> {
+    /// Source being cached.
+    pub source: S,
+    /// How long values are cached for, or `None` for forever.
+    pub ttl: Option<Duration>,
+    /// How long misses are cached for, if different from [`CachedSource::ttl`].
+    pub negative_ttl: Option<Duration>,
+    cache: RwLock<HashMap<String, (Option<String>, Instant)>>,
+}
+
+impl<S: ConfSource> CachedSource<S> {
+    /// Create a new [`CachedSource`] wrapping an already initialized source.
+    pub fn from_source(source: S) -> Self {
+        Self {
+            source,
+            ttl: None,
+            negative_ttl: None,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Re-query the wrapped source for a key once its cached value is older than `ttl`. Misses
+    /// expire after the same time, unless [`CachedSource::with_negative_ttl`] is set.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Re-query the wrapped source for a missing key once its cached miss is older than `ttl`,
+    /// e.g. to pick up newly created keys sooner than changed ones.
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = Some(ttl);
+        self
+    }
+
+    /// Whether a cached value fetched at `at` has expired.
+    fn expired(&self, v: &Option<String>, at: Instant) -> bool {
+        let ttl = match v {
+            Some(_) => self.ttl,
+            None => self.negative_ttl.or(self.ttl),
+        };
+        ttl.is_some_and(|ttl| at.elapsed() >= ttl)
+    }
+
+    /// Forget the cached value for a key, so the next lookup fetches it again.
+    pub fn invalidate(&self, key: &str) {
+        self.cache
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+    }
+
+    /// Forget all cached values, so every key is fetched again.
+    pub fn invalidate_all(&self) {
+        self.cache
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl<S: ConfSource> ConfSource for CachedSource<S> {
+    /// Create a new [`CachedSource`] wrapping `S::new(name)`.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_source(S::new(name))
+    }
+
+    /// Create a new [`CachedSource`] wrapping `S::try_new(name)`.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        S::try_new(name).map(Self::from_source)
+    }
+
+    /// Return the cached value if it hasn't expired, or look it up in the wrapped source and
+    /// cache it.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        if let Some((v, at)) = self
+            .cache
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+        {
+            if !self.expired(v, *at) {
+                return Ok(v.clone());
+            }
+        }
+        let v = self.source.get(key)?;
+        self.cache
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.to_string(), (v.clone(), Instant::now()));
+        Ok(v)
+    }
+
+    /// List the keys of the wrapped source. Not cached.
+    fn keys(&self) -> Result<Vec<String>> {
+        self.source.keys()
+    }
+
+    /// Describe the key in the wrapped source.
+    fn describe(&self, key: &str) -> String {
+        self.source.describe(key)
+    }
+
+    /// Name the key's env var in the wrapped source.
+    fn env_var(&self, key: &str) -> Option<String> {
+        self.source.env_var(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Conf, ConfError, FileSource, MapSource};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts lookups, failing for the key `broken`.
+    struct CountingSource {
+        map: MapSource,
+        hits: AtomicUsize,
+    }
+
+    impl ConfSource for CountingSource {
+        fn new(_name: impl Into<String>) -> Self {
+            Self {
+                map: MapSource::from_iter([("port", "8080")]),
+                hits: AtomicUsize::new(0),
+            }
+        }
+
+        fn get(&self, key: &str) -> Result<Option<String>> {
+            self.hits.fetch_add(1, Ordering::SeqCst);
+            match key {
+                "broken" => Err(ConfError::source_io("counting", "broken")),
+                _ => self.map.get(key),
+            }
+        }
+    }
+
+    #[test]
+    pub fn get_cached_hits() {
+        let conf = Conf::<CountingSource>::new("vcfg")
+            .with_cache()
+            .uint("port", None)
+            .string("missing", None)
+            .string("broken", None);
+        let hits = || conf.source.source.hits.load(Ordering::SeqCst);
+        for _ in 0..3 {
+            assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+            assert_eq!(conf.get_string("missing").unwrap(), None);
+        }
+        assert_eq!(hits(), 2);
+        conf.get_string("broken").unwrap_err();
+        conf.get_string("broken").unwrap_err();
+        assert_eq!(hits(), 4);
+        conf.invalidate("port");
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(hits(), 5);
+        conf.clear_cache();
+        conf.get_uint("port").unwrap();
+        conf.source.invalidate_all();
+        conf.get_uint("port").unwrap();
+        conf.get_string("missing").unwrap();
+        assert_eq!(hits(), 8);
+    }
+
+    #[test]
+    pub fn get_cached_ttl() {
+        let conf = Conf::<CountingSource>::new("vcfg")
+            .with_cache_ttl(Duration::from_millis(200))
+            .uint("port", None)
+            .string("missing", None);
+        let hits = || conf.source.source.hits.load(Ordering::SeqCst);
+        conf.get_uint("port").unwrap();
+        conf.get_string("missing").unwrap();
+        conf.get_uint("port").unwrap();
+        conf.get_string("missing").unwrap();
+        assert_eq!(hits(), 2);
+        std::thread::sleep(Duration::from_millis(250));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        conf.get_string("missing").unwrap();
+        assert_eq!(hits(), 4);
+
+        let source = CachedSource::from_source(CountingSource::new("vcfg"))
+            .with_ttl(Duration::from_secs(60))
+            .with_negative_ttl(Duration::ZERO);
+        source.get("port").unwrap();
+        source.get("port").unwrap();
+        source.get("missing").unwrap();
+        source.get("missing").unwrap();
+        assert_eq!(source.source.hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    pub fn try_new_cached_malformed() {
+        let dir = tempfile::tempdir().unwrap();
+        let name: &'static str = dir.path().join("vcfg").to_str().unwrap().to_string().leak();
+        assert!(Conf::<CachedSource<FileSource>>::try_new(name).is_ok());
+        std::fs::write(format!("{name}.conf"), "not a var\n").unwrap();
+        let err = Conf::<CachedSource<FileSource>>::try_new(name)
+            .err()
+            .unwrap();
+        assert!(matches!(err, ConfError::SourceInit { .. }));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{ConfSource, EnvSource, FileSource, Result};
+
+/// A [`ConfSource`] which queries several sources in order, returning the first value found.
+/// Errors from any source are returned immediately rather than falling through.
+///
+/// Layers can be assembled fluently, highest priority first:
+///
+/// ```
+/// use voidconf::{ChainedSource, ConfSource, EnvSource, JsonSource};
+///
+/// let source = ChainedSource::with_sources("vcfg", Vec::new())
+///     .push(EnvSource::new("vcfg"))
+///     .push(JsonSource::from_path("/etc/vcfg/config.json"));
+/// ```
+pub struct ChainedSource {
+    /// Config name the chain was created with.
+    pub name: String,
+    /// Sources in priority order, highest first.
+    pub sources: Vec<Box<dyn ConfSource>>,
+}
+
+impl ChainedSource {
+    /// Create a new [`ChainedSource`] from the given sources, in priority order.
+    pub fn with_sources(name: impl Into<String>, sources: Vec<Box<dyn ConfSource>>) -> Self {
+        Self {
+            name: name.into(),
+            sources,
+        }
+    }
+
+    /// Add a source to the end of the chain, at a lower priority than all existing sources.
+    pub fn push(mut self, source: impl ConfSource + 'static) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+}
+
+impl ConfSource for ChainedSource {
+    /// Create a new [`ChainedSource`] where an [`EnvSource`] overrides a [`FileSource`], both
+    /// created with the given name.
+    fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let sources: Vec<Box<dyn ConfSource>> = vec![
+            Box::new(EnvSource::new(&name)),
+            Box::new(FileSource::new(&name)),
+        ];
+        Self::with_sources(name, sources)
+    }
+
+    /// Create a new [`ChainedSource`] like [`ConfSource::new`], initializing each layer with
+    /// [`ConfSource::try_new`] so a malformed file fails here.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        let name = name.into();
+        let sources: Vec<Box<dyn ConfSource>> = vec![
+            Box::new(EnvSource::try_new(&name)?),
+            Box::new(FileSource::try_new(&name)?),
+        ];
+        Ok(Self::with_sources(name, sources))
+    }
+
+    /// Query each source in order, returning the first value found.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        self.sources
+            .iter()
+            .find_map(|source| source.get(key).transpose())
+            .transpose()
+    }
+
+    /// List the keys of every source, sorted and deduplicated. Fails if any source can't list
+    /// its keys.
+    fn keys(&self) -> Result<Vec<String>> {
+        let mut keys = std::collections::BTreeSet::new();
+        for source in &self.sources {
+            keys.extend(source.keys()?);
+        }
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Describe the key in the first source with a value for it.
+    fn describe(&self, key: &str) -> String {
+        match self.get_described(key) {
+            Ok(Some((_, detail))) => detail,
+            _ => format!("key {key}"),
+        }
+    }
+
+    /// Query each source in order like [`ChainedSource::get`], describing the key in the source
+    /// which had the value.
+    fn get_described(&self, key: &str) -> Result<Option<(String, String)>> {
+        self.sources
+            .iter()
+            .find_map(|source| source.get_described(key).transpose())
+            .transpose()
+    }
+
+    /// Name the key's env var in the first layer which has one.
+    fn env_var(&self, key: &str) -> Option<String> {
+        self.sources.iter().find_map(|source| source.env_var(key))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Conf, ConfEntry, ConfError, InMemorySource, JsonSource, Origin};
+    use std::io::Write;
+
+    #[test]
+    pub fn get_chained_env_over_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"VCFG_CHAINED_NAME=file\nVCFG_CHAINED_GREETING=Hello\n")
+            .unwrap();
+        let vars = [("VCFG_CHAINED_NAME".to_string(), "env".into())];
+        let source = ChainedSource::with_sources(
+            "vcfg",
+            vec![
+                Box::new(EnvSource::new("vcfg").with_vars(vars.into_iter().collect())),
+                Box::new(FileSource::from_path("vcfg", file.path())),
+            ],
+        );
+        let conf = Conf::with_source("vcfg", source)
+            .string("chained_name", Some("default"))
+            .string("chained_greeting", None)
+            .string("chained_missing", Some("default"));
+        assert_eq!(
+            conf.get_string("chained_name").unwrap(),
+            Some("env".to_string())
+        );
+        assert_eq!(
+            conf.get_string("chained_greeting").unwrap(),
+            Some("Hello".to_string())
+        );
+        assert_eq!(
+            conf.get_string("chained_missing").unwrap(),
+            Some("default".to_string())
+        );
+    }
+
+    #[test]
+    pub fn get_chained_fallthrough() {
+        let source = ChainedSource::with_sources("vcfg", Vec::new())
+            .push(InMemorySource::new("vcfg"))
+            .push(InMemorySource::new("vcfg").set("VCFG_NAME", "xela"));
+        assert_eq!(source.get("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(source.get("greeting").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_chained_error() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"{\"name\": ").unwrap();
+        let source = ChainedSource::with_sources("vcfg", Vec::new())
+            .push(JsonSource::from_path(file.path()))
+            .push(InMemorySource::new("vcfg").set("VCFG_NAME", "xela"));
+        assert!(matches!(
+            source.get("name").unwrap_err(),
+            ConfError::FileParseFailed { .. }
+        ));
+    }
+
+    #[test]
+    pub fn get_chained_origin() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(br#"{"port": 8080, "workers": "many"}"#)
+            .unwrap();
+        let source = ChainedSource::with_sources("vcfg", Vec::new())
+            .push(
+                InMemorySource::new("vcfg")
+                    .set("VCFG_NAME", "xela")
+                    .set("VCFG_LOGIN", "root")
+                    .set("VCFG_THREADS", "lots"),
+            )
+            .push(JsonSource::from_path(file.path()));
+        let conf = Conf::with_source("vcfg", source)
+            .string("name", None)
+            .uint("port", None)
+            .uint("workers", None)
+            .entry(ConfEntry::<u64>::new("pool_size").alias("threads"))
+            .entry(ConfEntry::<String>::new("user").alias("login"))
+            .string("level", Some("info"));
+        assert_eq!(
+            conf.get_with_origin::<String>("name").unwrap(),
+            Some((
+                "xela".to_string(),
+                Origin::Source {
+                    detail: "in-memory var VCFG_NAME".to_string()
+                }
+            ))
+        );
+        assert_eq!(
+            conf.get_with_origin::<u64>("port").unwrap(),
+            Some((
+                8080,
+                Origin::Source {
+                    detail: format!("port in file {}", file.path().display())
+                }
+            ))
+        );
+        assert_eq!(
+            conf.get_with_origin::<String>("user").unwrap(),
+            Some((
+                "root".to_string(),
+                Origin::Source {
+                    detail: "in-memory var VCFG_LOGIN".to_string()
+                }
+            ))
+        );
+        assert_eq!(
+            conf.get_with_origin::<u64>("pool_size").unwrap_err(),
+            ConfError::ValParseFailed {
+                key: "pool_size".to_string(),
+                val: "lots".to_string(),
+                reason: "invalid digit found in string (from in-memory var VCFG_THREADS)"
+                    .to_string(),
+                description: None
+            }
+        );
+        assert_eq!(
+            conf.get_with_origin::<String>("level").unwrap(),
+            Some(("info".to_string(), Origin::Default))
+        );
+        assert!(matches!(
+            conf.get_with_origin::<u64>("workers").unwrap_err(),
+            ConfError::ValParseFailed { reason, .. }
+                if reason.ends_with(&format!("(from workers in file {})", file.path().display()))
+        ));
+    }
+
+    #[test]
+    pub fn get_chained_origin_single_lookup() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        /// Counts lookups made in the wrapped source.
+        struct CountingSource(InMemorySource, Arc<AtomicUsize>);
+
+        impl ConfSource for CountingSource {
+            fn new(name: impl Into<String>) -> Self {
+                Self(InMemorySource::new(name), Arc::default())
+            }
+
+            fn get(&self, key: &str) -> Result<Option<String>> {
+                self.1.fetch_add(1, Ordering::SeqCst);
+                self.0.get(key)
+            }
+
+            fn describe(&self, key: &str) -> String {
+                self.0.describe(key)
+            }
+        }
+
+        let (top, bottom) = (Arc::<AtomicUsize>::default(), Arc::<AtomicUsize>::default());
+        let source = ChainedSource::with_sources("vcfg", Vec::new())
+            .push(CountingSource(InMemorySource::new("vcfg"), top.clone()))
+            .push(CountingSource(
+                InMemorySource::new("vcfg").set("VCFG_LOGIN", "root"),
+                bottom.clone(),
+            ));
+        let conf = Conf::with_source("vcfg", source)
+            .entry(ConfEntry::<String>::new("user").alias("login"));
+        assert_eq!(
+            conf.get_with_origin::<String>("user").unwrap(),
+            Some((
+                "root".to_string(),
+                Origin::Source {
+                    detail: "in-memory var VCFG_LOGIN".to_string()
+                }
+            ))
+        );
+        assert_eq!(top.load(Ordering::SeqCst), 2);
+        assert_eq!(bottom.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    pub fn try_new_chained_malformed() {
+        let dir = tempfile::tempdir().unwrap();
+        let name: &'static str = dir.path().join("vcfg").to_str().unwrap().to_string().leak();
+        assert!(Conf::<ChainedSource>::try_new(name).is_ok());
+        std::fs::write(format!("{name}.conf"), "VCFG_NAME=xela\nnot a var\n").unwrap();
+        let err = Conf::<ChainedSource>::try_new(name).err().unwrap();
+        assert!(matches!(err, ConfError::SourceInit { .. }));
+    }
+}
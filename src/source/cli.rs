@@ -0,0 +1,158 @@
+use crate::{ConfSource, Result};
+use std::collections::BTreeMap;
+
+/// A [`ConfSource`] for resolving values from command-line flags like `--{name}-{key}=value` or
+/// `--{name}-{key} value`. Flags are matched case-insensitively with `-` and `_` treated as
+/// equivalent, so `--vcfg-max-conns` satisfies the `max_conns` key. A flag with no value is
+/// read as `true`. Flags without the prefix are ignored, as is anything after `--`.
+///
+/// Use [`CliSource::without_prefix`] to read plain flags like `--max-conns 16` instead. Put it
+/// first in a [`ChainedSource`](crate::ChainedSource) so flags override env and file config:
+///
+/// ```
+/// use voidconf::{ChainedSource, CliSource, Conf, ConfSource, EnvSource};
+///
+/// let source = ChainedSource::with_sources("vcfg", Vec::new())
+///     .push(CliSource::without_prefix(vec!["--port".into(), "9090".into()]))
+///     .push(EnvSource::new("vcfg"));
+/// let conf = Conf::with_source("vcfg", source).uint("port", Some(8080));
+/// assert_eq!(conf.get_uint("port").unwrap(), Some(9090));
+/// ```
+pub struct CliSource {
+    /// Normalized prefix flags must start with, e.g. `vcfg-`, or empty for
+    /// [`CliSource::without_prefix`].
+    pub prefix: String,
+    flags: BTreeMap<String, String>,
+}
+
+impl CliSource {
+    /// Create a new [`CliSource`] with the given name as a prefix, reading from explicit args.
+    /// The args should not include the program name.
+    pub fn from_args(name: impl Into<String>, args: Vec<String>) -> Self {
+        Self::parse(format!("{}-", normalize(&name.into())), args)
+    }
+
+    /// Create a new [`CliSource`] reading unprefixed flags from explicit args, so `--port 9090`
+    /// satisfies the `port` key. Flags which match no entry are never looked up, so this can
+    /// share args with another parser.
+    pub fn without_prefix(args: Vec<String>) -> Self {
+        Self::parse(String::new(), args)
+    }
+
+    /// Collect the flags starting with a normalized prefix.
+    fn parse(prefix: String, args: Vec<String>) -> Self {
+        let mut flags = BTreeMap::new();
+        let mut args = args.into_iter().take_while(|a| a != "--").peekable();
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                continue;
+            };
+            let (flag, val) = match flag.split_once('=') {
+                Some((f, v)) => (f.to_string(), v.to_string()),
+                None => {
+                    let val = args
+                        .next_if(|a| !a.starts_with("--"))
+                        .unwrap_or_else(|| "true".to_string());
+                    (flag.to_string(), val)
+                }
+            };
+            if let Some(key) = normalize(&flag).strip_prefix(&prefix) {
+                flags.insert(key.to_string(), val);
+            }
+        }
+        Self { prefix, flags }
+    }
+}
+
+impl ConfSource for CliSource {
+    /// Create a new [`CliSource`] with the given name as a prefix, reading from the process args.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_args(name, std::env::args().skip(1).collect())
+    }
+
+    /// Look up the value of the flag for the given key. When a flag is repeated the last one wins.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.flags.get(&normalize(key)).cloned())
+    }
+
+    /// List the keys of the flags given, with `_` separators.
+    fn keys(&self) -> Result<Vec<String>> {
+        Ok(self.flags.keys().map(|k| k.replace('-', "_")).collect())
+    }
+
+    /// Name the flag for the key.
+    fn describe(&self, key: &str) -> String {
+        format!("flag --{}{}", self.prefix, normalize(key))
+    }
+}
+
+/// Normalize a flag or key name to lowercase with `-` separators.
+fn normalize(name: &str) -> String {
+    name.to_ascii_lowercase().replace('_', "-")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ChainedSource, Conf, InMemorySource};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    pub fn get_cli_flags() {
+        let source = CliSource::from_args(
+            "vcfg",
+            args(&[
+                "--verbose",
+                "--vcfg-port=8080",
+                "--other",
+                "value",
+                "--VCFG_Max-Conns",
+                "16",
+                "--vcfg-debug",
+                "--vcfg-name",
+                "xela",
+                "--",
+                "--vcfg-name=ignored",
+            ]),
+        );
+        let conf = Conf::with_source("vcfg", source)
+            .uint("port", None)
+            .uint("max_conns", None)
+            .boolean("debug", Some(false))
+            .string("name", None)
+            .string("other", None);
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(conf.get_uint("max_conns").unwrap(), Some(16));
+        assert_eq!(conf.get_bool("debug").unwrap(), Some(true));
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(conf.get_string("other").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_cli_without_prefix_chained() {
+        let source = ChainedSource::with_sources("vcfg", Vec::new())
+            .push(CliSource::without_prefix(args(&[
+                "-v",
+                "--port",
+                "9090",
+                "--log-level=debug",
+                "--unknown",
+            ])))
+            .push(InMemorySource::new("vcfg").set("VCFG_PORT", "8080"));
+        let conf = Conf::with_source("vcfg", source)
+            .uint("port", None)
+            .string("log_level", None);
+        assert_eq!(conf.get_uint("port").unwrap(), Some(9090));
+        assert_eq!(
+            conf.get_string("log_level").unwrap(),
+            Some("debug".to_string())
+        );
+        assert_eq!(
+            conf.source.sources[0].keys().unwrap(),
+            ["log_level", "port", "unknown"]
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{ConfError, ConfSource, Result};
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// A [`ConfSource`] for resolving values by running commands, such as `pass show` or `op read`.
+/// Each key is mapped to a command with [`CommandSource::map`]; keys without a mapping are
+/// absent. Commands are only run when their key is looked up, and trailing whitespace is trimmed
+/// from their output.
+///
+/// ```
+/// use voidconf::{CommandSource, ConfSource};
+///
+/// let source = CommandSource::new("vcfg").map("db_password", ["pass", "show", "myapp/db"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CommandSource {
+    /// Program and arguments for each key.
+    pub commands: BTreeMap<String, Vec<String>>,
+}
+
+impl CommandSource {
+    /// Map a key to a program and its arguments. The command is run without a shell.
+    pub fn map<I: IntoIterator<Item: Into<String>>>(
+        mut self,
+        key: impl Into<String>,
+        command: I,
+    ) -> Self {
+        self.commands
+            .insert(key.into(), command.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+impl ConfSource for CommandSource {
+    /// Create a new [`CommandSource`] with no commands mapped.
+    fn new(_name: impl Into<String>) -> Self {
+        Self::default()
+    }
+
+    /// Run the command mapped to the key. A command that can't be started or exits
+    /// unsuccessfully is a [`ConfError::CommandFailed`] including its stderr.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let Some(command) = self.commands.get(key) else {
+            return Ok(None);
+        };
+        let failed = |reason: String| ConfError::command_failed(key, command.join(" "), reason);
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| failed("empty command".to_string()))?;
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|e| failed(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(failed(format!("{}: {}", output.status, stderr.trim_end())));
+        }
+        let stdout = String::from_utf8(output.stdout).map_err(|_| ConfError::val_not_utf8(key))?;
+        Ok(Some(stdout.trim_end().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_command_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("ran");
+        let source = CommandSource::new("vcfg")
+            .map("db_password", ["sh", "-c", "printf 'hunter2\\n\\n'"])
+            .map("unused", ["touch", marker.to_str().unwrap()]);
+        let conf = Conf::with_source("vcfg", source)
+            .string("db_password", None)
+            .string("db_user", None)
+            .string("unused", None);
+        assert_eq!(
+            conf.get_string("db_password").unwrap(),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(conf.get_string("db_user").unwrap(), None);
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    pub fn get_command_failed() {
+        let source = CommandSource::new("vcfg")
+            .map("db_password", ["sh", "-c", "echo 'not found' >&2; exit 3"]);
+        assert_eq!(
+            source.get("db_password").unwrap_err(),
+            ConfError::CommandFailed {
+                key: "db_password".to_string(),
+                command: "sh -c echo 'not found' >&2; exit 3".to_string(),
+                reason: "exit status: 3: not found".to_string(),
+            }
+        );
+    }
+}
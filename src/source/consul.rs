@@ -0,0 +1,141 @@
+use crate::{ConfError, ConfSource, Result};
+use base64::Engine;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Default Consul agent address for [`ConsulSource`].
+pub const DEFAULT_CONSUL_ADDR: &str = "http://127.0.0.1:8500";
+
+/// A [`ConfSource`] for resolving values from Consul's KV store. Each lookup fetches
+/// `{addr}/v1/kv/{name}/{key}`; keys that don't exist or have no value resolve to `None`.
+pub struct ConsulSource {
+    /// Name used as the KV path prefix.
+    pub name: String,
+    /// Consul agent address.
+    pub addr: String,
+    /// ACL token sent as `X-Consul-Token`, if any.
+    pub token: Option<String>,
+    agent: ureq::Agent,
+}
+
+/// Entry in Consul's KV response.
+#[derive(Deserialize)]
+struct KvPair {
+    #[serde(rename = "Value")]
+    value: Option<String>,
+}
+
+impl ConsulSource {
+    /// Update the Consul agent address.
+    pub fn with_addr(mut self, addr: impl Into<String>) -> Self {
+        self.addr = addr.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Set an ACL token for requests.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// KV endpoint URL for a key.
+    pub fn url(&self, key: &str) -> String {
+        format!("{}/v1/kv/{}/{}", self.addr, self.name, key)
+    }
+}
+
+impl ConfSource for ConsulSource {
+    /// Create a new [`ConsulSource`] reading `kv/{name}/` from the agent at
+    /// [`DEFAULT_CONSUL_ADDR`].
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            addr: DEFAULT_CONSUL_ADDR.to_string(),
+            token: None,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(10))
+                .build(),
+        }
+    }
+
+    /// Fetch a key and base64-decode its value. A 404 is `None`; any other failure is a
+    /// [`ConfError::RequestFailed`].
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let url = self.url(key);
+        let mut request = self.agent.get(&url);
+        if let Some(token) = &self.token {
+            request = request.set("X-Consul-Token", token);
+        }
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(e @ ureq::Error::Status(status, _)) => {
+                return Err(ConfError::request_failed(url, Some(status), e.to_string()))
+            }
+            Err(e) => return Err(ConfError::request_failed(url, None, e.to_string())),
+        };
+        let status = Some(response.status());
+        let pairs: Vec<KvPair> = response
+            .into_string()
+            .map_err(|e| e.to_string())
+            .and_then(|body| serde_json::from_str(&body).map_err(|e| e.to_string()))
+            .map_err(|reason| ConfError::request_failed(&url, status, reason))?;
+        let Some(value) = pairs.into_iter().next().and_then(|pair| pair.value) else {
+            return Ok(None);
+        };
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .map_err(|e| ConfError::request_failed(&url, status, e.to_string()))?;
+        String::from_utf8(bytes)
+            .map(Some)
+            .map_err(|e| ConfError::request_failed(url, status, e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::source::mock::serve;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_consul_kv() {
+        let (addr, requests) = serve(vec![
+            (200, r#"[{"Key": "vcfg/port", "Value": "ODA4MA=="}]"#),
+            (404, ""),
+        ]);
+        let source = ConsulSource::new("vcfg")
+            .with_addr(addr)
+            .with_token("secret");
+        let conf = Conf::with_source("vcfg", source)
+            .uint("port", None)
+            .string("name", Some("xela"));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        let requests = requests.join().unwrap();
+        assert!(requests[0].starts_with("GET /v1/kv/vcfg/port "));
+        assert!(requests[0]
+            .to_ascii_lowercase()
+            .contains("x-consul-token: secret"));
+        assert!(requests[1].starts_with("GET /v1/kv/vcfg/name "));
+    }
+
+    #[test]
+    pub fn get_consul_failed() {
+        let (addr, _) = serve(vec![(500, "rpc error")]);
+        let source = ConsulSource::new("vcfg").with_addr(&addr);
+        assert_eq!(
+            source.get("port").unwrap_err(),
+            ConfError::RequestFailed {
+                url: format!("{addr}/v1/kv/vcfg/port"),
+                status: Some(500),
+                reason: format!("{addr}/v1/kv/vcfg/port: status code 500"),
+            }
+        );
+        let source = ConsulSource::new("vcfg").with_addr("http://127.0.0.1:1");
+        assert!(matches!(
+            source.get("port").unwrap_err(),
+            ConfError::RequestFailed { status: None, .. }
+        ));
+    }
+}
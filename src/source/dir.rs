@@ -0,0 +1,99 @@
+use super::trim_newline;
+use crate::{ConfError, ConfSource, Result};
+use std::path::PathBuf;
+
+/// A [`ConfSource`] for resolving values from a directory with one file per key, as used for
+/// Docker and Kubernetes mounted secrets. A single trailing newline is trimmed from each value.
+pub struct DirSource {
+    /// Directory containing one file per key.
+    pub dir: PathBuf,
+}
+
+impl DirSource {
+    /// Create a new [`DirSource`] reading from an explicit directory.
+    pub fn from_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl ConfSource for DirSource {
+    /// Create a new [`DirSource`] reading from `/run/secrets/{name}`.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_dir(PathBuf::from("/run/secrets").join(name.into()))
+    }
+
+    /// Read the file named after the key. Missing files are treated as absent values.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let path = self.dir.join(key);
+        match std::fs::read_to_string(&path) {
+            Ok(v) => Ok(Some(trim_newline(v))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ConfError::source_io(
+                path.display().to_string(),
+                e.to_string(),
+            )),
+        }
+    }
+
+    /// List the names of the files in the directory. A missing directory has no keys.
+    fn keys(&self) -> Result<Vec<String>> {
+        let err =
+            |e: std::io::Error| ConfError::source_io(self.dir.display().to_string(), e.to_string());
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(err(e)),
+        };
+        let mut keys = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(err)?;
+            if entry.file_type().map_err(err)?.is_file() {
+                keys.extend(entry.file_name().into_string().ok());
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Name the file for the key.
+    fn describe(&self, key: &str) -> String {
+        format!("file {}", self.dir.join(key).display())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_dir_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("db_password"), "hunter2\n\n").unwrap();
+        std::fs::write(dir.path().join("db_user"), "xela").unwrap();
+        let conf = Conf::with_source("vcfg", DirSource::from_dir(dir.path()))
+            .string("db_password", None)
+            .string("db_user", None)
+            .string("db_host", None);
+        assert_eq!(
+            conf.get_string("db_password").unwrap(),
+            Some("hunter2\n".to_string())
+        );
+        assert_eq!(
+            conf.get_string("db_user").unwrap(),
+            Some("xela".to_string())
+        );
+        assert_eq!(conf.get_string("db_host").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_dir_unreadable() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        let source = DirSource::from_dir(dir.path());
+        assert!(matches!(
+            source.get("nested").unwrap_err(),
+            ConfError::SourceIo { path, .. } if path == dir.path().join("nested").display().to_string()
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{ConfError, ConfSource, FileSource, KeyMapper, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A [`ConfSource`] for resolving values from a `.env` file. Keys are translated the same way
+/// as [`EnvSource`](crate::EnvSource), so `VCFG_NAME=world` satisfies the `name` key.
+///
+/// Supports `#` comments, an optional `export` prefix, and single or double quoted values.
+/// Double quoted values may contain `\n`, `\t`, `\r`, `\"` and `\\` escapes; single quoted
+/// values are taken literally.
+///
+/// The file is never loaded into the process environment. To let process env override `.env`,
+/// as most tools expect, use [`EnvSource::with_dotenv`](crate::EnvSource::with_dotenv), or put an
+/// [`EnvSource`](crate::EnvSource) ahead of it in a [`ChainedSource`](crate::ChainedSource):
+///
+/// ```
+/// use voidconf::{ChainedSource, ConfSource, DotenvSource, EnvSource};
+///
+/// let source = ChainedSource::with_sources("vcfg", Vec::new())
+///     .push(EnvSource::new("vcfg"))
+///     .push(DotenvSource::new("vcfg"));
+/// ```
+pub struct DotenvSource {
+    file: FileSource,
+}
+
+impl DotenvSource {
+    /// Create a new [`DotenvSource`] with the given name as a prefix, reading from an explicit
+    /// path. The file is parsed once, here.
+    pub fn from_path(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            file: FileSource::load(name, path, parse_dotenv),
+        }
+    }
+
+    /// Translate keys to var names with a custom [`KeyMapper`] or closure, see
+    /// [`EnvSource::with_key_mapper`](crate::EnvSource::with_key_mapper).
+    pub fn with_key_mapper(mut self, mapper: impl Into<KeyMapper>) -> Self {
+        self.file = self.file.with_key_mapper(mapper);
+        self
+    }
+
+    /// Path of the `.env` file. A missing file is treated as empty.
+    pub fn path(&self) -> &Path {
+        &self.file.path
+    }
+}
+
+impl ConfSource for DotenvSource {
+    /// Create a new [`DotenvSource`] reading from `.env` in the current directory.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_path(name, ".env")
+    }
+
+    /// Create a new [`DotenvSource`] like [`ConfSource::new`], failing if the file can't be read
+    /// or parsed.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        let source = Self::new(name);
+        source.file.check()?;
+        Ok(source)
+    }
+
+    /// Query the value using the [translated key](crate::EnvSource::env_key) from the parsed file.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        self.file.get(key)
+    }
+
+    /// List the keys of the prefixed vars in the parsed file.
+    fn keys(&self) -> Result<Vec<String>> {
+        self.file.keys()
+    }
+
+    /// Name the [translated key](crate::EnvSource::env_key) and the file.
+    fn describe(&self, key: &str) -> String {
+        self.file.describe(key)
+    }
+
+    /// The [translated key](FileSource::env_key).
+    fn env_var(&self, key: &str) -> Option<String> {
+        self.file.env_var(key)
+    }
+}
+
+#[cfg(feature = "watch")]
+impl crate::WatchableSource for DotenvSource {
+    /// Reload the file when it changes, the same way as [`FileSource`].
+    fn watch(&self, on_change: crate::OnChange) -> Result {
+        self.file.watch(on_change)
+    }
+}
+
+/// Read and parse a `.env` file. A missing file is empty.
+pub(crate) fn load_dotenv(path: &Path) -> Result<BTreeMap<String, String>> {
+    match std::fs::read_to_string(path) {
+        Ok(s) => parse_dotenv(&path.display().to_string(), &s),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(ConfError::file_load_failed(
+            path.display().to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+/// Parse `.env` lines into a map.
+fn parse_dotenv(path: &str, contents: &str) -> Result<BTreeMap<String, String>> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            parse_line(line).map_err(|(column, reason)| {
+                ConfError::file_parse_failed(path, i + 1, column, reason)
+            })
+        })
+        .collect()
+}
+
+/// Parse a single `.env` line. Errors carry a 1-based column and a description.
+fn parse_line(line: &str) -> core::result::Result<(String, String), (usize, String)> {
+    let column = |rest: &str| line.len() - rest.len() + 1;
+    let rest = line.trim_start();
+    let rest = rest.strip_prefix("export ").map_or(rest, str::trim_start);
+    let (key, val) = rest
+        .split_once('=')
+        .ok_or_else(|| (column(rest), "expected KEY=value".to_string()))?;
+    let key = key.trim();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return Err((column(rest), format!("invalid key: {key:?}")));
+    }
+    let val = val.trim_start();
+    let (val, rest) = match val.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let mut unquoted = String::new();
+            let mut chars = val.char_indices().skip(1);
+            let end = loop {
+                match chars.next() {
+                    Some((i, c)) if c == quote => break i + 1,
+                    Some((_, '\\')) if quote == '"' => match chars.next() {
+                        Some((_, 'n')) => unquoted.push('\n'),
+                        Some((_, 't')) => unquoted.push('\t'),
+                        Some((_, 'r')) => unquoted.push('\r'),
+                        Some((_, c @ ('"' | '\\' | '$'))) => unquoted.push(c),
+                        Some((i, c)) => {
+                            return Err((column(&val[i..]), format!("invalid escape: \\{c}")))
+                        }
+                        None => return Err((column(val), "unterminated quote".to_string())),
+                    },
+                    Some((_, c)) => unquoted.push(c),
+                    None => return Err((column(val), "unterminated quote".to_string())),
+                }
+            };
+            (unquoted, &val[end..])
+        }
+        _ => {
+            let end = val.find(" #").unwrap_or(val.len());
+            (val[..end].trim_end().to_string(), &val[end..])
+        }
+    };
+    let trailing = rest.trim_start();
+    if !trailing.is_empty() && !trailing.starts_with('#') {
+        return Err((column(trailing), "unexpected text after value".to_string()));
+    }
+    Ok((key.to_string(), val))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+    use std::io::Write;
+
+    fn dotenv_file(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    pub fn get_dotenv_values() {
+        let file = dotenv_file(
+            "# local settings\nVCFG_NAME=world # inline\nexport VCFG_GREETING=\"Hello,\\tthere\"\nVCFG_RAW='a\\nb'\n",
+        );
+        let conf = Conf::with_source("vcfg", DotenvSource::from_path("vcfg", file.path()))
+            .string("name", None)
+            .string("greeting", None)
+            .string("raw", None);
+        assert_eq!(conf.get_string("name").unwrap(), Some("world".to_string()));
+        assert_eq!(
+            conf.get_string("greeting").unwrap(),
+            Some("Hello,\tthere".to_string())
+        );
+        assert_eq!(conf.get_string("raw").unwrap(), Some("a\\nb".to_string()));
+    }
+
+    #[test]
+    pub fn get_dotenv_malformed() {
+        let file = dotenv_file("VCFG_NAME=world\nVCFG_GREETING=\"Hello\n");
+        let source = DotenvSource::from_path("vcfg", file.path());
+        assert_eq!(
+            source.get("name").unwrap_err(),
+            ConfError::FileParseFailed {
+                path: file.path().display().to_string(),
+                line: 2,
+                column: 15,
+                reason: "unterminated quote".to_string()
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_dotenv_env_precedence() {
+        let file = dotenv_file("VCFG_NAME=\"world\"\nVCFG_DOTENV_PRECEDENCE=dotenv\n");
+        let vars = [("VCFG_DOTENV_PRECEDENCE".to_string(), "env".into())];
+        let source = crate::ChainedSource::with_sources("vcfg", Vec::new())
+            .push(crate::EnvSource::new("vcfg").with_vars(vars.into_iter().collect()))
+            .push(DotenvSource::from_path("vcfg", file.path()));
+        let conf = Conf::with_source("vcfg", source)
+            .string("name", None)
+            .string("dotenv_precedence", None);
+        assert_eq!(conf.get_string("name").unwrap(), Some("world".to_string()));
+        assert_eq!(
+            conf.get_string("dotenv_precedence").unwrap(),
+            Some("env".to_string())
+        );
+    }
+
+    #[test]
+    pub fn get_env_with_dotenv() {
+        let file = dotenv_file("VCFG_NAME=world\nVCFG_PORT=80\nVCFG_LEVEL=info\n");
+        let local = dotenv_file("VCFG_PORT=8080\n");
+        let vars = [("VCFG_LEVEL".to_string(), "debug".into())];
+        let source = crate::EnvSource::new("vcfg")
+            .with_vars(vars.into_iter().collect())
+            .with_dotenv_path(file.path())
+            .unwrap()
+            .with_dotenv_path(local.path())
+            .unwrap()
+            .with_dotenv_path("missing.env")
+            .unwrap();
+        assert_eq!(source.get("name").unwrap(), Some("world".to_string()));
+        assert_eq!(source.get("port").unwrap(), Some("8080".to_string()));
+        assert_eq!(source.get("level").unwrap(), Some("debug".to_string()));
+        assert_eq!(std::env::var("VCFG_NAME").ok(), None);
+
+        let file = dotenv_file("VCFG_NAME=world\nVCFG NAME=world\n");
+        let result = crate::EnvSource::new("vcfg").with_dotenv_path(file.path());
+        assert!(matches!(
+            result,
+            Err(ConfError::FileParseFailed { line: 2, .. })
+        ));
+    }
+}
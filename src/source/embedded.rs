@@ -0,0 +1,116 @@
+use crate::{ConfSource, Result};
+use std::collections::BTreeMap;
+
+/// A [`ConfSource`] for compiled-in defaults, typically the lowest layer under the environment
+/// and config files. Keys are used as-is with no prefix.
+///
+/// ```
+/// use voidconf::{Conf, StaticSource};
+///
+/// // Or `StaticSource::from_toml_str(include_str!("defaults.toml"))?`
+/// let defaults = StaticSource::from_pairs(&[("port", "8080"), ("log.level", "info")]);
+/// let conf = Conf::default()
+///     .add_source(defaults)
+///     .uint("port", None)
+///     .string("log.level", None);
+/// assert_eq!(conf.get_string("log.level").unwrap(), Some("info".to_string()));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StaticSource {
+    /// Map of keys to values.
+    pub vars: BTreeMap<String, String>,
+}
+
+impl StaticSource {
+    /// Create a new [`StaticSource`] from key-value pairs.
+    pub fn from_pairs(pairs: &[(&str, &str)]) -> Self {
+        Self {
+            vars: pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Create a new [`StaticSource`] from a TOML document, usually from `include_str!`. Nested
+    /// tables are flattened to dotted keys, as in [`TomlSource`](crate::TomlSource). Syntax
+    /// errors are a [`ConfError::FileParseFailed`](crate::ConfError::FileParseFailed) with the
+    /// path `<embedded>`.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(s: &str) -> Result<Self> {
+        let table: toml::Table = s.parse().map_err(|e: toml::de::Error| {
+            let (line, column) = super::line_col(s, e.span().map_or(0, |r| r.start));
+            crate::ConfError::file_parse_failed("<embedded>", line, column, e.message())
+        })?;
+        let mut vars = BTreeMap::new();
+        flatten(&mut vars, "", &table);
+        Ok(Self { vars })
+    }
+}
+
+/// Insert every value in `table` into `vars` under its dotted key. Tables are inserted whole
+/// as well as flattened.
+#[cfg(feature = "toml")]
+fn flatten(vars: &mut BTreeMap<String, String>, prefix: &str, table: &toml::Table) {
+    for (key, val) in table {
+        let key = format!("{prefix}{key}");
+        if let toml::Value::Table(t) = val {
+            flatten(vars, &format!("{key}."), t);
+        }
+        vars.insert(key, super::toml::value_string(val));
+    }
+}
+
+impl ConfSource for StaticSource {
+    /// Create a new, empty [`StaticSource`]. The name is unused.
+    fn new(_name: impl Into<String>) -> Self {
+        Self::default()
+    }
+
+    /// Query the value from the map.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.vars.get(key).cloned())
+    }
+
+    /// List the keys in the map, including those of flattened tables.
+    fn keys(&self) -> Result<Vec<String>> {
+        Ok(self.vars.keys().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Conf, InMemorySource};
+
+    #[test]
+    pub fn get_static_layered() {
+        let defaults = StaticSource::from_pairs(&[("name", "world"), ("port", "80")]);
+        let conf = Conf::with_source("vcfg", InMemorySource::new("vcfg").set("VCFG_PORT", "8080"))
+            .add_source(defaults)
+            .string("name", None)
+            .uint("port", None);
+        assert_eq!(conf.get_string("name").unwrap(), Some("world".to_string()));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    pub fn get_static_toml() {
+        let source =
+            StaticSource::from_toml_str("[server]\nport = 8080\nhosts = [\"a\", \"b\"]\n").unwrap();
+        assert_eq!(source.get("server.port").unwrap(), Some("8080".to_string()));
+        assert_eq!(
+            source.get("server.hosts").unwrap(),
+            Some(r#"["a","b"]"#.to_string())
+        );
+        assert_eq!(
+            source.get("server").unwrap(),
+            Some(r#"{"hosts":["a","b"],"port":8080}"#.to_string())
+        );
+        assert!(matches!(
+            StaticSource::from_toml_str("port = 1\nname = ").unwrap_err(),
+            crate::ConfError::FileParseFailed { line: 2, .. }
+        ));
+    }
+}
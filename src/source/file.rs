@@ -0,0 +1,476 @@
+use crate::{ConfError, ConfSink, ConfSource, EnvSource, KeyMapper, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+/// Line parser for a [`FileSource`], which receives the path and file contents.
+type ParseFn = fn(&str, &str) -> Result<BTreeMap<String, String>>;
+
+/// A [`ConfSource`] for resolving values from a file of `KEY=value` lines. Keys are translated
+/// the same way as [`EnvSource`], so the two are interchangeable. Blank lines and lines starting
+/// with `#` are ignored.
+pub struct FileSource {
+    /// Path of the config file. A missing file is treated as empty.
+    pub path: PathBuf,
+    keys: EnvSource,
+    /// Line parser, kept to reload the file.
+    #[cfg(feature = "watch")]
+    parse: ParseFn,
+    /// Parsed vars, shared with any watcher so it can reload them.
+    vars: Arc<RwLock<Result<BTreeMap<String, String>>>>,
+    #[cfg(feature = "watch")]
+    watchers: std::sync::Mutex<Vec<notify::RecommendedWatcher>>,
+}
+
+impl FileSource {
+    /// Create a new [`FileSource`] with the given name as a prefix, reading from an explicit path.
+    /// The file is parsed once, here.
+    pub fn from_path(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self::load(name, path, parse_lines)
+    }
+
+    /// Load a file with the given line parser, which receives the path and file contents.
+    pub(crate) fn load(name: impl Into<String>, path: impl Into<PathBuf>, parse: ParseFn) -> Self {
+        let path = path.into();
+        let vars = read_vars(&path, parse);
+        Self {
+            path,
+            keys: EnvSource::new(name),
+            #[cfg(feature = "watch")]
+            parse,
+            vars: Arc::new(RwLock::new(vars)),
+            #[cfg(feature = "watch")]
+            watchers: Default::default(),
+        }
+    }
+
+    /// Fail with the load error, if the file couldn't be read or parsed.
+    pub(crate) fn check(&self) -> Result {
+        self.vars().as_ref().map(|_| ()).map_err(Clone::clone)
+    }
+
+    /// A source sharing this one's path and key translation, reading the given vars.
+    #[cfg(feature = "watch")]
+    fn view(&self, vars: Arc<RwLock<Result<BTreeMap<String, String>>>>) -> Self {
+        Self {
+            path: self.path.clone(),
+            keys: self.keys.clone(),
+            parse: self.parse,
+            vars,
+            watchers: Default::default(),
+        }
+    }
+
+    /// Lock the parsed vars for reading.
+    fn vars(&self) -> RwLockReadGuard<'_, Result<BTreeMap<String, String>>> {
+        self.vars.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replace or remove the line for a var, keeping other lines and comments, then rewrite the
+    /// file atomically by writing a temp file next to it and renaming it into place.
+    fn write(&mut self, var: &str, val: Option<&str>) -> Result {
+        let mut vars = self.vars.write().unwrap_or_else(|e| e.into_inner());
+        let vars = vars.as_mut().map_err(|e| e.clone())?;
+        let io_err = |e: std::io::Error| {
+            ConfError::source_io(self.path.display().to_string(), e.to_string())
+        };
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(io_err(e)),
+        };
+        let line = val.map(|val| format!("{var}={val}"));
+        let mut out = String::new();
+        let mut found = false;
+        for existing in contents.lines() {
+            let trimmed = existing.trim();
+            let is_var = !trimmed.starts_with('#')
+                && trimmed
+                    .split_once('=')
+                    .is_some_and(|(k, _)| k.trim() == var);
+            let kept = match is_var {
+                false => Some(existing),
+                true if !found => {
+                    found = true;
+                    line.as_deref()
+                }
+                true => None,
+            };
+            if let Some(kept) = kept {
+                out.push_str(kept);
+                out.push('\n');
+            }
+        }
+        if let (false, Some(line)) = (found, &line) {
+            out.push_str(line);
+            out.push('\n');
+        }
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, out)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .map_err(io_err)?;
+        match val {
+            Some(val) => vars.insert(var.to_string(), val.to_string()),
+            None => vars.remove(var),
+        };
+        Ok(())
+    }
+
+    /// Translate a key name into its corresponding file key. See [`EnvSource::env_key`].
+    pub fn env_key(&self, key: impl Into<String>) -> String {
+        self.keys.env_key(key)
+    }
+
+    /// Translate keys to file keys with a custom [`KeyMapper`] or closure, see
+    /// [`EnvSource::with_key_mapper`].
+    pub fn with_key_mapper(mut self, mapper: impl Into<KeyMapper>) -> Self {
+        self.keys = self.keys.with_key_mapper(mapper);
+        self
+    }
+}
+
+impl ConfSource for FileSource {
+    /// Create a new [`FileSource`] reading from `{name}.conf` in the current directory.
+    fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let path = format!("{name}.conf");
+        Self::from_path(name, path)
+    }
+
+    /// Create a new [`FileSource`] like [`ConfSource::new`], failing if the file can't be read or
+    /// parsed.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        let source = Self::new(name);
+        source.check()?;
+        Ok(source)
+    }
+
+    /// Query the value using the [translated key](FileSource::env_key) from the parsed file.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let vars = self.vars();
+        let vars = vars.as_ref().map_err(Clone::clone)?;
+        Ok(vars.get(&self.env_key(key)).cloned())
+    }
+
+    /// List the keys of the prefixed lines, see [`EnvSource::key_from_env_key`]. Fails with
+    /// [`ConfError::Unsupported`] if a [key mapper](FileSource::with_key_mapper) is set.
+    fn keys(&self) -> Result<Vec<String>> {
+        if self.keys.key_mapper.is_some() {
+            return Err(ConfError::unsupported("keys"));
+        }
+        let vars = self.vars();
+        let vars = vars.as_ref().map_err(Clone::clone)?;
+        Ok(vars
+            .keys()
+            .filter_map(|var| self.keys.key_from_env_key(var))
+            .collect())
+    }
+
+    /// Name the [translated key](FileSource::env_key) and the file.
+    fn describe(&self, key: &str) -> String {
+        format!("{} in file {}", self.env_key(key), self.path.display())
+    }
+
+    /// The [translated key](FileSource::env_key).
+    fn env_var(&self, key: &str) -> Option<String> {
+        Some(self.env_key(key))
+    }
+}
+
+impl ConfSink for FileSource {
+    /// Set the line for the [translated key](FileSource::env_key), replacing an existing line or
+    /// appending one, and rewrite the file. Values are trimmed when read back, and can't contain
+    /// newlines.
+    fn set(&mut self, key: &str, val: &str) -> Result {
+        if val.contains(['\n', '\r']) {
+            return Err(ConfError::val_invalid(
+                key,
+                "values in a KEY=value file can't contain newlines",
+            ));
+        }
+        let var = self.env_key(key);
+        self.write(&var, Some(val))
+    }
+
+    /// Remove the line for the [translated key](FileSource::env_key) and rewrite the file.
+    fn remove(&mut self, key: &str) -> Result {
+        let var = self.env_key(key);
+        self.write(&var, None)
+    }
+}
+
+/// How long a watched [`FileSource`] waits for writes to its file to settle before reloading it.
+#[cfg(feature = "watch")]
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(100);
+
+#[cfg(feature = "watch")]
+impl crate::WatchableSource for FileSource {
+    /// Watch the file's directory, and reload the file when it's created, changed, renamed or
+    /// removed. `on_change` is only called if the parsed vars differ, so changes made through
+    /// [`ConfSink`] aren't reported.
+    ///
+    /// Events are debounced by 100ms, so a burst of writes is reloaded once. If the file can't be
+    /// read, e.g. while it's being replaced, the previous vars are kept until the next event. If
+    /// it can't be parsed, the previous vars are kept too, and `on_change` is called with a view
+    /// whose lookups fail with the parse error. Replace the file atomically, as [`ConfSink`] and
+    /// most editors do, so it isn't read half-written.
+    fn watch(&self, on_change: crate::OnChange) -> Result {
+        use notify::Watcher as _;
+        use std::sync::mpsc::{channel, RecvTimeoutError};
+        let err =
+            |e: notify::Error| ConfError::source_io(self.path.display().to_string(), e.to_string());
+        let view = self.view(self.vars.clone());
+        let file_name = self.path.file_name().map(|name| name.to_os_string());
+        let (tx, rx) = channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                if !event.kind.is_access()
+                    && event
+                        .paths
+                        .iter()
+                        .any(|path| path.file_name() == file_name.as_deref())
+                {
+                    let _ = tx.send(());
+                }
+            })
+            .map_err(err)?;
+        // Reload on a separate thread, which stops once the watcher and its sender are dropped.
+        std::thread::spawn(move || {
+            while rx.recv().is_ok() {
+                loop {
+                    match rx.recv_timeout(WATCH_DEBOUNCE) {
+                        Ok(()) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                let vars = match read_vars(&view.path, view.parse) {
+                    Ok(vars) => vars,
+                    Err(ConfError::FileLoadFailed { .. }) => continue,
+                    Err(e) => {
+                        on_change(&view.view(Arc::new(RwLock::new(Err(e)))));
+                        continue;
+                    }
+                };
+                {
+                    let mut current = view.vars.write().unwrap_or_else(|e| e.into_inner());
+                    if current.as_ref() == Ok(&vars) {
+                        continue;
+                    }
+                    *current = Ok(vars);
+                }
+                on_change(&view);
+            }
+        });
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        watcher
+            .watch(dir, notify::RecursiveMode::NonRecursive)
+            .map_err(err)?;
+        self.watchers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(watcher);
+        Ok(())
+    }
+}
+
+/// Read and parse a file. A missing file is empty.
+fn read_vars(path: &Path, parse: ParseFn) -> Result<BTreeMap<String, String>> {
+    match std::fs::read_to_string(path) {
+        Ok(s) => parse(&path.display().to_string(), &s),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(ConfError::file_load_failed(
+            path.display().to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+/// Parse `KEY=value` lines, trimming whitespace around keys and values.
+fn parse_lines(path: &str, contents: &str) -> Result<BTreeMap<String, String>> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| match line.split_once('=') {
+            Some((k, v)) => Ok((k.trim().to_string(), v.trim().to_string())),
+            None => Err(ConfError::file_parse_failed(
+                path,
+                i + 1,
+                1,
+                "expected KEY=value",
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+    use std::io::Write;
+
+    fn conf_file(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    pub fn get_file_lines() {
+        let file = conf_file("# greeting config\n\nVCFG_NAME=world\n  VCFG_GREETING = Hello  \n");
+        let conf = Conf::with_source("vcfg", FileSource::from_path("vcfg", file.path()))
+            .string("name", None)
+            .string("greeting", None)
+            .string("missing", None);
+        assert_eq!(conf.get_string("name").unwrap(), Some("world".to_string()));
+        assert_eq!(
+            conf.get_string("greeting").unwrap(),
+            Some("Hello".to_string())
+        );
+        assert_eq!(conf.get_string("missing").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_file_key_mapper() {
+        let file = conf_file(
+            "DB__HOST=localhost
+VCFG_DB__HOST=ignored
+",
+        );
+        let source =
+            FileSource::from_path("vcfg", file.path()).with_key_mapper(KeyMapper::unprefixed());
+        let conf = Conf::with_source("vcfg", source).string("db.host", None);
+        assert_eq!(
+            conf.get_string("db.host").unwrap(),
+            Some("localhost".to_string())
+        );
+        assert_eq!(
+            conf.source_keys().unwrap_err(),
+            ConfError::unsupported("keys")
+        );
+    }
+
+    #[test]
+    pub fn set_file_rewrites() {
+        let file = conf_file("# prefs\nVCFG_THEME=light\nVCFG_FONT_SIZE = 12\n");
+        let mut conf = Conf::with_source("vcfg", FileSource::from_path("vcfg", file.path()))
+            .string("theme", None)
+            .uint("font_size", None)
+            .boolean("autosave", None);
+        conf.set("theme", "dark".to_string()).unwrap();
+        conf.set("autosave", true).unwrap();
+        conf.source.remove("font_size").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(file.path()).unwrap(),
+            "# prefs\nVCFG_THEME=dark\nVCFG_AUTOSAVE=true\n"
+        );
+        assert_eq!(conf.get_string("theme").unwrap(), Some("dark".to_string()));
+        assert_eq!(conf.get_uint("font_size").unwrap(), None);
+        let reloaded = FileSource::from_path("vcfg", file.path());
+        assert_eq!(reloaded.get("autosave").unwrap(), Some("true".to_string()));
+        assert!(matches!(
+            conf.source.set("theme", "a\nb").unwrap_err(),
+            ConfError::ValInvalid { .. }
+        ));
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    pub fn watch_file_rewritten() {
+        use crate::ConfChange;
+        use std::time::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vcfg.conf");
+        let rewrite = |contents: &str| {
+            let tmp = dir.path().join("vcfg.conf.tmp");
+            std::fs::write(&tmp, contents).unwrap();
+            std::fs::rename(&tmp, &path).unwrap();
+        };
+        rewrite("VCFG_PORT=80\nVCFG_NAME=xela\n");
+        let conf = Conf::with_source("vcfg", FileSource::from_path("vcfg", &path))
+            .uint("port", None)
+            .string("name", None);
+        let changes = conf.watch().unwrap();
+
+        rewrite("VCFG_PORT=8080\nVCFG_NAME=xela\n");
+        let timeout = Duration::from_secs(10);
+        assert_eq!(
+            changes.recv_timeout(timeout).unwrap().unwrap(),
+            ConfChange {
+                key: "port".to_string(),
+                old: Some("80".to_string()),
+                new: Some("8080".to_string())
+            }
+        );
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+
+        rewrite("VCFG_PORT=8081\nVCFG_NAME\n");
+        assert!(matches!(
+            changes.recv_timeout(timeout).unwrap().unwrap_err(),
+            ConfError::FileParseFailed { line: 2, .. }
+        ));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+
+        rewrite("VCFG_PORT=eighty\nVCFG_NAME=xela\n");
+        assert!(matches!(
+            changes.recv_timeout(timeout).unwrap().unwrap_err(),
+            ConfError::ValParseFailed { key, .. } if key == "port"
+        ));
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    pub fn watch_file_debounced() {
+        use crate::ConfChange;
+        use std::time::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vcfg.conf");
+        std::fs::write(&path, "VCFG_PORT=80\n").unwrap();
+        let conf =
+            Conf::with_source("vcfg", FileSource::from_path("vcfg", &path)).uint("port", None);
+        let changes = conf.watch().unwrap();
+
+        for port in 81..=83 {
+            std::fs::write(&path, format!("VCFG_PORT={port}\n")).unwrap();
+        }
+        assert_eq!(
+            changes
+                .recv_timeout(Duration::from_secs(10))
+                .unwrap()
+                .unwrap(),
+            ConfChange {
+                key: "port".to_string(),
+                old: Some("80".to_string()),
+                new: Some("83".to_string())
+            }
+        );
+        assert!(changes.recv_timeout(Duration::from_millis(500)).is_err());
+    }
+
+    #[test]
+    pub fn get_file_malformed() {
+        let file = conf_file("VCFG_NAME=world\nVCFG_GREETING\n");
+        let source = FileSource::from_path("vcfg", file.path());
+        assert_eq!(
+            source.get("name").unwrap_err(),
+            ConfError::FileParseFailed {
+                path: file.path().display().to_string(),
+                line: 2,
+                column: 1,
+                reason: "expected KEY=value".to_string(),
+            }
+        );
+    }
+}
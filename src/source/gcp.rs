@@ -0,0 +1,209 @@
+use crate::{ConfError, ConfSource, Result};
+use base64::Engine;
+use google_cloud_auth::project::Config;
+use google_cloud_auth::token::DefaultTokenSourceProvider;
+use google_cloud_token::TokenSourceProvider;
+use serde::Deserialize;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Default Secret Manager API endpoint for [`GcpSecretSource`].
+pub const DEFAULT_SECRET_MANAGER_ENDPOINT: &str = "https://secretmanager.googleapis.com";
+
+/// OAuth scope for Secret Manager access.
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+/// A [`ConfSource`] for resolving values from Google Secret Manager. Each lookup accesses the
+/// latest version of the secret `{name}-{key}`; a missing secret resolves to `None`.
+///
+/// Credentials come from application default credentials: `GOOGLE_APPLICATION_CREDENTIALS`,
+/// the gcloud user credentials, or the metadata server on GCE and GKE workload identity. The
+/// project is taken from `GOOGLE_CLOUD_PROJECT`, [`GcpSecretSource::with_project`], or the
+/// credentials, in that order. Lookups block on a private runtime, so they must not be made
+/// from within an async runtime.
+pub struct GcpSecretSource {
+    /// Prefix of the secret names.
+    pub name: String,
+    /// Project containing the secrets.
+    pub project: Option<String>,
+    /// Secret Manager API endpoint.
+    pub endpoint: String,
+    token: Option<String>,
+    auth: OnceLock<Auth>,
+    agent: ureq::Agent,
+}
+
+/// Application default credentials, with the runtime used to drive them.
+struct Auth {
+    runtime: tokio::runtime::Runtime,
+    provider: DefaultTokenSourceProvider,
+}
+
+#[derive(Deserialize)]
+struct AccessResponse {
+    payload: Payload,
+}
+
+#[derive(Deserialize)]
+struct Payload {
+    data: String,
+}
+
+impl GcpSecretSource {
+    /// Update the project containing the secrets.
+    pub fn with_project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    /// Override the Secret Manager API endpoint.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Use a fixed OAuth access token instead of application default credentials.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Load application default credentials. A failure isn't cached, so the next lookup tries
+    /// again.
+    fn init_auth(&self) -> Result<Auth> {
+        let failed = |reason: String| ConfError::auth_failed(&self.endpoint, reason);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| failed(e.to_string()))?;
+        let config = Config::default().with_scopes(&[CLOUD_PLATFORM_SCOPE]);
+        let provider = runtime
+            .block_on(DefaultTokenSourceProvider::new(config))
+            .map_err(|e| failed(e.to_string()))?;
+        Ok(Auth { runtime, provider })
+    }
+
+    /// Project and `Authorization` header value for a request.
+    fn credentials(&self) -> Result<(String, String)> {
+        if let Some(token) = &self.token {
+            let project = self.project.clone().ok_or_else(|| {
+                ConfError::auth_failed(&self.endpoint, "no Google Cloud project configured")
+            })?;
+            return Ok((project, format!("Bearer {token}")));
+        }
+        let auth = match self.auth.get() {
+            Some(auth) => auth,
+            None => {
+                let auth = self.init_auth()?;
+                self.auth.get_or_init(|| auth)
+            }
+        };
+        let project = self
+            .project
+            .clone()
+            .or_else(|| auth.provider.project_id.clone())
+            .ok_or_else(|| {
+                ConfError::auth_failed(&self.endpoint, "no Google Cloud project configured")
+            })?;
+        let header = auth
+            .runtime
+            .block_on(auth.provider.token_source().token())
+            .map_err(|e| ConfError::auth_failed(&self.endpoint, e.to_string()))?;
+        Ok((project, header))
+    }
+}
+
+impl ConfSource for GcpSecretSource {
+    /// Create a new [`GcpSecretSource`] reading secrets prefixed `{name}-`.
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            project: std::env::var("GOOGLE_CLOUD_PROJECT").ok(),
+            endpoint: DEFAULT_SECRET_MANAGER_ENDPOINT.to_string(),
+            token: None,
+            auth: OnceLock::new(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(10))
+                .build(),
+        }
+    }
+
+    /// Access the latest version of `{name}-{key}`. A 404 is `None`, a 401 or 403 is a
+    /// [`ConfError::AuthFailed`], and a payload that isn't UTF-8 is a [`ConfError::ValNotUtf8`].
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let (project, header) = self.credentials()?;
+        let url = format!(
+            "{}/v1/projects/{project}/secrets/{}-{key}/versions/latest:access",
+            self.endpoint, self.name
+        );
+        let response = match self.agent.get(&url).set("Authorization", &header).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(e @ ureq::Error::Status(401 | 403, _)) => {
+                return Err(ConfError::auth_failed(url, e.to_string()))
+            }
+            Err(e @ ureq::Error::Status(status, _)) => {
+                return Err(ConfError::request_failed(url, Some(status), e.to_string()))
+            }
+            Err(e) => return Err(ConfError::request_failed(url, None, e.to_string())),
+        };
+        let status = Some(response.status());
+        let data = response
+            .into_string()
+            .map_err(|e| e.to_string())
+            .and_then(|body| {
+                serde_json::from_str::<AccessResponse>(&body).map_err(|e| e.to_string())
+            })
+            .and_then(|access| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(access.payload.data)
+                    .map_err(|e| e.to_string())
+            })
+            .map_err(|reason| ConfError::request_failed(&url, status, reason))?;
+        String::from_utf8(data)
+            .map(Some)
+            .map_err(|_| ConfError::val_not_utf8(key))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::source::mock::serve;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_gcp_secret() {
+        let (endpoint, requests) = serve(vec![
+            (200, r#"{"name": "x", "payload": {"data": "aHVudGVyMg=="}}"#),
+            (404, r#"{"error": {"code": 404, "status": "NOT_FOUND"}}"#),
+            (200, r#"{"name": "x", "payload": {"data": "//4="}}"#),
+        ]);
+        let source = GcpSecretSource::new("vcfg")
+            .with_project("my-project")
+            .with_endpoint(endpoint)
+            .with_token("ya29.token");
+        let conf = Conf::with_source("vcfg", source)
+            .string("password", None)
+            .string("user", None)
+            .string("key", None);
+        assert_eq!(
+            conf.get_string("password").unwrap(),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(conf.get_string("user").unwrap(), None);
+        assert_eq!(
+            conf.get_string("key").unwrap_err(),
+            ConfError::ValNotUtf8 {
+                key: "key".to_string()
+            }
+        );
+        let requests = requests.join().unwrap();
+        assert!(requests[0].starts_with(
+            "GET /v1/projects/my-project/secrets/vcfg-password/versions/latest:access "
+        ));
+        assert!(requests[0]
+            .to_ascii_lowercase()
+            .contains("authorization: bearer ya29.token"));
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{ConfError, ConfSource, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// A [`ConfSource`] for resolving values the way `git config` does: from INI-style files with
+/// `[section]` and `[section "subsection"]` headers, where a repo-local file overrides a
+/// user-global one. `get("user.name")` reads `name` under `[user]`, and
+/// `get("remote.origin.url")` reads `url` under `[remote "origin"]`.
+///
+/// Section and key names are case-insensitive; subsection names are not. A key with no `=` is
+/// `true`, values may be double quoted, and `#` or `;` starts a comment outside quotes. Use
+/// [`GitStyleSource::origin`] to find which file a value came from.
+pub struct GitStyleSource {
+    files: Vec<(PathBuf, Result<BTreeMap<String, String>>)>,
+}
+
+impl GitStyleSource {
+    /// Create a new [`GitStyleSource`] from the given paths, highest precedence first. Missing
+    /// files are treated as empty. The files are parsed once, here.
+    pub fn from_paths(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let vars = match std::fs::read_to_string(&path) {
+                    Ok(s) => parse_git_config(&path.display().to_string(), &s),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+                    Err(e) => Err(ConfError::file_load_failed(
+                        path.display().to_string(),
+                        e.to_string(),
+                    )),
+                };
+                (path, vars)
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Paths read for the config named `name`, highest precedence first:
+    ///
+    /// 1. `.{name}/config` in the current directory
+    /// 2. `~/.{name}config`
+    pub fn candidates(name: &str) -> Vec<PathBuf> {
+        let mut paths = vec![Path::new(&format!(".{name}")).join("config")];
+        paths.extend(
+            crate::value::home_dir()
+                .ok()
+                .map(|home| home.join(format!(".{name}config"))),
+        );
+        paths
+    }
+
+    /// Paths of the files read, highest precedence first.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Find the value for a key along with the path of the file it came from.
+    pub fn lookup(&self, key: &str) -> Result<Option<(&Path, String)>> {
+        let key = normalize_key(key);
+        for (path, vars) in &self.files {
+            if let Some(v) = vars.as_ref().map_err(Clone::clone)?.get(&key) {
+                return Ok(Some((path, v.clone())));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Path of the file a key's value came from, or `None` if no file sets it.
+    pub fn origin(&self, key: &str) -> Result<Option<&Path>> {
+        Ok(self.lookup(key)?.map(|(path, _)| path))
+    }
+}
+
+impl ConfSource for GitStyleSource {
+    /// Create a new [`GitStyleSource`] reading the [candidates](GitStyleSource::candidates) for
+    /// `name`.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_paths(Self::candidates(&name.into()))
+    }
+
+    /// Create a new [`GitStyleSource`] like [`ConfSource::new`], failing if any file can't be
+    /// read or parsed.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        let source = Self::new(name);
+        for (_, vars) in &source.files {
+            vars.as_ref().map_err(Clone::clone)?;
+        }
+        Ok(source)
+    }
+
+    /// Look up a `section.key` or `section.subsection.key`, local files first.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.lookup(key)?.map(|(_, v)| v))
+    }
+
+    /// List the normalized keys set in any file.
+    fn keys(&self) -> Result<Vec<String>> {
+        let mut keys = BTreeSet::new();
+        for (_, vars) in &self.files {
+            keys.extend(vars.as_ref().map_err(Clone::clone)?.keys().cloned());
+        }
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Name the key and the file its value came from, see [`GitStyleSource::origin`].
+    fn describe(&self, key: &str) -> String {
+        match self.origin(key) {
+            Ok(Some(path)) => format!("{key} in file {}", path.display()),
+            _ => format!("key {key}"),
+        }
+    }
+}
+
+/// Lowercase the section and key name of a dotted key, keeping any subsection as-is.
+fn normalize_key(key: &str) -> String {
+    match (key.split_once('.'), key.rsplit_once('.')) {
+        (Some((section, _)), Some((rest, name))) if section.len() < rest.len() => format!(
+            "{}{}.{}",
+            section.to_ascii_lowercase(),
+            &rest[section.len()..],
+            name.to_ascii_lowercase()
+        ),
+        _ => key.to_ascii_lowercase(),
+    }
+}
+
+/// Parse git-config lines into a map of normalized dotted keys to values. Later values win.
+fn parse_git_config(path: &str, contents: &str) -> Result<BTreeMap<String, String>> {
+    let mut section: Option<String> = None;
+    let mut vars = BTreeMap::new();
+    for (i, line) in contents.lines().enumerate() {
+        let err =
+            |column: usize, reason: &str| ConfError::file_parse_failed(path, i + 1, column, reason);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let (header, _) = header
+                .split_once(']')
+                .ok_or_else(|| err(line.len(), "expected `]`"))?;
+            section = Some(match header.split_once(char::is_whitespace) {
+                Some((name, sub)) => {
+                    let sub = sub.trim();
+                    let sub = sub
+                        .strip_prefix('"')
+                        .and_then(|s| s.strip_suffix('"'))
+                        .ok_or_else(|| err(2 + name.len(), "expected quoted subsection"))?;
+                    format!("{}.{sub}", name.to_ascii_lowercase())
+                }
+                None => header.trim().to_ascii_lowercase(),
+            });
+            continue;
+        }
+        let section = section
+            .as_deref()
+            .ok_or_else(|| err(1, "expected [section] before keys"))?;
+        let (name, val) = match line.split_once('=') {
+            Some((name, val)) => (name.trim(), parse_value(val).map_err(|r| err(1, r))?),
+            None => (line, "true".to_string()),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(err(1, "invalid key name"));
+        }
+        vars.insert(format!("{section}.{}", name.to_ascii_lowercase()), val);
+    }
+    Ok(vars)
+}
+
+/// Parse a value, joining quoted and unquoted parts and stopping at a comment.
+fn parse_value(val: &str) -> core::result::Result<String, &'static str> {
+    let mut out = String::new();
+    let mut quoted = false;
+    let mut chars = val.trim().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' | ';' if !quoted => break,
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(c @ ('"' | '\\')) => out.push(c),
+                _ => return Err("invalid escape"),
+            },
+            c => out.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quote");
+    }
+    Ok(out.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_git_style_local_overrides_global() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("local");
+        let global = dir.path().join("global");
+        std::fs::write(
+            &local,
+            "[user]\n\temail = \"xela@work.example\" # work\n[remote \"Origin\"]\n\turl = git@example.com:vcfg\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &global,
+            "; global\n[User]\n\tName = xela\n\temail = xela@home.example\n[core]\n\tautocrlf\n",
+        )
+        .unwrap();
+        let conf = Conf::with_source(
+            "vcfg",
+            GitStyleSource::from_paths([local.clone(), global.clone()]),
+        )
+        .string("user.name", None)
+        .string("user.email", None)
+        .string("remote.Origin.url", None)
+        .boolean("core.autocrlf", None);
+        assert_eq!(
+            conf.get_string("user.name").unwrap(),
+            Some("xela".to_string())
+        );
+        assert_eq!(
+            conf.get_string("user.email").unwrap(),
+            Some("xela@work.example".to_string())
+        );
+        assert_eq!(
+            conf.get_string("remote.Origin.url").unwrap(),
+            Some("git@example.com:vcfg".to_string())
+        );
+        assert_eq!(conf.get_bool("core.autocrlf").unwrap(), Some(true));
+        assert_eq!(
+            conf.source.origin("USER.EMAIL").unwrap(),
+            Some(local.as_path())
+        );
+        assert_eq!(
+            conf.source.origin("user.name").unwrap(),
+            Some(global.as_path())
+        );
+        assert_eq!(conf.source.origin("remote.origin.url").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_git_style_malformed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config");
+        std::fs::write(&path, "[user]\n\tname = \"xela\n").unwrap();
+        let source = GitStyleSource::from_paths([path]);
+        assert!(matches!(
+            source.get("user.name").unwrap_err(),
+            ConfError::FileParseFailed { line: 2, .. }
+        ));
+    }
+}
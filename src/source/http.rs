@@ -0,0 +1,139 @@
+use super::json::json_lookup;
+use crate::{ConfError, ConfSource, Result};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Default timeout for [`HttpSource`] requests.
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A [`ConfSource`] for resolving values from a JSON object fetched over HTTP. The document is
+/// fetched on the first lookup and cached until [`HttpSource::refresh`]; nested values are looked
+/// up with dotted keys like [`JsonSource`](crate::JsonSource).
+///
+/// Since [`ConfSource::new`] can't fail, a failed fetch is returned from the first lookup as a
+/// [`ConfError::RequestFailed`], and from every lookup after it until a refresh succeeds.
+pub struct HttpSource {
+    /// URL of the JSON config document.
+    pub url: String,
+    /// Timeout for the whole request.
+    pub timeout: Duration,
+    doc: RwLock<Option<Result<serde_json::Value>>>,
+}
+
+impl HttpSource {
+    /// Create a new [`HttpSource`] fetching from an explicit URL.
+    pub fn from_url(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            timeout: DEFAULT_HTTP_TIMEOUT,
+            doc: RwLock::new(None),
+        }
+    }
+
+    /// Update the request timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Fetch the document again, replacing the cached one. The old document is kept if the
+    /// fetch fails.
+    pub fn refresh(&self) -> Result {
+        let doc = self.fetch()?;
+        *self.doc.write().unwrap_or_else(|e| e.into_inner()) = Some(Ok(doc));
+        Ok(())
+    }
+
+    /// Fetch and parse the config document.
+    fn fetch(&self) -> Result<serde_json::Value> {
+        let agent = ureq::AgentBuilder::new().timeout(self.timeout).build();
+        let response = agent.get(&self.url).call().map_err(|e| match e {
+            ureq::Error::Status(status, _) => {
+                ConfError::request_failed(&self.url, Some(status), e.to_string())
+            }
+            ureq::Error::Transport(_) => ConfError::request_failed(&self.url, None, e.to_string()),
+        })?;
+        let status = response.status();
+        let body = response
+            .into_string()
+            .map_err(|e| ConfError::request_failed(&self.url, Some(status), e.to_string()))?;
+        serde_json::from_str(&body)
+            .map_err(|e| ConfError::request_failed(&self.url, Some(status), e.to_string()))
+    }
+}
+
+impl ConfSource for HttpSource {
+    /// Create a new [`HttpSource`] fetching from the URL in the `{NAME}_CONFIG_URL` env var, or
+    /// `http://localhost/{name}` if unset.
+    fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let var = format!("{}_CONFIG_URL", name.to_ascii_uppercase());
+        Self::from_url(std::env::var(var).unwrap_or_else(|_| format!("http://localhost/{name}")))
+    }
+
+    /// Look up a dotted key in the fetched document.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        if let Some(doc) = &*self.doc.read().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(json_lookup(doc.as_ref().map_err(Clone::clone)?, key));
+        }
+        let mut doc = self.doc.write().unwrap_or_else(|e| e.into_inner());
+        let doc = doc.get_or_insert_with(|| self.fetch());
+        Ok(json_lookup(doc.as_ref().map_err(Clone::clone)?, key))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::source::mock::serve;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_http_cached() {
+        let (url, requests) = serve(vec![(200, r#"{"name": "xela", "port": 8080}"#)]);
+        let conf = Conf::with_source("vcfg", HttpSource::from_url(format!("{url}/vcfg")))
+            .string("name", None)
+            .uint("port", None)
+            .string("missing", None);
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(conf.get_string("missing").unwrap(), None);
+        assert_eq!(requests.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    pub fn get_http_refresh() {
+        let (url, requests) = serve(vec![
+            (500, "starting"),
+            (200, r#"{"name": "xela"}"#),
+            (500, "restarting"),
+        ]);
+        let source = HttpSource::from_url(format!("{url}/vcfg"));
+        source.get("name").unwrap_err();
+        source.get("name").unwrap_err();
+        source.refresh().unwrap();
+        assert_eq!(source.get("name").unwrap(), Some("xela".to_string()));
+        source.refresh().unwrap_err();
+        assert_eq!(source.get("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(requests.join().unwrap().len(), 3);
+    }
+
+    #[test]
+    pub fn get_http_failed() {
+        let (url, _) = serve(vec![(503, "unavailable")]);
+        let source = HttpSource::from_url(format!("{url}/vcfg"));
+        assert!(matches!(
+            source.get("name").unwrap_err(),
+            ConfError::RequestFailed {
+                status: Some(503),
+                ..
+            }
+        ));
+        let source =
+            HttpSource::from_url("http://127.0.0.1:1/vcfg").with_timeout(Duration::from_secs(1));
+        assert!(matches!(
+            source.get("name").unwrap_err(),
+            ConfError::RequestFailed { status: None, .. }
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{ConfError, ConfSource, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// A [`ConfSource`] for resolving values from an INI file. Keys under a `[section]` header are
+/// looked up as `section.key`, while keys before the first header are looked up bare.
+///
+/// Values are trimmed of surrounding whitespace and optional matching quotes. Lines starting
+/// with `;` or `#` are comments, and when a key is repeated the last value wins.
+pub struct IniSource {
+    /// Path of the INI file. A missing file is treated as empty.
+    pub path: PathBuf,
+    vars: Result<BTreeMap<String, String>>,
+}
+
+impl IniSource {
+    /// Create a new [`IniSource`] reading from an explicit path. The file is parsed once, here.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let vars = match std::fs::read_to_string(&path) {
+            Ok(s) => parse_ini(&path.display().to_string(), &s),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(ConfError::file_load_failed(
+                path.display().to_string(),
+                e.to_string(),
+            )),
+        };
+        Self { path, vars }
+    }
+}
+
+impl ConfSource for IniSource {
+    /// Create a new [`IniSource`] reading from `{name}.ini` in the current directory.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_path(format!("{}.ini", name.into()))
+    }
+
+    /// Create a new [`IniSource`] like [`ConfSource::new`], failing if the file can't be read or
+    /// parsed.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        let source = Self::new(name);
+        source.vars.as_ref().map_err(Clone::clone)?;
+        Ok(source)
+    }
+
+    /// Look up a `section.key` or bare global key.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let vars = self.vars.as_ref().map_err(Clone::clone)?;
+        Ok(vars.get(key).cloned())
+    }
+
+    /// List every `section.key` and bare global key.
+    fn keys(&self) -> Result<Vec<String>> {
+        let vars = self.vars.as_ref().map_err(Clone::clone)?;
+        Ok(vars.keys().cloned().collect())
+    }
+
+    /// Name the key and the file.
+    fn describe(&self, key: &str) -> String {
+        format!("{key} in file {}", self.path.display())
+    }
+}
+
+/// Parse INI lines into a map of `section.key` to value.
+fn parse_ini(path: &str, contents: &str) -> Result<BTreeMap<String, String>> {
+    let mut section = None;
+    let mut vars = BTreeMap::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name.strip_suffix(']').ok_or_else(|| {
+                ConfError::file_parse_failed(path, i + 1, line.len(), "expected `]`")
+            })?;
+            section = Some(name.trim().to_string());
+            continue;
+        }
+        let (key, val) = line.split_once('=').ok_or_else(|| {
+            ConfError::file_parse_failed(path, i + 1, 1, "expected key = value or [section]")
+        })?;
+        let key = match &section {
+            Some(section) => format!("{section}.{}", key.trim()),
+            None => key.trim().to_string(),
+        };
+        vars.insert(key, unquote(val.trim()).to_string());
+    }
+    Ok(vars)
+}
+
+/// Strip one pair of matching surrounding quotes, if present.
+fn unquote(val: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|&q| val.strip_prefix(q).and_then(|v| v.strip_suffix(q)))
+        .unwrap_or(val)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+    use std::io::Write;
+
+    fn ini_file(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    pub fn get_ini_sections() {
+        let file = ini_file(
+            "; legacy config\nname = xela\n\n[empty]\n\n[db]\nhost = \"localhost\"\nport = 5432\nport = 6543\n[server]\nbanner = ' hi '\n",
+        );
+        let conf = Conf::with_source("vcfg", IniSource::from_path(file.path()))
+            .string("name", None)
+            .string("db.host", None)
+            .uint("db.port", None)
+            .string("server.banner", None)
+            .string("empty.key", None);
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(
+            conf.get_string("db.host").unwrap(),
+            Some("localhost".to_string())
+        );
+        assert_eq!(conf.get_uint("db.port").unwrap(), Some(6543));
+        assert_eq!(
+            conf.get_string("server.banner").unwrap(),
+            Some(" hi ".to_string())
+        );
+        assert_eq!(conf.get_string("empty.key").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_ini_malformed() {
+        let file = ini_file("[db\nhost = localhost\n");
+        let source = IniSource::from_path(file.path());
+        assert!(matches!(
+            source.get("db.host").unwrap_err(),
+            ConfError::FileParseFailed { line: 1, .. }
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{ConfError, ConfSource, Result};
+use std::path::PathBuf;
+
+/// A [`ConfSource`] for resolving values from a JSON file. Nested values are looked up with
+/// dotted keys, so `db.host` resolves `{"db": {"host": "..."}}`. Malformed JSON is reported as
+/// [`ConfError::FileParseFailed`] on lookup, like the other file sources.
+pub struct JsonSource {
+    /// Path of the JSON file. A missing file is treated as empty.
+    pub path: PathBuf,
+    doc: Result<serde_json::Value>,
+}
+
+/// Former name of [`JsonSource`].
+#[deprecated(note = "renamed to `JsonSource`")]
+pub type JsonFileSource = JsonSource;
+
+impl JsonSource {
+    /// Create a new [`JsonSource`] reading from an explicit path. The file is parsed once, here.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let doc = match std::fs::read_to_string(&path) {
+            Ok(s) => serde_json::from_str(&s).map_err(|e| {
+                ConfError::file_parse_failed(
+                    path.display().to_string(),
+                    e.line(),
+                    e.column(),
+                    e.to_string(),
+                )
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(serde_json::Value::Null),
+            Err(e) => Err(ConfError::file_load_failed(
+                path.display().to_string(),
+                e.to_string(),
+            )),
+        };
+        Self { path, doc }
+    }
+}
+
+impl ConfSource for JsonSource {
+    /// Create a new [`JsonSource`] reading from `{name}.json` in the current directory.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_path(format!("{}.json", name.into()))
+    }
+
+    /// Create a new [`JsonSource`] like [`ConfSource::new`], failing if the file can't be read or
+    /// parsed.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        let source = Self::new(name);
+        source.doc.as_ref().map_err(Clone::clone)?;
+        Ok(source)
+    }
+
+    /// Look up a dotted key. Scalars are returned in their string form, while arrays and objects
+    /// are serialized as JSON so they can be parsed into [`serde_json::Value`].
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let doc = self.doc.as_ref().map_err(Clone::clone)?;
+        Ok(json_lookup(doc, key))
+    }
+
+    /// List the dotted keys of every scalar and array, see [`ConfSource::keys`].
+    fn keys(&self) -> Result<Vec<String>> {
+        let doc = self.doc.as_ref().map_err(Clone::clone)?;
+        Ok(json_keys(doc))
+    }
+
+    /// Name the key and the file.
+    fn describe(&self, key: &str) -> String {
+        format!("{key} in file {}", self.path.display())
+    }
+}
+
+/// Resolve a dotted key in a JSON document. Path segments index into objects, or into arrays
+/// when numeric. `null` is treated as absent.
+pub(crate) fn json_lookup(doc: &serde_json::Value, key: &str) -> Option<String> {
+    key.split('.')
+        .try_fold(doc, |v, k| match v {
+            serde_json::Value::Object(map) => map.get(k),
+            serde_json::Value::Array(arr) => k.parse::<usize>().ok().and_then(|i| arr.get(i)),
+            _ => None,
+        })
+        .and_then(|v| match v {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some(s.clone()),
+            _ => Some(v.to_string()),
+        })
+}
+
+/// List the dotted keys of every non-null scalar and array in a JSON document, sorted. Objects
+/// are descended into rather than listed.
+pub(crate) fn json_keys(doc: &serde_json::Value) -> Vec<String> {
+    let mut keys = Vec::new();
+    if let serde_json::Value::Object(map) = doc {
+        collect_json_keys(&mut keys, "", map);
+    }
+    keys.sort();
+    keys
+}
+
+/// Push the dotted keys under `prefix` of every leaf in an object.
+fn collect_json_keys(
+    keys: &mut Vec<String>,
+    prefix: &str,
+    map: &serde_json::Map<String, serde_json::Value>,
+) {
+    for (k, v) in map {
+        let key = format!("{prefix}{k}");
+        match v {
+            serde_json::Value::Object(map) => collect_json_keys(keys, &format!("{key}."), map),
+            serde_json::Value::Null => {}
+            _ => keys.push(key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Conf, ConfEntry};
+    use std::io::Write;
+
+    fn json_file(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    pub fn get_json_nested() {
+        let file = json_file(r#"{"db": {"host": "localhost", "port": 5432}, "tags": ["a", "b"]}"#);
+        let conf = Conf::with_source("vcfg", JsonSource::from_path(file.path()))
+            .string("db.host", None)
+            .uint("db.port", None)
+            .string("db.user", None)
+            .entry(ConfEntry::<serde_json::Value>::new("tags"));
+        assert_eq!(
+            conf.get_string("db.host").unwrap(),
+            Some("localhost".to_string())
+        );
+        assert_eq!(conf.get_uint("db.port").unwrap(), Some(5432));
+        assert_eq!(conf.get_string("db.user").unwrap(), None);
+        assert_eq!(
+            conf.require::<serde_json::Value>("tags").unwrap(),
+            serde_json::json!(["a", "b"])
+        );
+        assert_eq!(conf.source_keys().unwrap(), ["db.host", "db.port", "tags"]);
+    }
+
+    #[test]
+    pub fn get_json_parse_failed() {
+        let file = json_file("{\n  \"name\": \"xela\",\n  oops\n}");
+        let source = JsonSource::from_path(file.path());
+        assert!(matches!(
+            source.get("name").unwrap_err(),
+            ConfError::FileParseFailed {
+                line: 3,
+                column: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    pub fn get_json_server() {
+        let file = json_file(r#"{"name": "xela", "server": {"host": "0.0.0.0", "port": 8080}}"#);
+        let conf = Conf::with_source("vcfg", JsonSource::from_path(file.path()))
+            .string("name", None)
+            .uint("server.port", None)
+            .entry(ConfEntry::<serde_json::Value>::new("server"));
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(conf.get_uint("server.port").unwrap(), Some(8080));
+        assert_eq!(
+            conf.require::<serde_json::Value>("server").unwrap(),
+            serde_json::json!({"host": "0.0.0.0", "port": 8080})
+        );
+    }
+
+    #[test]
+    pub fn try_new_json_malformed() {
+        let dir = tempfile::tempdir().unwrap();
+        let name: &'static str = dir.path().join("vcfg").to_str().unwrap().to_string().leak();
+        assert!(Conf::<JsonSource>::try_new(name).is_ok());
+        std::fs::write(format!("{name}.json"), "{\"name\": ").unwrap();
+        let err = Conf::<JsonSource>::try_new(name).err().unwrap();
+        assert!(matches!(
+            &err,
+            ConfError::SourceInit { source, .. }
+                if matches!(**source, ConfError::FileParseFailed { line: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{ConfError, ConfSource, Result};
+
+/// A [`ConfSource`] for resolving secrets from the OS keychain: the macOS Keychain, Windows
+/// Credential Manager, or the Secret Service on Linux. Each lookup reads the credential with
+/// service `name` and account `key`.
+///
+/// A missing credential is `None`. A locked keychain or a backend failure is a
+/// [`ConfError::KeyringFailed`], so an app can prompt the user to unlock it and retry.
+pub struct KeyringSource {
+    /// Keychain service name.
+    pub name: String,
+}
+
+impl KeyringSource {
+    /// Store a credential, replacing any existing one. Useful to save a token after a login flow
+    /// so later lookups find it.
+    pub fn set(&self, key: &str, value: &str) -> Result {
+        self.entry(key)?
+            .set_password(value)
+            .map_err(|e| self.error(key, e))
+    }
+
+    /// Open the keychain entry for a key.
+    fn entry(&self, key: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(&self.name, key).map_err(|e| self.error(key, e))
+    }
+
+    fn error(&self, key: &str, e: keyring::Error) -> ConfError {
+        match e {
+            keyring::Error::BadEncoding(_) => ConfError::val_not_utf8(key),
+            e => ConfError::keyring_failed(&self.name, key, e.to_string()),
+        }
+    }
+}
+
+impl ConfSource for KeyringSource {
+    /// Create a new [`KeyringSource`] with the given name as the service.
+    fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    /// Read the password of the credential for `key`.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        match self.entry(key)?.get_password() {
+            Ok(v) => Ok(Some(v)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(self.error(key, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_keyring_missing() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let source = KeyringSource::new("vcfg");
+        source.set("token", "secret").unwrap();
+        let conf = Conf::with_source("vcfg", source).string("api_token", Some("default"));
+        assert_eq!(
+            conf.get_string("api_token").unwrap(),
+            Some("default".to_string())
+        );
+    }
+}
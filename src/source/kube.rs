@@ -0,0 +1,276 @@
+use crate::{ConfError, ConfSource, Result};
+use rustls::pki_types::{pem::PemObject, CertificateDer};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Directory where Kubernetes mounts the pod's service account credentials.
+pub const SERVICE_ACCOUNT_DIR: &str = "/var/run/secrets/kubernetes.io/serviceaccount";
+
+/// A [`ConfSource`] for resolving values from the `data` of a Kubernetes ConfigMap, read through
+/// the API server rather than a volume mount. In a pod, the API server, namespace, token and CA
+/// certificate are found from the in-cluster service account.
+///
+/// The ConfigMap is fetched on the first lookup and cached until [`KubeConfigMapSource::refresh`].
+/// A missing ConfigMap resolves every key to `None`. Reading it requires RBAC permission to `get`
+/// `configmaps` in the namespace; a denied request is a [`ConfError::AuthFailed`] saying so.
+pub struct KubeConfigMapSource {
+    /// API server URL.
+    pub server: String,
+    /// Namespace of the ConfigMap.
+    pub namespace: String,
+    /// Name of the ConfigMap.
+    pub config_map: String,
+    /// CA certificate used to verify the API server, if any.
+    pub ca_cert: Option<PathBuf>,
+    token: Option<String>,
+    data: RwLock<Option<Result<BTreeMap<String, String>>>>,
+}
+
+/// ConfigMap read response.
+#[derive(Deserialize)]
+struct ConfigMap {
+    #[serde(default)]
+    data: BTreeMap<String, String>,
+}
+
+/// Kubernetes `Status` error response.
+#[derive(Deserialize)]
+struct Status {
+    message: String,
+}
+
+impl KubeConfigMapSource {
+    /// Update the API server URL.
+    pub fn with_server(mut self, server: impl Into<String>) -> Self {
+        self.server = server.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Update the namespace, overriding the pod's own.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// Update the ConfigMap name, overriding the config name.
+    pub fn with_config_map(mut self, config_map: impl Into<String>) -> Self {
+        self.config_map = config_map.into();
+        self
+    }
+
+    /// Set the bearer token, overriding the service account token.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Set the CA certificate used to verify the API server.
+    pub fn with_ca_cert(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_cert = Some(path.into());
+        self
+    }
+
+    /// ConfigMap read URL.
+    pub fn url(&self) -> String {
+        format!(
+            "{}/api/v1/namespaces/{}/configmaps/{}",
+            self.server, self.namespace, self.config_map
+        )
+    }
+
+    /// Fetch the ConfigMap again, replacing the cached data. The old data is kept if the fetch
+    /// fails.
+    pub fn refresh(&self) -> Result {
+        let data = self.fetch()?;
+        *self.data.write().unwrap_or_else(|e| e.into_inner()) = Some(Ok(data));
+        Ok(())
+    }
+
+    /// Build an agent trusting [`KubeConfigMapSource::ca_cert`], if set.
+    fn agent(&self, url: &str) -> Result<ureq::Agent> {
+        let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(10));
+        let Some(path) = &self.ca_cert else {
+            return Ok(agent.build());
+        };
+        let ca_err = |reason: String| ConfError::source_io(path.display().to_string(), reason);
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in CertificateDer::pem_file_iter(path).map_err(|e| ca_err(e.to_string()))? {
+            let cert = cert.map_err(|e| ca_err(e.to_string()))?;
+            roots.add(cert).map_err(|e| ca_err(e.to_string()))?;
+        }
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| ConfError::request_failed(url, None, e.to_string()))?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        Ok(agent.tls_config(Arc::new(config)).build())
+    }
+
+    /// Fetch the ConfigMap data. A 404 is an empty map.
+    fn fetch(&self) -> Result<BTreeMap<String, String>> {
+        let url = self.url();
+        let token = match &self.token {
+            Some(token) => Some(token.clone()),
+            None => read_service_account("token")?,
+        };
+        let mut request = self.agent(&url)?.get(&url);
+        if let Some(token) = &token {
+            request = request.set("Authorization", &format!("Bearer {token}"));
+        }
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(BTreeMap::new()),
+            Err(ureq::Error::Status(status @ (401 | 403), response)) => {
+                let message = response
+                    .into_string()
+                    .ok()
+                    .and_then(|body| serde_json::from_str::<Status>(&body).ok())
+                    .map(|s| s.message)
+                    .unwrap_or_else(|| format!("status code {status}"));
+                let reason = match status {
+                    403 => format!(
+                        "{message}; the service account needs RBAC permission to `get` \
+                         `configmaps` in namespace `{}`",
+                        self.namespace
+                    ),
+                    _ => message,
+                };
+                return Err(ConfError::auth_failed(url, reason));
+            }
+            Err(e @ ureq::Error::Status(status, _)) => {
+                return Err(ConfError::request_failed(url, Some(status), e.to_string()))
+            }
+            Err(e) => return Err(ConfError::request_failed(url, None, e.to_string())),
+        };
+        let status = Some(response.status());
+        response
+            .into_string()
+            .map_err(|e| e.to_string())
+            .and_then(|body| serde_json::from_str::<ConfigMap>(&body).map_err(|e| e.to_string()))
+            .map(|config_map| config_map.data)
+            .map_err(|reason| ConfError::request_failed(url, status, reason))
+    }
+}
+
+/// Read a file from [`SERVICE_ACCOUNT_DIR`], or `None` if it doesn't exist.
+fn read_service_account(file: &str) -> Result<Option<String>> {
+    let path = Path::new(SERVICE_ACCOUNT_DIR).join(file);
+    match std::fs::read_to_string(&path) {
+        Ok(s) => Ok(Some(s.trim().to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(ConfError::source_io(
+            path.display().to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+impl ConfSource for KubeConfigMapSource {
+    /// Create a new [`KubeConfigMapSource`] reading the ConfigMap named `name` in the pod's
+    /// namespace. The API server is found from `KUBERNETES_SERVICE_HOST` and
+    /// `KUBERNETES_SERVICE_PORT`, and the namespace, token and CA certificate from
+    /// [`SERVICE_ACCOUNT_DIR`].
+    fn new(name: impl Into<String>) -> Self {
+        let server = match (
+            std::env::var("KUBERNETES_SERVICE_HOST"),
+            std::env::var("KUBERNETES_SERVICE_PORT"),
+        ) {
+            (Ok(host), Ok(port)) if host.contains(':') => format!("https://[{host}]:{port}"),
+            (Ok(host), Ok(port)) => format!("https://{host}:{port}"),
+            _ => "https://kubernetes.default.svc".to_string(),
+        };
+        let ca_cert = Path::new(SERVICE_ACCOUNT_DIR).join("ca.crt");
+        Self {
+            server,
+            namespace: read_service_account("namespace")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "default".to_string()),
+            config_map: name.into(),
+            ca_cert: ca_cert.exists().then_some(ca_cert),
+            token: None,
+            data: RwLock::new(None),
+        }
+    }
+
+    /// Look up a key in the cached ConfigMap data.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        if let Some(data) = &*self.data.read().unwrap_or_else(|e| e.into_inner()) {
+            let data = data.as_ref().map_err(Clone::clone)?;
+            return Ok(data.get(key).cloned());
+        }
+        let mut data = self.data.write().unwrap_or_else(|e| e.into_inner());
+        let data = data.get_or_insert_with(|| self.fetch());
+        Ok(data.as_ref().map_err(Clone::clone)?.get(key).cloned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::source::mock::serve;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_kube_config_map_refresh() {
+        let (server, requests) = serve(vec![
+            (
+                200,
+                r#"{"kind": "ConfigMap", "data": {"log_level": "info"}}"#,
+            ),
+            (
+                200,
+                r#"{"kind": "ConfigMap", "data": {"log_level": "debug"}}"#,
+            ),
+        ]);
+        let source = KubeConfigMapSource::new("vcfg")
+            .with_server(server)
+            .with_namespace("apps")
+            .with_config_map("app-config")
+            .with_token("sa.token");
+        let conf = Conf::with_source("vcfg", source)
+            .string("log_level", None)
+            .string("missing", None);
+        assert_eq!(
+            conf.get_string("log_level").unwrap(),
+            Some("info".to_string())
+        );
+        assert_eq!(conf.get_string("missing").unwrap(), None);
+        conf.source.refresh().unwrap();
+        assert_eq!(
+            conf.get_string("log_level").unwrap(),
+            Some("debug".to_string())
+        );
+        let requests = requests.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("GET /api/v1/namespaces/apps/configmaps/app-config "));
+        assert!(requests[0]
+            .to_ascii_lowercase()
+            .contains("authorization: bearer sa.token"));
+    }
+
+    #[test]
+    pub fn get_kube_config_map_forbidden() {
+        let (server, _) = serve(vec![(
+            403,
+            r#"{"kind": "Status", "message": "configmaps \"vcfg\" is forbidden"}"#,
+        )]);
+        let source = KubeConfigMapSource::new("vcfg")
+            .with_server(&server)
+            .with_namespace("apps")
+            .with_token("sa.token");
+        assert_eq!(
+            source.get("log_level").unwrap_err(),
+            ConfError::AuthFailed {
+                url: format!("{server}/api/v1/namespaces/apps/configmaps/vcfg"),
+                reason: "configmaps \"vcfg\" is forbidden; the service account needs RBAC \
+                         permission to `get` `configmaps` in namespace `apps`"
+                    .to_string(),
+            }
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{ConfSink, ConfSource, EnvSource, Result};
+use std::collections::{BTreeMap, HashMap};
+
+/// A [`ConfSource`] for resolving values from an in-memory map of env-style variables. Keys are
+/// translated the same way as [`EnvSource`], so it can stand in for the environment in tests
+/// without touching global state.
+pub struct InMemorySource {
+    keys: EnvSource,
+    vars: HashMap<String, String>,
+}
+
+impl InMemorySource {
+    /// Set a variable, named as it would be in the environment, e.g. `VCFG_NAME`.
+    pub fn set(mut self, var: impl Into<String>, val: impl Into<String>) -> Self {
+        self.vars.insert(var.into(), val.into());
+        self
+    }
+
+    /// Translate a key name into its corresponding variable name. See [`EnvSource::env_key`].
+    pub fn env_key(&self, key: impl Into<String>) -> String {
+        self.keys.env_key(key)
+    }
+}
+
+impl ConfSource for InMemorySource {
+    /// Create a new, empty [`InMemorySource`] with the given name as a prefix.
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            keys: EnvSource::new(name),
+            vars: HashMap::new(),
+        }
+    }
+
+    /// Query the value using the [translated key](InMemorySource::env_key) from the map.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.vars.get(&self.env_key(key)).cloned())
+    }
+
+    /// List the keys of the prefixed variables, see [`EnvSource::key_from_env_key`].
+    fn keys(&self) -> Result<Vec<String>> {
+        let mut keys: Vec<String> = self
+            .vars
+            .keys()
+            .filter_map(|var| self.keys.key_from_env_key(var))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Name the [translated key](InMemorySource::env_key).
+    fn describe(&self, key: &str) -> String {
+        format!("in-memory var {}", self.env_key(key))
+    }
+
+    /// The [translated key](InMemorySource::env_key).
+    fn env_var(&self, key: &str) -> Option<String> {
+        Some(self.env_key(key))
+    }
+}
+
+/// A [`ConfSource`] for resolving values from an in-memory map. Unlike [`InMemorySource`], keys
+/// are used as-is with no prefix, which makes it handy for embedding hardcoded config.
+#[derive(Clone, Debug, Default)]
+pub struct MapSource {
+    /// Map of keys to values.
+    pub vars: BTreeMap<String, String>,
+}
+
+impl MapSource {
+    /// Set a value, replacing any existing one.
+    pub fn set(&mut self, key: impl Into<String>, val: impl Into<String>) {
+        self.vars.insert(key.into(), val.into());
+    }
+
+    /// Remove a value.
+    pub fn remove(&mut self, key: &str) {
+        self.vars.remove(key);
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for MapSource {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            vars: iter
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+}
+
+impl ConfSource for MapSource {
+    /// Create a new, empty [`MapSource`]. The name is unused.
+    fn new(_name: impl Into<String>) -> Self {
+        Self::default()
+    }
+
+    /// Query the value from the map.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.vars.get(key).cloned())
+    }
+
+    /// List the keys in the map.
+    fn keys(&self) -> Result<Vec<String>> {
+        Ok(self.vars.keys().cloned().collect())
+    }
+}
+
+impl ConfSink for MapSource {
+    /// Set a value in the map.
+    fn set(&mut self, key: &str, val: &str) -> Result {
+        MapSource::set(self, key, val);
+        Ok(())
+    }
+
+    /// Remove a value from the map.
+    fn remove(&mut self, key: &str) -> Result {
+        MapSource::remove(self, key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_memory_isolated() {
+        let conf_a =
+            Conf::with_source("vcfg", InMemorySource::new("vcfg").set("VCFG_NAME", "xela"))
+                .string("name", Some("world"));
+        let conf_b =
+            Conf::with_source("vcfg", InMemorySource::new("vcfg")).string("name", Some("world"));
+        assert_eq!(conf_a.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(
+            conf_b.get_string("name").unwrap(),
+            Some("world".to_string())
+        );
+    }
+
+    #[test]
+    pub fn get_memory_prefixed() {
+        let source = InMemorySource::new("app")
+            .set("APP_PORT", "8080")
+            .set("VCFG_PORT", "9090");
+        let conf = Conf::with_source("app", source).uint("port", None);
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+    }
+
+    #[test]
+    pub fn get_map_mutation() {
+        let source = MapSource::from_iter([("name", "xela")]);
+        let mut conf = Conf::with_source("test", source)
+            .string("name", None)
+            .uint("port", Some(80));
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(80));
+        conf.source.set("port", "8080");
+        conf.source.remove("name");
+        assert_eq!(conf.get_string("name").unwrap(), None);
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        let empty = Conf::<MapSource>::new("test").string("name", None);
+        assert_eq!(empty.get_string("name").unwrap(), None);
+    }
+}
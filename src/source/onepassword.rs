@@ -0,0 +1,185 @@
+use crate::{ConfError, ConfSource, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Default [`OnePasswordSource::template`].
+pub const DEFAULT_OP_TEMPLATE: &str = "op://{vault}/{name}/{key}";
+
+/// A [`ConfSource`] for resolving secrets with the 1Password CLI. Each key is read with
+/// `op read` from a secret reference built from [`OnePasswordSource::template`], by default
+/// `op://{vault}/{name}/{key}` in the `Private` vault.
+///
+/// `op read` is slow, so values and misses are cached for the life of the source and the CLI is
+/// only run once per key. A reference to a missing item or field is `None`. If `op` isn't
+/// installed or isn't signed in, lookups fail with [`ConfError::OnePasswordUnavailable`], whose
+/// help explains how to fix it.
+pub struct OnePasswordSource {
+    /// Config name, substituted for `{name}` in the template.
+    pub name: String,
+    /// Vault, substituted for `{vault}` in the template.
+    pub vault: String,
+    /// Secret reference template with `{vault}`, `{name}` and `{key}` placeholders.
+    pub template: String,
+    /// Path of the `op` program, found on `PATH` by default.
+    pub program: PathBuf,
+    cache: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl OnePasswordSource {
+    /// Update the vault.
+    pub fn with_vault(mut self, vault: impl Into<String>) -> Self {
+        self.vault = vault.into();
+        self
+    }
+
+    /// Update the secret reference template.
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = template.into();
+        self
+    }
+
+    /// Update the path of the `op` program.
+    pub fn with_program(mut self, program: impl Into<PathBuf>) -> Self {
+        self.program = program.into();
+        self
+    }
+
+    /// Secret reference for a key.
+    pub fn reference(&self, key: &str) -> String {
+        self.template
+            .replace("{vault}", &self.vault)
+            .replace("{name}", &self.name)
+            .replace("{key}", key)
+    }
+
+    /// Run `op read` for a key.
+    fn read(&self, key: &str) -> Result<Option<String>> {
+        let reference = self.reference(key);
+        let command = format!("{} read {reference}", self.program.display());
+        let output = Command::new(&self.program)
+            .args(["read", "--no-newline", &reference])
+            .output()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => ConfError::one_password_unavailable(
+                    format!("`{}` is not installed", self.program.display()),
+                    "install the 1Password CLI from https://developer.1password.com/docs/cli/ \
+                     and make sure `op` is on PATH",
+                ),
+                _ => ConfError::command_failed(key, &command, e.to_string()),
+            })?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim_end();
+            if stderr.contains("not currently signed in") || stderr.contains("no accounts") {
+                return Err(ConfError::one_password_unavailable(
+                    stderr,
+                    "sign in with `eval $(op signin)`, or turn on the 1Password app integration \
+                     under Settings > Developer",
+                ));
+            }
+            if stderr.contains("isn't an item") || stderr.contains("isn't a field") {
+                return Ok(None);
+            }
+            return Err(ConfError::command_failed(
+                key,
+                command,
+                format!("{}: {stderr}", output.status),
+            ));
+        }
+        let stdout = String::from_utf8(output.stdout).map_err(|_| ConfError::val_not_utf8(key))?;
+        Ok(Some(stdout.trim().to_string()))
+    }
+}
+
+impl ConfSource for OnePasswordSource {
+    /// Create a new [`OnePasswordSource`] reading items named `name` from the `Private` vault.
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            vault: "Private".to_string(),
+            template: DEFAULT_OP_TEMPLATE.to_string(),
+            program: PathBuf::from("op"),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the cached value, or read it with `op read`. Errors are not cached.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(v) = cache.get(key) {
+            return Ok(v.clone());
+        }
+        let v = self.read(key)?;
+        cache.insert(key.to_string(), v.clone());
+        Ok(v)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use crate::Conf;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Write a fake `op` script which logs its arguments next to itself.
+    fn fake_op(dir: &std::path::Path, script: &str) -> PathBuf {
+        let path = dir.join("op");
+        let log = dir.join("calls");
+        std::fs::write(
+            &path,
+            format!("#!/bin/sh\necho \"$@\" >> '{}'\n{script}\n", log.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    pub fn get_one_password_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let op = fake_op(
+            dir.path(),
+            r#"case "$3" in
+  op://Work/vcfg/token) printf ' s3cret\n' ;;
+  *) echo "[ERROR] could not read secret '$3': 'vcfg' isn't an item in the 'Work' vault" >&2; exit 1 ;;
+esac"#,
+        );
+        let source = OnePasswordSource::new("vcfg")
+            .with_vault("Work")
+            .with_program(op);
+        let conf = Conf::with_source("vcfg", source)
+            .secret("token", None)
+            .string("missing", None);
+        for _ in 0..3 {
+            assert_eq!(conf.require_secret("token").unwrap().expose(), "s3cret");
+            assert_eq!(conf.get_string("missing").unwrap(), None);
+        }
+        let calls = std::fs::read_to_string(dir.path().join("calls")).unwrap();
+        assert_eq!(
+            calls,
+            "read --no-newline op://Work/vcfg/token\nread --no-newline op://Work/vcfg/missing\n"
+        );
+    }
+
+    #[test]
+    pub fn get_one_password_unavailable() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = OnePasswordSource::new("vcfg").with_program(dir.path().join("missing"));
+        assert!(matches!(
+            source.get("token").unwrap_err(),
+            ConfError::OnePasswordUnavailable { .. }
+        ));
+
+        let op = fake_op(
+            dir.path(),
+            "echo '[ERROR] you are not currently signed in. Please run `op signin --help`' >&2\nexit 1",
+        );
+        let source = OnePasswordSource::new("vcfg").with_program(op);
+        assert!(matches!(
+            source.get("token").unwrap_err(),
+            ConfError::OnePasswordUnavailable { help, .. } if help.contains("op signin")
+        ));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{ConfError, ConfSource, Result};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Default Redis URL for [`RedisSource`] when `REDIS_URL` is unset.
+pub const DEFAULT_REDIS_URL: &str = "redis://127.0.0.1:6379";
+
+/// A [`ConfSource`] for resolving values from plain Redis string keys. Each lookup issues
+/// `GET {name}:{key}`, so the `port` key of the `vcfg` config is stored at `vcfg:port`.
+///
+/// The connection is opened on the first lookup rather than at construction, so building a
+/// [`Conf`](crate::Conf) doesn't require Redis to be up. After a failure the connection is
+/// dropped and reopened on the next lookup.
+pub struct RedisSource {
+    /// Key prefix.
+    pub name: String,
+    /// Redis connection URL, e.g. `redis://:password@host:6379/0`.
+    pub url: String,
+    conn: Mutex<Option<redis::Connection>>,
+}
+
+impl RedisSource {
+    /// Create a new [`RedisSource`] with the given name as a key prefix, connecting to an
+    /// explicit URL.
+    pub fn from_url(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            conn: Mutex::new(None),
+        }
+    }
+
+    /// The URL with any password removed, for error messages.
+    fn redacted_url(&self) -> String {
+        match (self.url.split_once("://"), self.url.rfind('@')) {
+            (Some((scheme, _)), Some(at)) => format!("{scheme}://***{}", &self.url[at..]),
+            _ => self.url.clone(),
+        }
+    }
+
+    /// Open a new connection.
+    fn connect(&self) -> redis::RedisResult<redis::Connection> {
+        redis::Client::open(self.url.as_str())?.get_connection_with_timeout(Duration::from_secs(10))
+    }
+}
+
+impl ConfSource for RedisSource {
+    /// Create a new [`RedisSource`] connecting to the URL in `REDIS_URL`, or
+    /// [`DEFAULT_REDIS_URL`] if unset.
+    fn new(name: impl Into<String>) -> Self {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| DEFAULT_REDIS_URL.to_string());
+        Self::from_url(name, url)
+    }
+
+    /// Fetch `{name}:{key}`. A nil reply is `None`; connection and protocol errors are a
+    /// [`ConfError::ConnectionFailed`].
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let result = match conn.as_mut() {
+            Some(c) => Ok(c),
+            None => self.connect().map(|c| conn.insert(c)),
+        }
+        .and_then(|c| {
+            redis::cmd("GET")
+                .arg(format!("{}:{}", self.name, key))
+                .query::<Option<String>>(c)
+        });
+        result.map_err(|e| {
+            *conn = None;
+            ConfError::connection_failed(self.redacted_url(), e.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// Serve a single connection answering `GET` from `vals` and anything else with `+OK`.
+    fn serve_redis(vals: &'static [(&'static str, &'static str)]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 {
+                let count: usize = line.trim()[1..].parse().unwrap();
+                let mut args = Vec::new();
+                for _ in 0..count * 2 {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    args.push(line.trim().to_string());
+                }
+                let reply = match (args[1].as_str(), args.get(3)) {
+                    ("GET", Some(key)) => match vals.iter().find(|(k, _)| k == key) {
+                        Some((_, v)) => format!("${}\r\n{v}\r\n", v.len()),
+                        None => "$-1\r\n".to_string(),
+                    },
+                    _ => "+OK\r\n".to_string(),
+                };
+                stream.write_all(reply.as_bytes()).unwrap();
+                line.clear();
+            }
+        });
+        url
+    }
+
+    #[test]
+    pub fn get_redis_values() {
+        let url = serve_redis(&[("vcfg:port", "6380"), ("vcfg:name", "xela")]);
+        let conf = Conf::with_source("vcfg", RedisSource::from_url("vcfg", url))
+            .uint("port", None)
+            .string("name", None)
+            .string("missing", None);
+        assert_eq!(conf.get_uint("port").unwrap(), Some(6380));
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(conf.get_string("missing").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_redis_unreachable() {
+        let source = RedisSource::from_url("vcfg", "redis://:hunter2@127.0.0.1:1");
+        assert!(matches!(
+            source.get("port").unwrap_err(),
+            ConfError::ConnectionFailed { url, .. } if url == "redis://***@127.0.0.1:1"
+        ));
+    }
+}
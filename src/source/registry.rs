@@ -0,0 +1,99 @@
+use crate::{ConfError, ConfSource, Result};
+use std::io::ErrorKind;
+use winreg::enums::{
+    HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, REG_DWORD, REG_EXPAND_SZ, REG_QWORD, REG_SZ,
+};
+use winreg::types::FromRegValue;
+use winreg::RegKey;
+
+/// The registry hive a [`RegistrySource`] reads from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryHive {
+    /// `HKEY_LOCAL_MACHINE`, for machine-wide settings such as those of Windows services.
+    LocalMachine,
+    /// `HKEY_CURRENT_USER`, for per-user settings.
+    CurrentUser,
+}
+
+impl RegistryHive {
+    fn key(self) -> RegKey {
+        RegKey::predef(match self {
+            Self::LocalMachine => HKEY_LOCAL_MACHINE,
+            Self::CurrentUser => HKEY_CURRENT_USER,
+        })
+    }
+}
+
+impl std::fmt::Display for RegistryHive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::LocalMachine => "HKLM",
+            Self::CurrentUser => "HKCU",
+        })
+    }
+}
+
+/// A [`ConfSource`] for resolving values from the Windows registry. Each key is read as a value
+/// under a single registry key. `REG_SZ` and `REG_EXPAND_SZ` values are returned as-is, while
+/// `REG_DWORD` and `REG_QWORD` values are returned in decimal so they parse as numbers.
+///
+/// A missing registry key or value resolves to `None`. Any other failure, including access being
+/// denied, is a [`ConfError::SourceIo`].
+pub struct RegistrySource {
+    /// Hive containing the registry key.
+    pub hive: RegistryHive,
+    /// Path of the registry key within the hive.
+    pub path: String,
+}
+
+impl RegistrySource {
+    /// Create a new [`RegistrySource`] reading from an explicit hive and key path.
+    pub fn from_path(hive: RegistryHive, path: impl Into<String>) -> Self {
+        Self {
+            hive,
+            path: path.into(),
+        }
+    }
+
+    fn location(&self, key: &str) -> String {
+        format!("{}\\{}\\{}", self.hive, self.path, key)
+    }
+}
+
+impl ConfSource for RegistrySource {
+    /// Create a new [`RegistrySource`] reading from `HKLM\SOFTWARE\{name}`. A name such as
+    /// `Vendor\App` reads from `HKLM\SOFTWARE\Vendor\App`.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_path(
+            RegistryHive::LocalMachine,
+            format!("SOFTWARE\\{}", name.into()),
+        )
+    }
+
+    /// Read the registry value named `key`.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let io_err = |e: std::io::Error| ConfError::source_io(self.location(key), e.to_string());
+        let reg_key = match self.hive.key().open_subkey(&self.path) {
+            Ok(k) => k,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(io_err(e)),
+        };
+        let val = match reg_key.get_raw_value(key) {
+            Ok(v) => v,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(io_err(e)),
+        };
+        let val = match val.vtype {
+            REG_SZ | REG_EXPAND_SZ => String::from_reg_value(&val).map_err(io_err)?,
+            REG_DWORD => u32::from_reg_value(&val).map_err(io_err)?.to_string(),
+            REG_QWORD => u64::from_reg_value(&val).map_err(io_err)?.to_string(),
+            ref vtype => {
+                return Err(ConfError::source_io(
+                    self.location(key),
+                    format!("unsupported value type {vtype:?}"),
+                ))
+            }
+        };
+        Ok(Some(val))
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{ConfError, ConfSource, Result};
+use std::hash::{BuildHasher, RandomState};
+use std::time::Duration;
+
+/// A [`ConfSource`] which retries failed lookups in another source, for network-backed sources
+/// that fail transiently. Each retry waits for an exponentially growing delay with random
+/// jitter, so many processes starting at once don't retry in lockstep. A missing value is not
+/// an error and is never retried.
+///
+/// Only errors the [predicate](RetryingSource::with_retryable) accepts are retried, by default
+/// those which are [transient](ConfError::is_transient); others, such as authentication
+/// failures, are returned at once. Once every attempt has failed, the last error is returned in
+/// [`ConfError::RetriesExhausted`].
+///
+/// ```
+/// use std::time::Duration;
+/// use voidconf::{Conf, ConfSource, EnvSource, RetryingSource};
+///
+/// let source = RetryingSource::from_source(EnvSource::new("vcfg"))
+///     .with_attempts(5)
+///     .with_backoff(Duration::from_millis(50), Duration::from_secs(2));
+/// let conf = Conf::with_source("vcfg", source).uint("port", None);
+/// ```
+pub struct RetryingSource<S> {
+    /// Source being retried.
+    pub source: S,
+    /// Maximum number of attempts per lookup, including the first.
+    pub attempts: u32,
+    /// Delay before the first retry, doubled for each retry after it.
+    pub base_delay: Duration,
+    /// Upper bound on the delay between attempts.
+    pub max_delay: Duration,
+    /// Whether an error is worth retrying.
+    pub retryable: fn(&ConfError) -> bool,
+}
+
+impl<S: ConfSource> RetryingSource<S> {
+    /// Create a new [`RetryingSource`] wrapping an already initialized source, making 3 attempts
+    /// with delays starting at 100ms and capped at 5s.
+    pub fn from_source(source: S) -> Self {
+        Self {
+            source,
+            attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            retryable: ConfError::is_transient,
+        }
+    }
+
+    /// Set the maximum number of attempts per lookup, including the first. At least one attempt
+    /// is always made.
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry and the cap on later delays.
+    pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set which errors are retried. Others are returned as-is, without retrying.
+    pub fn with_retryable(mut self, retryable: fn(&ConfError) -> bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Delay before the given retry, between half and all of the exponential backoff.
+    fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let jitter = RandomState::new().hash_one(retry) as f64 / u64::MAX as f64;
+        backoff.mul_f64(0.5 + jitter / 2.0)
+    }
+
+    /// Call `f` on the wrapped source until it succeeds, fails with an error which isn't
+    /// retryable, or runs out of attempts.
+    fn retry<T>(&self, f: impl Fn(&S) -> Result<T>) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            match f(&self.source) {
+                Err(e) if (self.retryable)(&e) => {
+                    if attempt >= self.attempts {
+                        return Err(ConfError::retries_exhausted(attempt, e));
+                    }
+                    std::thread::sleep(self.delay(attempt - 1));
+                    attempt += 1;
+                }
+                v => return v,
+            }
+        }
+    }
+}
+
+impl<S: ConfSource> ConfSource for RetryingSource<S> {
+    /// Create a new [`RetryingSource`] wrapping `S::new(name)`.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_source(S::new(name))
+    }
+
+    /// Create a new [`RetryingSource`] wrapping `S::try_new(name)`. Initialization isn't retried.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        S::try_new(name).map(Self::from_source)
+    }
+
+    /// Look up a key in the wrapped source, retrying on error.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        self.retry(|source| source.get(key))
+    }
+
+    /// List the keys of the wrapped source, retrying on error.
+    fn keys(&self) -> Result<Vec<String>> {
+        self.retry(|source| source.keys())
+    }
+
+    /// Describe the key in the wrapped source.
+    fn describe(&self, key: &str) -> String {
+        self.source.describe(key)
+    }
+
+    /// Look up and describe the key in the wrapped source, retrying on error.
+    fn get_described(&self, key: &str) -> Result<Option<(String, String)>> {
+        self.retry(|source| source.get_described(key))
+    }
+
+    /// Name the key's env var in the wrapped source.
+    fn env_var(&self, key: &str) -> Option<String> {
+        self.source.env_var(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Fails the first `failures` lookups with the given error.
+    struct FlakySource {
+        failures: usize,
+        error: ConfError,
+        hits: AtomicUsize,
+    }
+
+    impl ConfSource for FlakySource {
+        fn new(_name: impl Into<String>) -> Self {
+            Self {
+                failures: 2,
+                error: ConfError::connection_failed("flaky://", "connection reset"),
+                hits: AtomicUsize::new(0),
+            }
+        }
+
+        fn get(&self, key: &str) -> Result<Option<String>> {
+            if self.hits.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(self.error.clone());
+            }
+            Ok((key == "port").then(|| "8080".to_string()))
+        }
+    }
+
+    fn retrying(source: FlakySource) -> RetryingSource<FlakySource> {
+        RetryingSource::from_source(source).with_backoff(Duration::ZERO, Duration::ZERO)
+    }
+
+    #[test]
+    pub fn get_retrying_recovers() {
+        let conf = Conf::with_source("vcfg", retrying(FlakySource::new("vcfg")))
+            .uint("port", None)
+            .string("missing", None);
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(conf.source.source.hits.load(Ordering::SeqCst), 3);
+        assert_eq!(conf.get_string("missing").unwrap(), None);
+        assert_eq!(conf.source.source.hits.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    pub fn get_retrying_exhausted() {
+        let source = retrying(FlakySource::new("vcfg")).with_attempts(2);
+        assert_eq!(
+            source.get("port").unwrap_err(),
+            ConfError::retries_exhausted(
+                2,
+                ConfError::connection_failed("flaky://", "connection reset")
+            )
+        );
+
+        let source = retrying(FlakySource {
+            error: ConfError::auth_failed("flaky://", "bad token"),
+            ..FlakySource::new("vcfg")
+        });
+        assert_eq!(
+            source.get("port").unwrap_err(),
+            ConfError::auth_failed("flaky://", "bad token")
+        );
+        assert_eq!(source.source.hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    pub fn retrying_delay_backoff() {
+        let source = RetryingSource::from_source(FlakySource::new("vcfg"))
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(300));
+        for (retry, max) in [(0, 100), (1, 200), (2, 300), (10, 300)] {
+            let delay = source.delay(retry);
+            let max = Duration::from_millis(max);
+            assert!(delay >= max / 2 && delay <= max, "{retry}: {delay:?}");
+        }
+    }
+
+    #[cfg(feature = "vault")]
+    #[test]
+    pub fn get_retrying_remote_source() {
+        let (addr, requests) = crate::source::mock::serve(vec![
+            (503, r#"{"errors": ["Vault is sealed"]}"#),
+            (200, r#"{"data": {"data": {"password": "hunter2"}}}"#),
+        ]);
+        let source = RetryingSource::from_source(crate::VaultSource::new("vcfg").with_addr(addr))
+            .with_backoff(Duration::ZERO, Duration::ZERO);
+        let conf = Conf::with_source("vcfg", source)
+            .string("password", None)
+            .string("user", None);
+        assert_eq!(
+            conf.get_string("password").unwrap(),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(conf.get_string("user").unwrap(), None);
+        assert_eq!(requests.join().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,182 @@
+use super::aws::AwsClient;
+use super::json::json_lookup;
+use crate::{ConfError, ConfSource, Result};
+use aws_credential_types::Credentials;
+use serde::Deserialize;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// A [`ConfSource`] for resolving values from a JSON object stored in a single AWS Secrets
+/// Manager secret. The secret is fetched on the first lookup and cached; nested values are
+/// looked up with dotted keys like [`JsonSource`](crate::JsonSource). A failed fetch isn't
+/// cached, so the next lookup tries again. A missing secret resolves every key to `None`,
+/// while a binary or non-object secret is an error.
+///
+/// Credentials and region are resolved as for [`SsmSource`](crate::SsmSource).
+pub struct SecretsManagerSource {
+    /// Name or ARN of the secret.
+    pub secret_id: String,
+    client: AwsClient,
+    doc: RwLock<Option<serde_json::Value>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetSecretValueResponse {
+    secret_string: Option<String>,
+}
+
+impl SecretsManagerSource {
+    /// Create a new [`SecretsManagerSource`] reading the secret with the given name or ARN.
+    pub fn from_secret_id(secret_id: impl Into<String>) -> Self {
+        Self {
+            secret_id: secret_id.into(),
+            client: AwsClient::new("secretsmanager", "secretsmanager"),
+            doc: RwLock::new(None),
+        }
+    }
+
+    /// Update the AWS region.
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.client.region = region.into();
+        self
+    }
+
+    /// Override the Secrets Manager endpoint URL.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.client.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Set explicit credentials instead of resolving them from the environment.
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.client.credentials = Some(credentials);
+        self
+    }
+
+    /// Update the number of retries for throttled requests and the delay before the first
+    /// retry, which doubles for each subsequent retry.
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.client.retries = retries;
+        self.client.backoff = backoff;
+        self
+    }
+
+    /// Fetch the secret and parse it as a JSON object.
+    fn fetch(&self) -> Result<serde_json::Value> {
+        let body = serde_json::json!({ "SecretId": self.secret_id });
+        let Some(response) =
+            self.client
+                .call("GetSecretValue", &body, &["ResourceNotFoundException"])?
+        else {
+            return Ok(serde_json::Value::Object(Default::default()));
+        };
+        let invalid = |reason: String| {
+            ConfError::request_failed(
+                self.client.endpoint(),
+                None,
+                format!("secret {}: {reason}", self.secret_id),
+            )
+        };
+        let secret = serde_json::from_value::<GetSecretValueResponse>(response)
+            .map_err(|e| invalid(e.to_string()))?
+            .secret_string
+            .ok_or_else(|| invalid("binary secrets are not supported".to_string()))?;
+        match serde_json::from_str(&secret) {
+            Ok(doc @ serde_json::Value::Object(_)) => Ok(doc),
+            Ok(_) => Err(invalid("expected a JSON object".to_string())),
+            Err(e) => Err(invalid(format!("expected a JSON object: {e}"))),
+        }
+    }
+}
+
+impl ConfSource for SecretsManagerSource {
+    /// Create a new [`SecretsManagerSource`] reading the secret named `{name}`.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_secret_id(name)
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        if let Some(doc) = &*self.doc.read().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(json_lookup(doc, key));
+        }
+        let mut doc = self.doc.write().unwrap_or_else(|e| e.into_inner());
+        if doc.is_none() {
+            *doc = Some(self.fetch()?);
+        }
+        Ok(doc.as_ref().and_then(|doc| json_lookup(doc, key)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::source::mock::serve;
+    use crate::Conf;
+
+    fn secrets_source(endpoint: &str) -> SecretsManagerSource {
+        SecretsManagerSource::from_secret_id(
+            "arn:aws:secretsmanager:eu-west-1:123456789012:secret:vcfg-AbCdEf",
+        )
+        .with_region("eu-west-1")
+        .with_endpoint(endpoint)
+        .with_credentials(Credentials::new("AKID", "secret", None, None, "test"))
+    }
+
+    #[test]
+    pub fn get_secret_json() {
+        let (endpoint, requests) = serve(vec![(
+            200,
+            r#"{"Name": "vcfg", "SecretString": "{\"password\": \"hunter2\", \"port\": 5432}"}"#,
+        )]);
+        let conf = Conf::with_source("vcfg", secrets_source(&endpoint))
+            .string("password", None)
+            .uint("port", None)
+            .string("user", None);
+        assert_eq!(
+            conf.get_string("password").unwrap(),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(conf.get_uint("port").unwrap(), Some(5432));
+        assert_eq!(conf.get_string("user").unwrap(), None);
+        let requests = requests.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0]
+            .to_ascii_lowercase()
+            .contains("x-amz-target: secretsmanager.getsecretvalue"));
+        assert!(requests[0].contains(r#""SecretId":"arn:aws:secretsmanager:"#));
+    }
+
+    #[test]
+    pub fn get_secret_binary() {
+        let (endpoint, _) = serve(vec![(200, r#"{"Name": "vcfg", "SecretBinary": "AAEC"}"#)]);
+        let source = secrets_source(&endpoint);
+        assert_eq!(
+            source.get("password").unwrap_err(),
+            ConfError::RequestFailed {
+                url: endpoint,
+                status: None,
+                reason: format!(
+                    "secret {}: binary secrets are not supported",
+                    source.secret_id
+                ),
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_secret_retried() {
+        let (endpoint, requests) = serve(vec![
+            (503, r#"{"__type": "ServiceUnavailable"}"#),
+            (
+                200,
+                r#"{"Name": "vcfg", "SecretString": "{\"password\": \"hunter2\"}"}"#,
+            ),
+        ]);
+        let source = secrets_source(&endpoint).with_retries(0, Duration::from_millis(1));
+        assert!(source.get("password").unwrap_err().is_transient());
+        assert_eq!(source.get("password").unwrap(), Some("hunter2".to_string()));
+        assert_eq!(source.get("user").unwrap(), None);
+        assert_eq!(requests.join().unwrap().len(), 2);
+    }
+}
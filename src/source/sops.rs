@@ -0,0 +1,139 @@
+use super::json::{json_keys, json_lookup};
+use crate::{ConfError, ConfSource, Result};
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// A [`ConfSource`] for resolving values from a [SOPS](https://getsops.io)-encrypted file. The
+/// file is decrypted once, on construction, by running `sops --decrypt`, so any key type sops
+/// supports works. Nested values are looked up with dotted keys like
+/// [`YamlSource`](crate::YamlSource).
+///
+/// Decryption failure is a hard error rather than every key resolving to `None`: use
+/// [`SopsSource::from_path`] to handle it, while [`ConfSource::new`] panics.
+///
+/// ```no_run
+/// use voidconf::{Conf, SopsSource};
+///
+/// let source = SopsSource::from_path("secrets.enc.yaml")?;
+/// let conf = Conf::with_source("vcfg", source).secret("db.password", None);
+/// # Ok::<(), voidconf::ConfError>(())
+/// ```
+pub struct SopsSource {
+    /// Path of the encrypted file.
+    pub path: PathBuf,
+    doc: serde_json::Value,
+}
+
+impl SopsSource {
+    /// Decrypt a file with `sops` from `PATH`. Fails with [`ConfError::DecryptFailed`],
+    /// including the sops error output, if it can't be decrypted.
+    pub fn from_path(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::from_program("sops", path)
+    }
+
+    /// Decrypt a file with the given `sops` program. See [`SopsSource::from_path`].
+    pub fn from_program(program: impl AsRef<OsStr>, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let failed = |reason: String| ConfError::decrypt_failed(path.display().to_string(), reason);
+        let output = Command::new(program.as_ref())
+            .args(["--decrypt", "--output-type", "json"])
+            .arg(&path)
+            .output()
+            .map_err(|e| failed(format!("running {:?}: {e}", program.as_ref())))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(failed(format!("{}: {}", output.status, stderr.trim_end())));
+        }
+        let doc = serde_json::from_slice(&output.stdout).map_err(|e| failed(e.to_string()))?;
+        Ok(Self { path, doc })
+    }
+}
+
+impl ConfSource for SopsSource {
+    /// Create a new [`SopsSource`] decrypting `{name}.enc.yaml` in the current directory.
+    ///
+    /// # Panics
+    ///
+    /// If the file can't be decrypted. Use [`SopsSource::from_path`] to handle the error.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_path(format!("{}.enc.yaml", name.into())).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Create a new [`SopsSource`] decrypting `{name}.enc.yaml`, failing if it can't be
+    /// decrypted.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        Self::from_path(format!("{}.enc.yaml", name.into()))
+    }
+
+    /// Look up a dotted key in the decrypted document.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(json_lookup(&self.doc, key))
+    }
+
+    /// List the dotted keys of every scalar and array in the decrypted document.
+    fn keys(&self) -> Result<Vec<String>> {
+        Ok(json_keys(&self.doc))
+    }
+
+    /// Name the key and the encrypted file.
+    fn describe(&self, key: &str) -> String {
+        format!("{key} in file {}", self.path.display())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use crate::Conf;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Write a fake `sops` script.
+    fn fake_sops(dir: &std::path::Path, script: &str) -> PathBuf {
+        let path = dir.join("sops");
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    pub fn get_sops_decrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let sops = fake_sops(
+            dir.path(),
+            r#"[ "$1 $2 $3 $4" = "--decrypt --output-type json secrets.enc.yaml" ] || exit 2
+echo '{"db": {"user": "xela", "password": "hunter2"}}'"#,
+        );
+        let source = SopsSource::from_program(sops, "secrets.enc.yaml").unwrap();
+        let conf = Conf::with_source("vcfg", source)
+            .string("db.user", None)
+            .secret("db.password", None)
+            .string("db.host", None);
+        assert_eq!(
+            conf.get_string("db.user").unwrap(),
+            Some("xela".to_string())
+        );
+        assert_eq!(
+            conf.require_secret("db.password").unwrap().expose(),
+            "hunter2"
+        );
+        assert_eq!(conf.get_string("db.host").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_sops_decrypt_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        let sops = fake_sops(
+            dir.path(),
+            "echo 'Failed to get the data key required to decrypt the SOPS file.' >&2\nexit 128",
+        );
+        let err = SopsSource::from_program(sops, "secrets.enc.yaml")
+            .err()
+            .unwrap();
+        assert!(matches!(
+            &err,
+            ConfError::DecryptFailed { path, reason }
+                if path == "secrets.enc.yaml" && reason.contains("Failed to get the data key")
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{ConfError, ConfSource, Result};
+use rusqlite::{Connection, OptionalExtension};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// A [`ConfSource`] for resolving values from a `config(key TEXT PRIMARY KEY, value TEXT)` table
+/// in a SQLite database, with [`SqliteSource::set`] to persist changes. Keys are stored as-is.
+///
+/// The database is opened once, at construction, in WAL mode so other processes can read while
+/// it's written. The table is created if it doesn't exist.
+pub struct SqliteSource {
+    /// Path of the database file. Created if missing.
+    pub path: PathBuf,
+    conn: Result<Mutex<Connection>>,
+}
+
+impl SqliteSource {
+    /// Create a new [`SqliteSource`] with an explicit database path.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let conn = Connection::open(&path)
+            .and_then(|conn| {
+                conn.pragma_update(None, "journal_mode", "WAL")?;
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS config (key TEXT PRIMARY KEY, value TEXT)",
+                    (),
+                )?;
+                Ok(Mutex::new(conn))
+            })
+            .map_err(|e| ConfError::file_load_failed(path.display().to_string(), e.to_string()));
+        Self { path, conn }
+    }
+
+    /// Insert or replace the value for a key.
+    pub fn set(&self, key: &str, value: &str) -> Result {
+        self.query(key, |conn| {
+            conn.execute(
+                "INSERT INTO config (key, value) VALUES (?1, ?2)
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+                (key, value),
+            )
+            .map(|_| ())
+        })
+    }
+
+    /// Run a query, wrapping errors with the key.
+    fn query<T>(&self, key: &str, f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T> {
+        let conn = self.conn.as_ref().map_err(Clone::clone)?;
+        let conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+        f(&conn).map_err(|e| {
+            ConfError::source_io(self.path.display().to_string(), format!("key {key}: {e}"))
+        })
+    }
+}
+
+impl ConfSource for SqliteSource {
+    /// Create a new [`SqliteSource`] using `{name}.db` in the current directory.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_path(format!("{}.db", name.into()))
+    }
+
+    /// Look up a key. A `NULL` value is treated as absent.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        self.query(key, |conn| {
+            conn.query_row("SELECT value FROM config WHERE key = ?1", [key], |row| {
+                row.get::<_, Option<String>>(0)
+            })
+            .optional()
+            .map(Option::flatten)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_sqlite_set() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vcfg.db");
+        let source = SqliteSource::from_path(&path);
+        source.set("port", "8080").unwrap();
+        source.set("port", "8081").unwrap();
+        source.set("theme", "dark").unwrap();
+        let conf = Conf::with_source("vcfg", SqliteSource::from_path(&path))
+            .uint("port", None)
+            .string("theme", None)
+            .string("missing", None);
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8081));
+        assert_eq!(conf.get_string("theme").unwrap(), Some("dark".to_string()));
+        assert_eq!(conf.get_string("missing").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_sqlite_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vcfg.db");
+        std::fs::write(&path, "not a database, just some text that is long enough").unwrap();
+        let source = SqliteSource::from_path(&path);
+        assert!(matches!(
+            source.get("port").unwrap_err(),
+            ConfError::FileLoadFailed { .. }
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+use super::aws::AwsClient;
+use crate::{ConfError, ConfSource, Result};
+use aws_credential_types::Credentials;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// A [`ConfSource`] for resolving values from AWS SSM Parameter Store. Keys map to the
+/// parameter `/{name}/{key}`, and `SecureString` parameters are decrypted.
+///
+/// By default every parameter under `/{name}/` is fetched with `GetParametersByPath` on the
+/// first lookup and cached, so later lookups make no requests. A failed fetch isn't cached, so
+/// the next lookup tries again. For very large namespaces,
+/// [`SsmSource::without_prefetch`] switches to one `GetParameter` call per lookup instead.
+/// Credentials come from `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY` or the ECS container
+/// endpoint, and the region from `AWS_REGION`.
+pub struct SsmSource {
+    /// Path prefix of the parameters, without the trailing slash.
+    pub path: String,
+    /// Whether to fetch every parameter under the path on first lookup.
+    pub prefetch: bool,
+    client: AwsClient,
+    params: RwLock<Option<BTreeMap<String, String>>>,
+}
+
+/// A parameter in an SSM response.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Parameter {
+    name: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetParameterResponse {
+    parameter: Parameter,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetParametersByPathResponse {
+    parameters: Vec<Parameter>,
+    next_token: Option<String>,
+}
+
+impl SsmSource {
+    /// Disable prefetching, making one `GetParameter` call per lookup.
+    pub fn without_prefetch(mut self) -> Self {
+        self.prefetch = false;
+        self
+    }
+
+    /// Update the AWS region.
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.client.region = region.into();
+        self
+    }
+
+    /// Override the SSM endpoint URL.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.client.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Set explicit credentials instead of resolving them from the environment.
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.client.credentials = Some(credentials);
+        self
+    }
+
+    /// Update the number of retries for throttled requests and the delay before the first
+    /// retry, which doubles for each subsequent retry.
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.client.retries = retries;
+        self.client.backoff = backoff;
+        self
+    }
+
+    /// Fetch every parameter under the path, following pagination.
+    fn fetch_all(&self) -> Result<BTreeMap<String, String>> {
+        let prefix = format!("{}/", self.path);
+        let mut params = BTreeMap::new();
+        let mut next_token = None;
+        loop {
+            let mut body = serde_json::json!({
+                "Path": self.path,
+                "WithDecryption": true,
+            });
+            if let Some(token) = next_token {
+                body["NextToken"] = token;
+            }
+            let Some(response) = self.client.call("GetParametersByPath", &body, &[])? else {
+                break;
+            };
+            let page: GetParametersByPathResponse =
+                serde_json::from_value(response).map_err(|e| {
+                    ConfError::request_failed(self.client.endpoint(), None, e.to_string())
+                })?;
+            params.extend(page.parameters.into_iter().filter_map(|p| {
+                p.name
+                    .strip_prefix(&prefix)
+                    .map(|key| (key.to_string(), p.value))
+            }));
+            match page.next_token {
+                Some(token) => next_token = Some(token.into()),
+                None => break,
+            }
+        }
+        Ok(params)
+    }
+
+    /// Fetch a single parameter.
+    fn fetch_one(&self, key: &str) -> Result<Option<String>> {
+        let body = serde_json::json!({
+            "Name": format!("{}/{}", self.path, key),
+            "WithDecryption": true,
+        });
+        let Some(response) = self
+            .client
+            .call("GetParameter", &body, &["ParameterNotFound"])?
+        else {
+            return Ok(None);
+        };
+        serde_json::from_value::<GetParameterResponse>(response)
+            .map(|r| Some(r.parameter.value))
+            .map_err(|e| ConfError::request_failed(self.client.endpoint(), None, e.to_string()))
+    }
+}
+
+impl ConfSource for SsmSource {
+    /// Create a new [`SsmSource`] reading parameters under `/{name}/`.
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            path: format!("/{}", name.into()),
+            prefetch: true,
+            client: AwsClient::new("ssm", "AmazonSSM"),
+            params: RwLock::new(None),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        if !self.prefetch {
+            return self.fetch_one(key);
+        }
+        if let Some(params) = &*self.params.read().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(params.get(key).cloned());
+        }
+        let mut params = self.params.write().unwrap_or_else(|e| e.into_inner());
+        if params.is_none() {
+            *params = Some(self.fetch_all()?);
+        }
+        Ok(params.as_ref().and_then(|params| params.get(key).cloned()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::source::mock::serve;
+    use crate::Conf;
+
+    fn ssm_source(endpoint: &str) -> SsmSource {
+        SsmSource::new("vcfg")
+            .with_region("eu-west-1")
+            .with_endpoint(endpoint)
+            .with_credentials(Credentials::new("AKID", "secret", None, None, "test"))
+            .with_retries(2, Duration::from_millis(1))
+    }
+
+    #[test]
+    pub fn get_ssm_prefetched() {
+        let (endpoint, requests) = serve(vec![
+            (
+                200,
+                r#"{"Parameters": [{"Name": "/vcfg/db_url", "Value": "postgres://db"}], "NextToken": "page2"}"#,
+            ),
+            (
+                200,
+                r#"{"Parameters": [{"Name": "/vcfg/port", "Value": "5432"}]}"#,
+            ),
+        ]);
+        let conf = Conf::with_source("vcfg", ssm_source(&endpoint))
+            .string("db_url", None)
+            .uint("port", None)
+            .string("user", None);
+        assert_eq!(
+            conf.get_string("db_url").unwrap(),
+            Some("postgres://db".to_string())
+        );
+        assert_eq!(conf.get_uint("port").unwrap(), Some(5432));
+        assert_eq!(conf.get_string("user").unwrap(), None);
+        let requests = requests.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        let first = requests[0].to_ascii_lowercase();
+        assert!(first.contains("x-amz-target: amazonssm.getparametersbypath"));
+        assert!(first.contains("authorization: aws4-hmac-sha256 credential=akid/"));
+        assert!(first.contains("/eu-west-1/ssm/aws4_request"));
+        assert!(requests[0].contains(r#""Path":"/vcfg""#));
+        assert!(requests[1].contains(r#""NextToken":"page2""#));
+    }
+
+    #[test]
+    pub fn get_ssm_throttled() {
+        let throttled = r#"{"__type": "ThrottlingException", "message": "Rate exceeded"}"#;
+        let (endpoint, requests) = serve(vec![
+            (400, throttled),
+            (
+                200,
+                r#"{"Parameter": {"Name": "/vcfg/db_url", "Value": "postgres://db"}}"#,
+            ),
+            (400, r#"{"__type": "ParameterNotFound"}"#),
+            (400, throttled),
+            (400, throttled),
+            (400, throttled),
+        ]);
+        let source = ssm_source(&endpoint).without_prefetch();
+        assert_eq!(
+            source.get("db_url").unwrap(),
+            Some("postgres://db".to_string())
+        );
+        assert_eq!(source.get("user").unwrap(), None);
+        assert_eq!(
+            source.get("port").unwrap_err(),
+            ConfError::RequestFailed {
+                url: endpoint,
+                status: Some(400),
+                reason: "ThrottlingException: Rate exceeded".to_string(),
+            }
+        );
+        let requests = requests.join().unwrap();
+        assert!(requests[1].contains(r#""Name":"/vcfg/db_url""#));
+        assert!(requests[1].contains(r#""WithDecryption":true"#));
+    }
+
+    #[test]
+    pub fn get_ssm_prefetch_retried() {
+        let (endpoint, requests) = serve(vec![
+            (500, r#"{"__type": "InternalServerError"}"#),
+            (
+                200,
+                r#"{"Parameters": [{"Name": "/vcfg/port", "Value": "5432"}]}"#,
+            ),
+        ]);
+        let source = ssm_source(&endpoint).with_retries(0, Duration::from_millis(1));
+        assert!(source.get("port").unwrap_err().is_transient());
+        assert_eq!(source.get("port").unwrap(), Some("5432".to_string()));
+        assert_eq!(source.get("user").unwrap(), None);
+        assert_eq!(requests.join().unwrap().len(), 2);
+    }
+}
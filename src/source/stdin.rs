@@ -0,0 +1,115 @@
+use super::json::{json_keys, json_lookup};
+use crate::{ConfError, ConfSource, Result};
+use std::io::{IsTerminal, Read};
+
+/// Path used in errors from [`StdinSource`].
+const STDIN_PATH: &str = "<stdin>";
+
+/// A [`ConfSource`] for resolving values from a JSON object piped to stdin, e.g. by a job
+/// runner. Stdin is read to the end once, on construction. Nested values are looked up with
+/// dotted keys like [`JsonSource`](crate::JsonSource).
+///
+/// ```no_run
+/// use voidconf::{Conf, StdinSource};
+///
+/// let conf = Conf::with_source("vcfg", StdinSource::read()?).uint("port", None);
+/// # Ok::<(), voidconf::ConfError>(())
+/// ```
+pub struct StdinSource {
+    doc: serde_json::Value,
+}
+
+impl StdinSource {
+    /// Read and parse stdin. Fails with [`ConfError::SourceIo`] if stdin is a terminal rather
+    /// than a pipe, instead of waiting for input, or [`ConfError::FileParseFailed`] if it isn't
+    /// a JSON object.
+    pub fn read() -> Result<Self> {
+        let stdin = std::io::stdin();
+        if stdin.is_terminal() {
+            return Err(ConfError::source_io(
+                STDIN_PATH,
+                "stdin is a terminal; expected a JSON object piped to stdin",
+            ));
+        }
+        Self::from_reader(stdin.lock())
+    }
+
+    /// Read and parse a JSON object from any reader. See [`StdinSource::read`].
+    pub fn from_reader(mut reader: impl Read) -> Result<Self> {
+        let mut contents = String::new();
+        reader
+            .read_to_string(&mut contents)
+            .map_err(|e| ConfError::source_io(STDIN_PATH, e.to_string()))?;
+        let doc: serde_json::Value = serde_json::from_str(&contents).map_err(|e| {
+            ConfError::file_parse_failed(STDIN_PATH, e.line(), e.column(), e.to_string())
+        })?;
+        if !doc.is_object() {
+            return Err(ConfError::file_parse_failed(
+                STDIN_PATH,
+                1,
+                1,
+                "expected a JSON object",
+            ));
+        }
+        Ok(Self { doc })
+    }
+}
+
+impl ConfSource for StdinSource {
+    /// Create a new [`StdinSource`] with [`StdinSource::read`]. The name is unused.
+    ///
+    /// # Panics
+    ///
+    /// If stdin can't be read or parsed. Use [`StdinSource::read`] to handle the error.
+    fn new(_name: impl Into<String>) -> Self {
+        Self::read().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Create a new [`StdinSource`] with [`StdinSource::read`].
+    fn try_new(_name: impl Into<String>) -> Result<Self> {
+        Self::read()
+    }
+
+    /// Look up a dotted key in the parsed object.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(json_lookup(&self.doc, key))
+    }
+
+    /// List the dotted keys of every scalar and array in the parsed object.
+    fn keys(&self) -> Result<Vec<String>> {
+        Ok(json_keys(&self.doc))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_stdin_reader() {
+        let input = br#"{"name": "xela", "port": 8080, "db": {"host": "localhost"}}"#;
+        let conf = Conf::with_source("vcfg", StdinSource::from_reader(&input[..]).unwrap())
+            .string("name", None)
+            .uint("port", None)
+            .string("db.host", None)
+            .string("missing", None);
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(
+            conf.get_string("db.host").unwrap(),
+            Some("localhost".to_string())
+        );
+        assert_eq!(conf.get_string("missing").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_stdin_malformed() {
+        for input in ["{\n  \"name\": \n", "[1, 2]"] {
+            assert!(matches!(
+                StdinSource::from_reader(input.as_bytes()).err().unwrap(),
+                ConfError::FileParseFailed { .. }
+            ));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{ConfSource, DirSource, Result};
+use std::path::{Path, PathBuf};
+
+/// A [`ConfSource`] for resolving values from systemd credentials, as delivered by
+/// `LoadCredential=` and `SetCredential=` under `$CREDENTIALS_DIRECTORY`. Each lookup reads the
+/// credential `{name}_{key}`, falling back to `{key}`, with a trailing newline trimmed.
+///
+/// When `$CREDENTIALS_DIRECTORY` is unset every lookup returns `None`, so the source can be
+/// layered under an [`EnvSource`](crate::EnvSource) and still work outside systemd.
+pub struct SystemdCredentialSource {
+    /// Credential name prefix.
+    pub name: String,
+    dir: Option<DirSource>,
+}
+
+impl SystemdCredentialSource {
+    /// Create a new [`SystemdCredentialSource`] reading from an explicit credentials directory.
+    pub fn from_dir(name: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            dir: Some(DirSource::from_dir(dir)),
+        }
+    }
+
+    /// The credentials directory, or `None` if `$CREDENTIALS_DIRECTORY` was unset.
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_ref().map(|dir| dir.dir.as_path())
+    }
+}
+
+impl ConfSource for SystemdCredentialSource {
+    /// Create a new [`SystemdCredentialSource`] reading from `$CREDENTIALS_DIRECTORY`.
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            dir: std::env::var_os("CREDENTIALS_DIRECTORY")
+                .filter(|dir| !dir.is_empty())
+                .map(DirSource::from_dir),
+        }
+    }
+
+    /// Read the credential `{name}_{key}`, or `{key}` if that doesn't exist.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let Some(dir) = &self.dir else {
+            return Ok(None);
+        };
+        match dir.get(&format!("{}_{}", self.name, key))? {
+            Some(v) => Ok(Some(v)),
+            None => dir.get(key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_systemd_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("vcfg_db_password"), "hunter2\n").unwrap();
+        std::fs::write(dir.path().join("db_password"), "shadowed\n").unwrap();
+        std::fs::write(dir.path().join("api_token"), "t0ken").unwrap();
+        let source = SystemdCredentialSource::from_dir("vcfg", dir.path());
+        let conf = Conf::with_source("vcfg", source)
+            .string("db_password", None)
+            .string("api_token", None)
+            .string("db_user", None);
+        assert_eq!(
+            conf.get_string("db_password").unwrap(),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(
+            conf.get_string("api_token").unwrap(),
+            Some("t0ken".to_string())
+        );
+        assert_eq!(conf.get_string("db_user").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_systemd_unset() {
+        let source = SystemdCredentialSource {
+            name: "vcfg".to_string(),
+            dir: None,
+        };
+        assert_eq!(source.dir(), None);
+        assert_eq!(source.get("db_password").unwrap(), None);
+    }
+}
@@ -0,0 +1,187 @@
+use super::line_col;
+use crate::{ConfError, ConfSource, Result};
+use std::path::PathBuf;
+
+/// A [`ConfSource`] for resolving values from a TOML file. Nested values are looked up with
+/// dotted keys, so `server.port` resolves `port` in the `[server]` table. A path that passes
+/// through a non-table value is a [`ConfError::KeyPathInvalid`].
+pub struct TomlSource {
+    /// Path of the TOML file. A missing file is treated as empty.
+    pub path: PathBuf,
+    table: Result<toml::Table>,
+}
+
+impl TomlSource {
+    /// Create a new [`TomlSource`] reading from an explicit path. The file is parsed once, here.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let table = match std::fs::read_to_string(&path) {
+            Ok(s) => s.parse().map_err(|e: toml::de::Error| {
+                let (line, column) = line_col(&s, e.span().map_or(0, |r| r.start));
+                ConfError::file_parse_failed(path.display().to_string(), line, column, e.message())
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(toml::Table::new()),
+            Err(e) => Err(ConfError::file_load_failed(
+                path.display().to_string(),
+                e.to_string(),
+            )),
+        };
+        Self { path, table }
+    }
+
+    /// Fail with the load error, if the file couldn't be read or parsed.
+    pub(crate) fn check(&self) -> Result {
+        self.table.as_ref().map(|_| ()).map_err(Clone::clone)
+    }
+}
+
+impl ConfSource for TomlSource {
+    /// Create a new [`TomlSource`] reading from `{name}.toml` in the current directory.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_path(format!("{}.toml", name.into()))
+    }
+
+    /// Create a new [`TomlSource`] like [`ConfSource::new`], failing if the file can't be read or
+    /// parsed.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        let source = Self::new(name);
+        source.check()?;
+        Ok(source)
+    }
+
+    /// Look up a dotted key. A top-level key containing dots is matched as-is first. Scalars
+    /// are returned in their string form, while arrays and tables are serialized as JSON so they
+    /// can be parsed into [`serde_json::Value`].
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let table = self.table.as_ref().map_err(Clone::clone)?;
+        let val = match table.get(key) {
+            Some(v) => Some(v),
+            None => toml_lookup(table, key)?,
+        };
+        Ok(val.map(value_string))
+    }
+
+    /// List the dotted keys of every value that isn't a table, sorted.
+    fn keys(&self) -> Result<Vec<String>> {
+        fn collect(keys: &mut Vec<String>, prefix: &str, table: &toml::Table) {
+            for (k, v) in table {
+                match v {
+                    toml::Value::Table(t) => collect(keys, &format!("{prefix}{k}."), t),
+                    _ => keys.push(format!("{prefix}{k}")),
+                }
+            }
+        }
+        let table = self.table.as_ref().map_err(Clone::clone)?;
+        let mut keys = Vec::new();
+        collect(&mut keys, "", table);
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Name the key and the file.
+    fn describe(&self, key: &str) -> String {
+        format!("{key} in file {}", self.path.display())
+    }
+}
+
+/// Convert a value to its string form: strings as-is, arrays and tables as JSON, and other
+/// scalars as TOML.
+pub(crate) fn value_string(v: &toml::Value) -> String {
+    match v {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Array(_) | toml::Value::Table(_) => {
+            serde_json::to_string(v).unwrap_or_else(|_| v.to_string())
+        }
+        _ => v.to_string(),
+    }
+}
+
+/// Resolve a dotted key through nested tables.
+fn toml_lookup<'a>(table: &'a toml::Table, key: &str) -> Result<Option<&'a toml::Value>> {
+    let mut segments = key.split('.');
+    let mut val = match segments.next().and_then(|k| table.get(k)) {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    let mut at = key.split('.').next().unwrap_or_default().len();
+    for segment in segments {
+        val = match val {
+            toml::Value::Table(t) => match t.get(segment) {
+                Some(v) => v,
+                None => return Ok(None),
+            },
+            _ => return Err(ConfError::key_path_invalid(key, &key[..at])),
+        };
+        at += segment.len() + 1;
+    }
+    Ok(Some(val))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+    use std::io::Write;
+
+    fn toml_file(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    pub fn get_toml_scalars() {
+        let file = toml_file("name = \"xela\"\nport = 8080\ntags = [\"a\", \"b\"]\n");
+        let conf = Conf::with_source("vcfg", TomlSource::from_path(file.path()))
+            .string("name", None)
+            .uint("port", None)
+            .entry(crate::ConfEntry::<serde_json::Value>::new("tags"))
+            .string("missing", Some("default"));
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(
+            conf.require::<serde_json::Value>("tags").unwrap(),
+            serde_json::json!(["a", "b"])
+        );
+        assert_eq!(
+            conf.get_string("missing").unwrap(),
+            Some("default".to_string())
+        );
+    }
+
+    #[test]
+    pub fn get_toml_missing_file() {
+        let source = TomlSource::from_path("does/not/exist.toml");
+        assert_eq!(source.get("name").unwrap(), None);
+    }
+
+    #[test]
+    pub fn get_toml_invalid_file() {
+        let file = toml_file("port = 1\nname = ");
+        let source = TomlSource::from_path(file.path());
+        assert!(matches!(
+            source.get("name").unwrap_err(),
+            ConfError::FileParseFailed { line: 2, .. }
+        ));
+    }
+
+    #[test]
+    pub fn get_toml_dotted() {
+        let file = toml_file("[server]\nport = 8080\n\n[server.tls]\nenabled = true\n");
+        let conf = Conf::with_source("vcfg", TomlSource::from_path(file.path()))
+            .uint("server.port", None)
+            .boolean("server.tls.enabled", None)
+            .string("server.host", None)
+            .string("server.port.number", None);
+        assert_eq!(conf.get_uint("server.port").unwrap(), Some(8080));
+        assert_eq!(conf.get_bool("server.tls.enabled").unwrap(), Some(true));
+        assert_eq!(conf.get_string("server.host").unwrap(), None);
+        assert_eq!(
+            conf.get_string("server.port.number").unwrap_err(),
+            ConfError::KeyPathInvalid {
+                key: "server.port.number".to_string(),
+                at: "server.port".to_string()
+            }
+        );
+    }
+}
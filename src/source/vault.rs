@@ -0,0 +1,225 @@
+use crate::{ConfError, ConfSource, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Default Vault address for [`VaultSource`] when `VAULT_ADDR` is unset.
+pub const DEFAULT_VAULT_ADDR: &str = "http://127.0.0.1:8200";
+
+/// A [`ConfSource`] for resolving values from a secret in Vault's KV v2 engine. The secret is
+/// fetched on the first lookup and its data cached until [`VaultSource::refresh`]. A failed fetch
+/// isn't cached, so the next lookup tries again. A missing secret resolves every key to `None`,
+/// while a rejected token is a [`ConfError::AuthFailed`].
+pub struct VaultSource {
+    /// Vault address.
+    pub addr: String,
+    /// KV v2 mount.
+    pub mount: String,
+    /// Secret path within the mount.
+    pub path: String,
+    token: Option<String>,
+    data: RwLock<Option<BTreeMap<String, serde_json::Value>>>,
+}
+
+/// KV v2 read response.
+#[derive(Deserialize)]
+struct SecretResponse {
+    data: SecretData,
+}
+
+#[derive(Deserialize)]
+struct SecretData {
+    data: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+/// Vault error response.
+#[derive(Deserialize)]
+struct ErrorResponse {
+    errors: Vec<String>,
+}
+
+impl VaultSource {
+    /// Update the Vault address.
+    pub fn with_addr(mut self, addr: impl Into<String>) -> Self {
+        self.addr = addr.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Update the KV v2 mount.
+    pub fn with_mount(mut self, mount: impl Into<String>) -> Self {
+        self.mount = mount.into();
+        self
+    }
+
+    /// Update the secret path within the mount.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set the token, overriding `VAULT_TOKEN`.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// KV v2 read URL for the secret.
+    pub fn url(&self) -> String {
+        format!("{}/v1/{}/data/{}", self.addr, self.mount, self.path)
+    }
+
+    /// Fetch the secret again, replacing the cached data. The old data is kept if the fetch
+    /// fails.
+    pub fn refresh(&self) -> Result {
+        let data = self.fetch()?;
+        *self.data.write().unwrap_or_else(|e| e.into_inner()) = Some(data);
+        Ok(())
+    }
+
+    /// Fetch the secret data. A 404 is an empty map.
+    fn fetch(&self) -> Result<BTreeMap<String, serde_json::Value>> {
+        let url = self.url();
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(10))
+            .build();
+        let mut request = agent.get(&url);
+        if let Some(token) = &self.token {
+            request = request.set("X-Vault-Token", token);
+        }
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(BTreeMap::new()),
+            Err(ureq::Error::Status(status @ (401 | 403), response)) => {
+                let reason = response
+                    .into_string()
+                    .ok()
+                    .and_then(|body| serde_json::from_str::<ErrorResponse>(&body).ok())
+                    .map(|e| e.errors.join(", "))
+                    .filter(|reason| !reason.is_empty())
+                    .unwrap_or_else(|| format!("status code {status}"));
+                return Err(ConfError::auth_failed(url, reason));
+            }
+            Err(e @ ureq::Error::Status(status, _)) => {
+                return Err(ConfError::request_failed(url, Some(status), e.to_string()))
+            }
+            Err(e) => return Err(ConfError::request_failed(url, None, e.to_string())),
+        };
+        let status = Some(response.status());
+        response
+            .into_string()
+            .map_err(|e| e.to_string())
+            .and_then(|body| {
+                serde_json::from_str::<SecretResponse>(&body).map_err(|e| e.to_string())
+            })
+            .map(|secret| secret.data.data.unwrap_or_default())
+            .map_err(|reason| ConfError::request_failed(url, status, reason))
+    }
+}
+
+impl ConfSource for VaultSource {
+    /// Create a new [`VaultSource`] reading the secret `secret/data/{name}`. The address and
+    /// token are read from `VAULT_ADDR` and `VAULT_TOKEN`.
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            addr: std::env::var("VAULT_ADDR")
+                .map(|addr| addr.trim_end_matches('/').to_string())
+                .unwrap_or_else(|_| DEFAULT_VAULT_ADDR.to_string()),
+            mount: "secret".to_string(),
+            path: name.into(),
+            token: std::env::var("VAULT_TOKEN").ok(),
+            data: RwLock::new(None),
+        }
+    }
+
+    /// Look up a key in the cached secret data. Non-string values are returned as JSON.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let lookup = |data: &BTreeMap<String, serde_json::Value>| {
+            data.get(key).map(|v| match v {
+                serde_json::Value::String(s) => s.clone(),
+                _ => v.to_string(),
+            })
+        };
+        if let Some(data) = &*self.data.read().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(lookup(data));
+        }
+        let mut data = self.data.write().unwrap_or_else(|e| e.into_inner());
+        if data.is_none() {
+            *data = Some(self.fetch()?);
+        }
+        Ok(data.as_ref().and_then(lookup))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::source::mock::serve;
+    use crate::Conf;
+
+    #[test]
+    pub fn get_vault_cached() {
+        let (addr, requests) = serve(vec![(
+            200,
+            r#"{"data": {"data": {"password": "hunter2", "port": 5432}, "metadata": {}}}"#,
+        )]);
+        let source = VaultSource::new("vcfg")
+            .with_addr(addr)
+            .with_token("s.token");
+        let conf = Conf::with_source("vcfg", source)
+            .string("password", None)
+            .uint("port", None)
+            .string("user", None);
+        assert_eq!(
+            conf.get_string("password").unwrap(),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(conf.get_uint("port").unwrap(), Some(5432));
+        assert_eq!(conf.get_string("user").unwrap(), None);
+        let requests = requests.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /v1/secret/data/vcfg "));
+        assert!(requests[0]
+            .to_ascii_lowercase()
+            .contains("x-vault-token: s.token"));
+    }
+
+    #[test]
+    pub fn get_vault_missing_or_denied() {
+        let (addr, _) = serve(vec![(404, r#"{"errors": []}"#)]);
+        let source = VaultSource::new("vcfg").with_addr(addr);
+        assert_eq!(source.get("password").unwrap(), None);
+
+        let (addr, _) = serve(vec![(403, r#"{"errors": ["permission denied"]}"#)]);
+        let source = VaultSource::new("vcfg").with_addr(&addr);
+        assert_eq!(
+            source.get("password").unwrap_err(),
+            ConfError::AuthFailed {
+                url: format!("{addr}/v1/secret/data/vcfg"),
+                reason: "permission denied".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    pub fn get_vault_retry_after_error() {
+        let (addr, requests) = serve(vec![
+            (503, r#"{"errors": ["Vault is sealed"]}"#),
+            (200, r#"{"data": {"data": {"password": "hunter2"}}}"#),
+            (200, r#"{"data": {"data": {"password": "hunter3"}}}"#),
+        ]);
+        let source = VaultSource::new("vcfg").with_addr(addr);
+        assert!(matches!(
+            source.get("password").unwrap_err(),
+            ConfError::RequestFailed {
+                status: Some(503),
+                ..
+            }
+        ));
+        assert_eq!(source.get("password").unwrap(), Some("hunter2".to_string()));
+        assert_eq!(source.get("password").unwrap(), Some("hunter2".to_string()));
+        source.refresh().unwrap();
+        assert_eq!(source.get("password").unwrap(), Some("hunter3".to_string()));
+        assert_eq!(requests.join().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{ConfSource, Result, TomlSource};
+use std::path::{Path, PathBuf};
+
+/// A [`ConfSource`] for resolving values from a `config.toml` discovered in the user's config
+/// directory. The first existing file of the [candidates](XdgSource::candidates) is loaded as a
+/// [`TomlSource`]; if none exists, every lookup returns `None`.
+pub struct XdgSource {
+    toml: Option<TomlSource>,
+}
+
+impl XdgSource {
+    /// Create a new [`XdgSource`] loading the first existing file of the given paths.
+    pub fn from_paths(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            toml: paths
+                .into_iter()
+                .find(|path| path.is_file())
+                .map(TomlSource::from_path),
+        }
+    }
+
+    /// Paths searched for the config named `name`, in order:
+    ///
+    /// 1. `$XDG_CONFIG_HOME/{name}/config.toml`
+    /// 2. `~/.config/{name}/config.toml`
+    /// 3. The platform config directory, e.g. `~/Library/Application Support/{name}/config.toml`
+    ///    on macOS or `%APPDATA%\{name}\config.toml` on Windows.
+    pub fn candidates(name: &str) -> Vec<PathBuf> {
+        let xdg = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+        let home = crate::value::home_dir()
+            .ok()
+            .map(|home| home.join(".config"));
+        let mut paths = Vec::new();
+        for dir in [xdg, home, dirs::config_dir()].into_iter().flatten() {
+            let path = dir.join(name).join("config.toml");
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Path of the loaded config file, or `None` if no candidate exists.
+    pub fn path(&self) -> Option<&Path> {
+        self.toml.as_ref().map(|toml| toml.path.as_path())
+    }
+}
+
+impl ConfSource for XdgSource {
+    /// Create a new [`XdgSource`] searching the [candidates](XdgSource::candidates) for `name`.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_paths(Self::candidates(&name.into()))
+    }
+
+    /// Create a new [`XdgSource`] like [`ConfSource::new`], failing if the file found can't be
+    /// read or parsed.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        let source = Self::new(name);
+        if let Some(toml) = &source.toml {
+            toml.check()?;
+        }
+        Ok(source)
+    }
+
+    /// Look up a key in the loaded file, the same way as [`TomlSource`].
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        match &self.toml {
+            Some(toml) => toml.get(key),
+            None => Ok(None),
+        }
+    }
+
+    /// List the keys in the loaded file, if any.
+    fn keys(&self) -> Result<Vec<String>> {
+        match &self.toml {
+            Some(toml) => toml.keys(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Name the key and the loaded file, if any.
+    fn describe(&self, key: &str) -> String {
+        match &self.toml {
+            Some(toml) => toml.describe(key),
+            None => format!("key {key}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Conf, ConfError};
+
+    #[test]
+    pub fn get_xdg_first_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("xdg/vcfg/config.toml");
+        let found = dir.path().join("home/vcfg/config.toml");
+        std::fs::create_dir_all(found.parent().unwrap()).unwrap();
+        std::fs::write(&found, "name = \"xela\"\n").unwrap();
+        let source = XdgSource::from_paths([missing, found.clone()]);
+        assert_eq!(source.path(), Some(found.as_path()));
+        let conf = Conf::with_source("vcfg", source).string("name", None);
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+    }
+
+    #[test]
+    pub fn get_xdg_none_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = XdgSource::from_paths([dir.path().join("vcfg/config.toml")]);
+        assert_eq!(source.path(), None);
+        assert_eq!(source.get("name").unwrap(), None);
+    }
+
+    #[test]
+    pub fn try_new_xdg_malformed() {
+        let dir = tempfile::tempdir().unwrap();
+        let name: &'static str = dir.path().to_str().unwrap().to_string().leak();
+        assert!(Conf::<XdgSource>::try_new(name).is_ok());
+        std::fs::write(dir.path().join("config.toml"), "name = ").unwrap();
+        let err = Conf::<XdgSource>::try_new(name).err().unwrap();
+        assert!(matches!(
+            &err,
+            ConfError::SourceInit { source, .. }
+                if matches!(**source, ConfError::FileParseFailed { line: 1, .. })
+        ));
+    }
+}
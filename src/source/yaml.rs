@@ -0,0 +1,115 @@
+use super::json::{json_keys, json_lookup};
+use crate::{ConfError, ConfSource, Result};
+use std::path::PathBuf;
+
+/// A [`ConfSource`] for resolving values from a YAML file. Nested values are looked up with
+/// dotted keys like [`JsonSource`](crate::JsonSource), and sequences are returned as JSON
+/// arrays. Anchors, aliases and `<<` merge keys are resolved on load.
+pub struct YamlSource {
+    /// Path of the YAML file. A missing file is treated as empty.
+    pub path: PathBuf,
+    doc: Result<serde_json::Value>,
+}
+
+impl YamlSource {
+    /// Create a new [`YamlSource`] reading from an explicit path. The file is parsed once, here.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let doc = match std::fs::read_to_string(&path) {
+            Ok(s) => parse_yaml(&path.display().to_string(), &s),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(serde_json::Value::Null),
+            Err(e) => Err(ConfError::file_load_failed(
+                path.display().to_string(),
+                e.to_string(),
+            )),
+        };
+        Self { path, doc }
+    }
+}
+
+impl ConfSource for YamlSource {
+    /// Create a new [`YamlSource`] reading from `{name}.yaml` in the current directory.
+    fn new(name: impl Into<String>) -> Self {
+        Self::from_path(format!("{}.yaml", name.into()))
+    }
+
+    /// Create a new [`YamlSource`] like [`ConfSource::new`], failing if the file can't be read or
+    /// parsed.
+    fn try_new(name: impl Into<String>) -> Result<Self> {
+        let source = Self::new(name);
+        source.doc.as_ref().map_err(Clone::clone)?;
+        Ok(source)
+    }
+
+    /// Look up a dotted key. Scalars are returned in their string form, while sequences and
+    /// mappings are serialized as JSON so they can be parsed into [`serde_json::Value`].
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let doc = self.doc.as_ref().map_err(Clone::clone)?;
+        Ok(json_lookup(doc, key))
+    }
+
+    /// List the dotted keys of every scalar and sequence.
+    fn keys(&self) -> Result<Vec<String>> {
+        let doc = self.doc.as_ref().map_err(Clone::clone)?;
+        Ok(json_keys(doc))
+    }
+
+    /// Name the key and the file.
+    fn describe(&self, key: &str) -> String {
+        format!("{key} in file {}", self.path.display())
+    }
+}
+
+/// Parse a YAML document, resolving merge keys, and convert it to JSON for lookups.
+fn parse_yaml(path: &str, contents: &str) -> Result<serde_json::Value> {
+    let parse_failed = |e: serde_yaml::Error| {
+        let (line, column) = e.location().map_or((0, 0), |l| (l.line(), l.column()));
+        ConfError::file_parse_failed(path, line, column, e.to_string())
+    };
+    let mut doc: serde_yaml::Value = serde_yaml::from_str(contents).map_err(parse_failed)?;
+    doc.apply_merge().map_err(parse_failed)?;
+    serde_json::to_value(doc).map_err(|e| ConfError::file_load_failed(path, e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Conf, ConfEntry};
+    use std::io::Write;
+
+    fn yaml_file(contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    pub fn get_yaml_nested() {
+        let file = yaml_file(
+            "defaults: &defaults\n  host: localhost\n  port: 5432\ndb:\n  <<: *defaults\n  port: 6543\nhosts:\n  - a\n  - b\n",
+        );
+        let conf = Conf::with_source("vcfg", YamlSource::from_path(file.path()))
+            .string("db.host", None)
+            .uint("db.port", None)
+            .entry(ConfEntry::<serde_json::Value>::new("hosts"));
+        assert_eq!(
+            conf.get_string("db.host").unwrap(),
+            Some("localhost".to_string())
+        );
+        assert_eq!(conf.get_uint("db.port").unwrap(), Some(6543));
+        assert_eq!(
+            conf.require::<serde_json::Value>("hosts").unwrap(),
+            serde_json::json!(["a", "b"])
+        );
+    }
+
+    #[test]
+    pub fn get_yaml_parse_failed() {
+        let file = yaml_file("name: xela\n  port: [\n");
+        let source = YamlSource::from_path(file.path());
+        assert!(matches!(
+            source.get("name").unwrap_err(),
+            ConfError::FileParseFailed { line: 2, .. }
+        ));
+    }
+}
@@ -0,0 +1,300 @@
+use crate::{ConfError, ConfSource, Result};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default session timeout for [`ZkSource`] connections.
+pub const DEFAULT_ZK_SESSION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `getData` operation code.
+const OP_GET_DATA: i32 = 4;
+/// `NoNode` error code.
+const ERR_NO_NODE: i32 = -101;
+
+/// A [`ConfSource`] for resolving values from ZooKeeper: `get(key)` reads the data of the znode
+/// `/{name}/{key}` as UTF-8, and a missing znode is `None`.
+///
+/// The connection is opened on the first lookup and reused for later ones. It is dropped after
+/// an error or once the session may have expired, and the next lookup reconnects. Connection or
+/// session loss is a [`ConfError::SourceIo`] naming the znode.
+pub struct ZkSource {
+    /// Ensemble servers as `host:port`, tried in order when connecting.
+    pub servers: Vec<String>,
+    /// Parent znode of the keys, `/{name}` by default.
+    pub root: String,
+    /// Requested session timeout, also used as the socket timeout.
+    pub session_timeout: Duration,
+    conn: Mutex<Option<Connection>>,
+}
+
+/// An open session.
+struct Connection {
+    stream: TcpStream,
+    xid: i32,
+    /// Session timeout negotiated with the server.
+    timeout: Duration,
+    last_used: Instant,
+}
+
+impl ZkSource {
+    /// Create a new [`ZkSource`] reading `/{name}` from the given ensemble servers.
+    pub fn from_servers(
+        name: impl Into<String>,
+        servers: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            servers: servers.into_iter().map(Into::into).collect(),
+            root: format!("/{}", name.into()),
+            session_timeout: DEFAULT_ZK_SESSION_TIMEOUT,
+            conn: Mutex::new(None),
+        }
+    }
+
+    /// Update the parent znode of the keys.
+    pub fn with_root(mut self, root: impl Into<String>) -> Self {
+        self.root = root.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Update the requested session timeout.
+    pub fn with_session_timeout(mut self, timeout: Duration) -> Self {
+        self.session_timeout = timeout;
+        self
+    }
+
+    /// Path of the znode for a key.
+    pub fn path(&self, key: &str) -> String {
+        format!("{}/{key}", self.root)
+    }
+
+    /// Connect to the first reachable server and open a session.
+    fn connect(&self) -> std::io::Result<Connection> {
+        let mut last_err = std::io::Error::new(std::io::ErrorKind::NotFound, "no servers given");
+        for server in &self.servers {
+            match self.handshake(server) {
+                Ok(conn) => return Ok(conn),
+                Err(e) => last_err = e,
+            }
+        }
+        Err(last_err)
+    }
+
+    /// Open a session on one server.
+    fn handshake(&self, server: &str) -> std::io::Result<Connection> {
+        let mut stream = TcpStream::connect(server)?;
+        stream.set_read_timeout(Some(self.session_timeout))?;
+        stream.set_write_timeout(Some(self.session_timeout))?;
+        let mut req = Vec::new();
+        req.extend(0i32.to_be_bytes()); // protocol version
+        req.extend(0i64.to_be_bytes()); // last zxid seen
+        req.extend((self.session_timeout.as_millis() as i32).to_be_bytes());
+        req.extend(0i64.to_be_bytes()); // session id
+        req.extend(16i32.to_be_bytes()); // password
+        req.extend([0; 16]);
+        req.push(0); // read only
+        write_frame(&mut stream, &req)?;
+        let resp = read_frame(&mut stream)?;
+        let timeout = resp
+            .get(4..8)
+            .map(|b| i32::from_be_bytes(b.try_into().unwrap()))
+            .ok_or_else(|| invalid_data("short connect response"))?;
+        if timeout <= 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionRefused,
+                "session expired",
+            ));
+        }
+        Ok(Connection {
+            stream,
+            xid: 0,
+            timeout: Duration::from_millis(timeout as u64),
+            last_used: Instant::now(),
+        })
+    }
+
+    /// Read a znode's data. `Ok(None)` if it doesn't exist.
+    fn get_data(conn: &mut Connection, path: &str) -> std::io::Result<Option<Vec<u8>>> {
+        conn.xid += 1;
+        let mut req = Vec::new();
+        req.extend(conn.xid.to_be_bytes());
+        req.extend(OP_GET_DATA.to_be_bytes());
+        req.extend((path.len() as i32).to_be_bytes());
+        req.extend(path.as_bytes());
+        req.push(0); // watch
+        write_frame(&mut conn.stream, &req)?;
+        loop {
+            let resp = read_frame(&mut conn.stream)?;
+            let mut reader = resp.as_slice();
+            let xid = read_i32(&mut reader)?;
+            let _zxid = read_i64(&mut reader)?;
+            let err = read_i32(&mut reader)?;
+            if xid != conn.xid {
+                // watch events and pings
+                continue;
+            }
+            conn.last_used = Instant::now();
+            return match err {
+                0 => match read_i32(&mut reader)? {
+                    -1 => Ok(Some(Vec::new())),
+                    len => reader
+                        .get(..len as usize)
+                        .map(|data| Some(data.to_vec()))
+                        .ok_or_else(|| invalid_data("short getData response")),
+                },
+                ERR_NO_NODE => Ok(None),
+                err => Err(std::io::Error::other(format!("ZooKeeper error code {err}"))),
+            };
+        }
+    }
+}
+
+impl ConfSource for ZkSource {
+    /// Create a new [`ZkSource`] reading `/{name}` from the comma-separated servers in the
+    /// `ZOOKEEPER_SERVERS` env var, or `localhost:2181` if unset.
+    fn new(name: impl Into<String>) -> Self {
+        let servers =
+            std::env::var("ZOOKEEPER_SERVERS").unwrap_or_else(|_| "localhost:2181".to_string());
+        Self::from_servers(name, servers.split(',').map(str::trim))
+    }
+
+    /// Read the data of the key's znode.
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let path = self.path(key);
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        if conn
+            .as_ref()
+            .is_some_and(|c| c.last_used.elapsed() >= c.timeout * 2 / 3)
+        {
+            *conn = None;
+        }
+        let result = match &mut *conn {
+            Some(c) => Self::get_data(c, &path),
+            None => self.connect().and_then(|c| {
+                let c = conn.insert(c);
+                Self::get_data(c, &path)
+            }),
+        };
+        let data = result.map_err(|e| {
+            *conn = None;
+            ConfError::source_io(&path, e.to_string())
+        })?;
+        data.map(|data| String::from_utf8(data).map_err(|_| ConfError::val_not_utf8(key)))
+            .transpose()
+    }
+}
+
+/// Write a length-prefixed packet.
+fn write_frame(stream: &mut TcpStream, data: &[u8]) -> std::io::Result<()> {
+    let mut frame = (data.len() as i32).to_be_bytes().to_vec();
+    frame.extend(data);
+    stream.write_all(&frame)
+}
+
+/// Read a length-prefixed packet.
+fn read_frame(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut len = [0; 4];
+    stream.read_exact(&mut len)?;
+    let mut data = vec![0; i32::from_be_bytes(len).max(0) as usize];
+    stream.read_exact(&mut data)?;
+    Ok(data)
+}
+
+fn read_i32(reader: &mut &[u8]) -> std::io::Result<i32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(i32::from_be_bytes(buf))
+}
+
+fn read_i64(reader: &mut &[u8]) -> std::io::Result<i64> {
+    let mut buf = [0; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(i64::from_be_bytes(buf))
+}
+
+fn invalid_data(reason: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, reason)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Conf;
+    use std::collections::BTreeMap;
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    /// Serve znodes to one connection, then return the paths read.
+    fn serve_znodes(znodes: &[(&str, &str)]) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let znodes: BTreeMap<String, String> = znodes
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_frame(&mut stream).unwrap();
+            let mut resp = Vec::new();
+            resp.extend(0i32.to_be_bytes());
+            resp.extend(30000i32.to_be_bytes());
+            resp.extend(1i64.to_be_bytes());
+            resp.extend(16i32.to_be_bytes());
+            resp.extend([0; 16]);
+            write_frame(&mut stream, &resp).unwrap();
+            let mut paths = Vec::new();
+            while let Ok(req) = read_frame(&mut stream) {
+                let mut reader = req.as_slice();
+                let xid = read_i32(&mut reader).unwrap();
+                assert_eq!(read_i32(&mut reader).unwrap(), OP_GET_DATA);
+                let len = read_i32(&mut reader).unwrap() as usize;
+                let path = String::from_utf8(reader[..len].to_vec()).unwrap();
+                let mut resp = Vec::new();
+                resp.extend(xid.to_be_bytes());
+                resp.extend(0i64.to_be_bytes());
+                match znodes.get(&path) {
+                    Some(data) => {
+                        resp.extend(0i32.to_be_bytes());
+                        resp.extend((data.len() as i32).to_be_bytes());
+                        resp.extend(data.as_bytes());
+                        resp.extend([0; 68]);
+                    }
+                    None => resp.extend(ERR_NO_NODE.to_be_bytes()),
+                }
+                write_frame(&mut stream, &resp).unwrap();
+                paths.push(path);
+            }
+            paths
+        });
+        (addr, handle)
+    }
+
+    #[test]
+    pub fn get_zk_reuses_connection() {
+        let (addr, paths) = serve_znodes(&[("/vcfg/name", "xela"), ("/vcfg/port", "8080")]);
+        let conf = Conf::with_source(
+            "vcfg",
+            ZkSource::from_servers("vcfg", ["127.0.0.1:1", &addr]),
+        )
+        .string("name", None)
+        .uint("port", None)
+        .string("missing", None);
+        assert_eq!(conf.get_string("name").unwrap(), Some("xela".to_string()));
+        assert_eq!(conf.get_uint("port").unwrap(), Some(8080));
+        assert_eq!(conf.get_string("missing").unwrap(), None);
+        drop(conf);
+        assert_eq!(
+            paths.join().unwrap(),
+            ["/vcfg/name", "/vcfg/port", "/vcfg/missing"]
+        );
+    }
+
+    #[test]
+    pub fn get_zk_connection_loss() {
+        let source = ZkSource::from_servers("vcfg", ["127.0.0.1:1"]);
+        assert!(matches!(
+            source.get("name").unwrap_err(),
+            ConfError::SourceIo { path, .. } if path == "/vcfg/name"
+        ));
+    }
+}
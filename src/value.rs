@@ -0,0 +1,387 @@
+//! Wrapper types implementing [`ConfValue`](crate::ConfValue) where the inner type's own parsing
+//! doesn't suit config values.
+use derive_more::{Display, Error, From};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::path::{Component, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A lenient `bool`. Parses `true`/`false`, `yes`/`no`, `on`/`off` and `1`/`0` case-insensitively,
+/// and displays as `true`/`false`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Display, From, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ConfBool(pub bool);
+
+impl From<ConfBool> for bool {
+    fn from(val: ConfBool) -> Self {
+        val.0
+    }
+}
+
+/// Error parsing a [`ConfBool`].
+#[derive(Clone, Debug, Display, Error, PartialEq, Eq)]
+#[display("invalid bool: {val}")]
+pub struct ParseConfBoolError {
+    pub val: String,
+}
+
+impl FromStr for ConfBool {
+    type Err = ParseConfBoolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(Self(true)),
+            "false" | "no" | "off" | "0" => Ok(Self(false)),
+            _ => Err(ParseConfBoolError { val: s.into() }),
+        }
+    }
+}
+
+/// A finite `f64`. Parsing rejects `NaN` and infinities, which plain `f64` parsing accepts.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Display, PartialEq, PartialOrd)]
+#[serde(transparent)]
+pub struct FiniteFloat(f64);
+
+impl From<FiniteFloat> for f64 {
+    fn from(val: FiniteFloat) -> Self {
+        val.0
+    }
+}
+
+/// Error parsing a [`FiniteFloat`].
+#[derive(Clone, Debug, Display, Error, PartialEq, Eq)]
+pub enum ParseFiniteFloatError {
+    /// Not a float at all.
+    #[display("invalid float: {_0}")]
+    Invalid(std::num::ParseFloatError),
+    /// A float, but `NaN` or infinite.
+    #[display("float is not finite: {val}")]
+    NotFinite { val: String },
+}
+
+impl FromStr for FiniteFloat {
+    type Err = ParseFiniteFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<f64>() {
+            Ok(f) if f.is_finite() => Ok(Self(f)),
+            Ok(_) => Err(ParseFiniteFloatError::NotFinite { val: s.into() }),
+            Err(e) => Err(ParseFiniteFloatError::Invalid(e)),
+        }
+    }
+}
+
+/// A list of values separated by the delimiter `D`, which defaults to `,`. Whitespace around
+/// each element is trimmed. An empty string parses as an empty list, so a present-but-empty
+/// value is distinct from an absent one.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ConfList<V, const D: char = ','>(pub Vec<V>);
+
+impl<V, const D: char> From<ConfList<V, D>> for Vec<V> {
+    fn from(val: ConfList<V, D>) -> Self {
+        val.0
+    }
+}
+
+/// Error parsing an element of a [`ConfList`].
+#[derive(Clone, Debug, Display, Error, PartialEq, Eq)]
+#[display("invalid list element `{element}`: {reason}")]
+pub struct ParseConfListError {
+    pub element: String,
+    pub reason: String,
+}
+
+impl<V: FromStr<Err: std::fmt::Display>, const D: char> FromStr for ConfList<V, D> {
+    type Err = ParseConfListError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Self(Vec::new()));
+        }
+        s.split(D)
+            .map(str::trim)
+            .map(|element| {
+                element.parse().map_err(|e: V::Err| ParseConfListError {
+                    element: element.into(),
+                    reason: e.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl<V: std::fmt::Display, const D: char> std::fmt::Display for ConfList<V, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, v) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{D}")?;
+            }
+            write!(f, "{v}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A map of `key=value` entries separated by the delimiter `D`, which defaults to `,`, with keys
+/// and values separated by `S`, which defaults to `=`. Whitespace around keys and values is
+/// trimmed, and a later entry for a key replaces an earlier one. An empty string parses as an
+/// empty map. Entries are displayed in no particular order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ConfMap<K: Eq + Hash, V, const D: char = ',', const S: char = '='>(pub HashMap<K, V>);
+
+impl<K: Eq + Hash, V, const D: char, const S: char> From<ConfMap<K, V, D, S>> for HashMap<K, V> {
+    fn from(val: ConfMap<K, V, D, S>) -> Self {
+        val.0
+    }
+}
+
+/// Error parsing an entry of a [`ConfMap`].
+#[derive(Clone, Debug, Display, Error, PartialEq, Eq)]
+#[display("invalid map entry `{entry}`: {reason}")]
+pub struct ParseConfMapError {
+    pub entry: String,
+    pub reason: String,
+}
+
+impl<K, V, const D: char, const S: char> FromStr for ConfMap<K, V, D, S>
+where
+    K: Eq + Hash + FromStr<Err: std::fmt::Display>,
+    V: FromStr<Err: std::fmt::Display>,
+{
+    type Err = ParseConfMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Self(HashMap::new()));
+        }
+        s.split(D)
+            .map(str::trim)
+            .map(|entry| {
+                let err = |reason: String| ParseConfMapError {
+                    entry: entry.into(),
+                    reason,
+                };
+                let (k, v) = entry
+                    .split_once(S)
+                    .ok_or_else(|| err(format!("expected `{S}`")))?;
+                let k = k
+                    .trim()
+                    .parse()
+                    .map_err(|e: K::Err| err(format!("key: {e}")))?;
+                let v = v
+                    .trim()
+                    .parse()
+                    .map_err(|e: V::Err| err(format!("value: {e}")))?;
+                Ok((k, v))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl<K, V, const D: char, const S: char> std::fmt::Display for ConfMap<K, V, D, S>
+where
+    K: Eq + Hash + std::fmt::Display,
+    V: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (k, v)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{D}")?;
+            }
+            write!(f, "{k}{S}{v}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A filesystem path. Parsing only rejects empty strings; use [`ConfPath::normalize`] to expand
+/// `~` and resolve relative paths.
+#[derive(Serialize, Deserialize, Clone, Debug, From, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ConfPath(pub PathBuf);
+
+impl From<ConfPath> for PathBuf {
+    fn from(val: ConfPath) -> Self {
+        val.0
+    }
+}
+
+impl ConfPath {
+    /// Expand a leading `~` to the home directory (`HOME`, or `USERPROFILE` on Windows) and
+    /// resolve a relative path against the current working directory. `~user` forms are not
+    /// expanded. The path is not required to exist and symlinks are not resolved.
+    pub fn normalize(&self) -> std::io::Result<PathBuf> {
+        self.normalize_with_home(home_dir)
+    }
+
+    /// [Normalize](ConfPath::normalize) the path, expanding `~` to the directory from `home`.
+    pub(crate) fn normalize_with_home(
+        &self,
+        home: impl FnOnce() -> std::io::Result<PathBuf>,
+    ) -> std::io::Result<PathBuf> {
+        let mut components = self.0.components();
+        let path = match components.next() {
+            Some(Component::Normal(c)) if c == "~" => home()?.join(components.as_path()),
+            _ => self.0.clone(),
+        };
+        std::path::absolute(path)
+    }
+}
+
+/// Current user's home directory from the environment.
+pub(crate) fn home_dir() -> std::io::Result<PathBuf> {
+    ["HOME", "USERPROFILE"]
+        .into_iter()
+        .filter_map(std::env::var_os)
+        .find(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "home directory not set"))
+}
+
+/// Error parsing a [`ConfPath`].
+#[derive(Clone, Debug, Display, Error, PartialEq, Eq)]
+#[display("path is empty")]
+pub struct ParseConfPathError;
+
+impl FromStr for ConfPath {
+    type Err = ParseConfPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseConfPathError);
+        }
+        Ok(Self(s.into()))
+    }
+}
+
+impl std::fmt::Display for ConfPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+/// A [`Duration`] parsed from one or more numbers with units: `ns`, `us`, `ms`, `s`, `m`, `h` or
+/// `d`, e.g. `500ms`, `5m` or `1h30m`. A plain number is seconds. Displays the same way, largest
+/// unit first, so values round-trip.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, From, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct ConfDuration(pub Duration);
+
+impl From<ConfDuration> for Duration {
+    fn from(val: ConfDuration) -> Self {
+        val.0
+    }
+}
+
+/// Duration units as suffix and length in nanoseconds, largest first.
+const DURATION_UNITS: [(&str, u64); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// Error parsing a [`ConfDuration`].
+#[derive(Clone, Debug, Display, Error, PartialEq, Eq)]
+#[display(
+    "invalid duration: {val} (expected a number with unit ns, us, ms, s, m, h or d, e.g. 1h30m)"
+)]
+pub struct ParseConfDurationError {
+    pub val: String,
+}
+
+impl FromStr for ConfDuration {
+    type Err = ParseConfDurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseConfDurationError { val: s.into() };
+        let mut rest = s.trim();
+        if rest.is_empty() {
+            return Err(err());
+        }
+        if rest.bytes().all(|b| b.is_ascii_digit()) {
+            let secs = rest.parse().map_err(|_| err())?;
+            return Ok(Self(Duration::from_secs(secs)));
+        }
+        let mut nanos = 0u128;
+        while !rest.is_empty() {
+            let split = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let (num, tail) = rest.split_at(split);
+            let num: u64 = num.parse().map_err(|_| err())?;
+            let tail = tail.trim_start();
+            let split = tail
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(split);
+            let (_, unit) = DURATION_UNITS
+                .iter()
+                .find(|(suffix, _)| *suffix == unit)
+                .ok_or_else(err)?;
+            nanos = nanos.saturating_add(u128::from(num) * u128::from(*unit));
+            rest = tail.trim_start();
+        }
+        let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| err())?;
+        Ok(Self(Duration::new(secs, (nanos % 1_000_000_000) as u32)))
+    }
+}
+
+impl std::fmt::Display for ConfDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return write!(f, "0s");
+        }
+        for (suffix, unit) in DURATION_UNITS {
+            let count = nanos / u128::from(unit);
+            if count > 0 {
+                write!(f, "{count}{suffix}")?;
+                nanos %= u128::from(unit);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A secret value such as a password or API key. Displays and debug-prints as `****` so it
+/// doesn't leak into logs, while parsing and serialization use the real value.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Secret<V>(pub V);
+
+impl<V> Secret<V> {
+    /// The real value.
+    pub fn expose(&self) -> &V {
+        &self.0
+    }
+}
+
+impl<V: FromStr> FromStr for Secret<V> {
+    type Err = V::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+impl<V> std::fmt::Display for Secret<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("****")
+    }
+}
+
+impl<V> std::fmt::Debug for Secret<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(****)")
+    }
+}
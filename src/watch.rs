@@ -0,0 +1,80 @@
+use crate::{AnyConfEntry, Conf, ConfSource, Result};
+use std::collections::BTreeMap;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+
+/// A change to the value of a defined entry, delivered by [`Conf::watch`]. Values are in raw
+/// string form with the entry default applied, and `new` always parses into the entry's type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfChange {
+    /// Key of the entry which changed.
+    pub key: String,
+    /// Previous value, or `None` if there was none.
+    pub old: Option<String>,
+    /// New value, or `None` if it was removed.
+    pub new: Option<String>,
+}
+
+/// Callback for [`WatchableSource::watch`], called with the source's new values.
+pub type OnChange = Box<dyn Fn(&dyn ConfSource) + Send>;
+
+/// A [`ConfSource`] which can report when its values change, e.g. because its file was
+/// rewritten. See [`Conf::watch`].
+pub trait WatchableSource: ConfSource {
+    /// Start watching for changes in the background. Once the source's own values have been
+    /// updated, `on_change` is called with a view of them. Watching stops when the source is
+    /// dropped.
+    fn watch(&self, on_change: OnChange) -> Result;
+}
+
+impl<S: WatchableSource> Conf<S> {
+    /// Subscribe to changes to the values of defined entries, so a long-running service can
+    /// react without restarting. Lookups through this config see the new values as soon as the
+    /// source reloads them.
+    ///
+    /// Each changed value is parsed and validated before delivery. One which fails is delivered
+    /// as an error instead, and the previous good value stays the `old` value of the next
+    /// change. Source errors, such as a malformed file, are delivered as a single error.
+    /// Entries defined after this is called aren't watched, and values aren't
+    /// [interpolated](Conf::with_interpolation). Values of [secret](crate::ConfEntry::secret)
+    /// entries are included as-is.
+    pub fn watch(&self) -> Result<Receiver<Result<ConfChange>>> {
+        let entries: Vec<(String, Box<dyn AnyConfEntry>)> = self
+            .entries()
+            .map(|(key, option)| (key.clone(), option.boxed_clone()))
+            .collect();
+        let last: BTreeMap<String, Option<String>> = entries
+            .iter()
+            .map(|(key, option)| (key.clone(), option.raw(&self.source).ok().flatten()))
+            .collect();
+        let last = Mutex::new(last);
+        let (tx, rx) = channel();
+        self.source.watch(Box::new(move |source| {
+            let mut last = last.lock().unwrap_or_else(|e| e.into_inner());
+            for (key, option) in &entries {
+                let new = match option.raw(source) {
+                    Ok(new) => new,
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        return;
+                    }
+                };
+                let old = last.get(key).cloned().flatten();
+                if new == old {
+                    continue;
+                }
+                if let Some(Err(e)) = new.clone().map(|v| option.check(v)) {
+                    let _ = tx.send(Err(e));
+                    continue;
+                }
+                last.insert(key.clone(), new.clone());
+                let _ = tx.send(Ok(ConfChange {
+                    key: key.clone(),
+                    old,
+                    new,
+                }));
+            }
+        }))?;
+        Ok(rx)
+    }
+}
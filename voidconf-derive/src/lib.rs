@@ -0,0 +1,203 @@
+//! Derive macro for [`voidconf`](https://docs.rs/voidconf) configs. Use it through the `derive`
+//! feature of `voidconf`, which re-exports it as `voidconf::Conf`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, GenericArgument, LitStr, PathArguments, Type};
+
+/// Generate a `conf()` function building a `voidconf::Conf` with one entry per field, and a
+/// `from_conf(&Conf)` function reading the fields back from it.
+///
+/// Each field becomes an entry named after the field, whose value type is the field type, which
+/// must implement `ConfValue`. `Option<T>` fields are read with `Conf::get` and may be absent;
+/// other fields are read with `Conf::require`.
+///
+/// Struct attributes:
+///
+/// - `#[conf(name = "...")]`: the config name passed to `Conf::new`, `vcfg` by default.
+///
+/// Field attributes:
+///
+/// - `#[conf(name = "...")]`: the entry key, e.g. `"db.host"`, instead of the field name.
+/// - `#[conf(default = "...")]`: the default value in string form.
+/// - `#[conf(required)]`: mark the entry [required](https://docs.rs/voidconf/latest/voidconf/struct.ConfEntry.html#structfield.required).
+/// - `#[conf(describe = "...")]`: help text for the entry.
+/// - `#[conf(secret)]`: mark the entry secret, so errors don't include its value.
+///
+/// ```
+/// use voidconf::{Conf, ConfError, ConfSource, InMemorySource};
+///
+/// #[derive(Conf)]
+/// #[conf(name = "app")]
+/// struct Settings {
+///     #[conf(default = "8080")]
+///     port: u16,
+///     #[conf(name = "db.host", required)]
+///     db_host: String,
+///     log_level: Option<String>,
+/// }
+///
+/// let source = InMemorySource::new("app").set("APP_DB__HOST", "localhost");
+/// let conf = Conf::with_source("app", source).merge(Settings::conf());
+/// let settings = Settings::from_conf(&conf)?;
+/// assert_eq!(settings.port, 8080);
+/// assert_eq!(settings.db_host, "localhost");
+/// assert_eq!(settings.log_level, None);
+/// # Ok::<(), ConfError>(())
+/// ```
+#[proc_macro_derive(Conf, attributes(conf))]
+pub fn derive_conf(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Options from `#[conf(...)]` on a field.
+#[derive(Default)]
+struct FieldOpts {
+    name: Option<LitStr>,
+    default: Option<LitStr>,
+    describe: Option<LitStr>,
+    required: bool,
+    secret: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "#[derive(Conf)] does not support generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "#[derive(Conf)] requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                ident.span(),
+                "#[derive(Conf)] can only be used on structs",
+            ))
+        }
+    };
+
+    let mut conf_name = LitStr::new("vcfg", Span::call_site());
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("conf")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                conf_name = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unknown struct attribute, expected `name`"))
+            }
+        })?;
+    }
+
+    let mut entries = Vec::new();
+    let mut getters = Vec::new();
+    for field in fields {
+        let field_ident = field.ident.as_ref().expect("named field");
+        let opts = field_opts(field)?;
+        let key = opts
+            .name
+            .unwrap_or_else(|| LitStr::new(&field_ident.to_string(), field_ident.span()));
+        let (val_type, optional) = match option_inner(&field.ty) {
+            Some(inner) => (inner, true),
+            None => (&field.ty, false),
+        };
+        let mut entry = quote_spanned! {val_type.span()=>
+            ::voidconf::ConfEntry::<#val_type>::new(#key)
+        };
+        if let Some(default) = &opts.default {
+            entry = quote!(#entry.with_default(#default));
+        }
+        if opts.required {
+            entry = quote!(#entry.required());
+        }
+        if let Some(describe) = &opts.describe {
+            entry = quote!(#entry.describe(#describe));
+        }
+        if opts.secret {
+            entry = quote!(#entry.secret());
+        }
+        entries.push(entry);
+        getters.push(match optional {
+            true => quote!(#field_ident: conf.get::<#val_type>(#key)?),
+            false => quote!(#field_ident: conf.require::<#val_type>(#key)?),
+        });
+    }
+
+    Ok(quote! {
+        impl #ident {
+            /// Create a new config with an entry for each field.
+            pub fn conf() -> ::voidconf::Conf {
+                ::voidconf::Conf::new(#conf_name)
+                    #(.entry(#entries))*
+            }
+
+            /// Read each field from a config with the entries from [`Self::conf`].
+            pub fn from_conf<S: ::voidconf::ConfSource>(
+                conf: &::voidconf::Conf<S>,
+            ) -> ::core::result::Result<Self, ::voidconf::ConfError> {
+                ::core::result::Result::Ok(Self {
+                    #(#getters,)*
+                })
+            }
+        }
+    })
+}
+
+/// Parse the `#[conf(...)]` attributes on a field.
+fn field_opts(field: &syn::Field) -> syn::Result<FieldOpts> {
+    let mut opts = FieldOpts::default();
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("conf")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                opts.name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                opts.default = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("describe") {
+                opts.describe = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("required") {
+                opts.required = true;
+            } else if meta.path.is_ident("secret") {
+                opts.secret = true;
+            } else {
+                return Err(meta.error(
+                    "unknown field attribute, expected one of `name`, `default`, `describe`, \
+                     `required`, `secret`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(opts)
+}
+
+/// The `T` of an `Option<T>` type.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
@@ -0,0 +1,50 @@
+use voidconf::{Conf, ConfError, ConfSource, InMemorySource};
+
+#[derive(Conf, Debug, PartialEq)]
+#[conf(name = "app")]
+struct Settings {
+    #[conf(default = "8080", describe = "Port to listen on")]
+    port: u16,
+    #[conf(name = "db.host", required)]
+    db_host: String,
+    #[conf(secret)]
+    db_password: Option<String>,
+    debug: Option<bool>,
+}
+
+#[test]
+pub fn derive_conf_round_trip() {
+    let source = InMemorySource::new("app")
+        .set("APP_DB__HOST", "localhost")
+        .set("APP_DB_PASSWORD", "hunter2");
+    let conf = Conf::with_source("app", source).merge(Settings::conf());
+    assert_eq!(
+        conf.keys().collect::<Vec<_>>(),
+        ["db.host", "db_password", "debug", "port"]
+    );
+    conf.validate().unwrap();
+    assert_eq!(
+        Settings::from_conf(&conf).unwrap(),
+        Settings {
+            port: 8080,
+            db_host: "localhost".to_string(),
+            db_password: Some("hunter2".to_string()),
+            debug: None,
+        }
+    );
+    let info = conf.describe();
+    assert_eq!(info[3].description.as_deref(), Some("Port to listen on"));
+    assert!(info[0].required);
+}
+
+#[test]
+pub fn derive_conf_missing() {
+    let conf = Conf::with_source("app", InMemorySource::new("app")).merge(Settings::conf());
+    assert_eq!(
+        Settings::from_conf(&conf).unwrap_err(),
+        ConfError::ValNotFound {
+            key: "db.host".to_string(),
+            description: None
+        }
+    );
+}
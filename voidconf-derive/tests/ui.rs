@@ -0,0 +1,5 @@
+#[test]
+pub fn derive_conf_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
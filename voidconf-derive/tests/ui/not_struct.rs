@@ -0,0 +1,8 @@
+use voidconf::Conf;
+
+#[derive(Conf)]
+enum Settings {
+    Port(u16),
+}
+
+fn main() {}
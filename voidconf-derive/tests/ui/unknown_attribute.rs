@@ -0,0 +1,9 @@
+use voidconf::Conf;
+
+#[derive(Conf)]
+struct Settings {
+    #[conf(defualt = "8080")]
+    port: u16,
+}
+
+fn main() {}
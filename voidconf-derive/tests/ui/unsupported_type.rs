@@ -0,0 +1,11 @@
+use voidconf::Conf;
+
+struct Endpoint;
+
+#[derive(Conf)]
+struct Settings {
+    port: u16,
+    endpoint: Endpoint,
+}
+
+fn main() {}